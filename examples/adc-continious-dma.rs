@@ -10,11 +10,11 @@ use crate::hal::{
         AdcClaim, ClockSource, Temperature, Vref,
     },
     delay::SYSTDelayExt,
-    dma::{config::DmaConfig, stream::DMAExt, TransferExt},
+    dma::{config::DmaConfig, stream::DMAExt, DMAError, TransferExt},
     gpio::GpioExt,
     pwr::PwrExt,
     rcc::{Config, RccExt},
-    signature::{VrefCal, VDDA_CALIB},
+    signature::VDDA_CALIB,
     stm32::Peripherals,
 };
 use stm32g4xx_hal as hal;
@@ -72,7 +72,14 @@ fn main() -> ! {
 
     loop {
         let mut b = [0_u16; 6];
-        let r = transfer.read_exact(&mut b);
+        let r = match transfer.read_exact(&mut b) {
+            Ok(r) => r,
+            Err(DMAError::Overflow) => {
+                info!("Samples were overwritten before they were read, skipping them");
+                continue;
+            }
+            Err(e) => panic!("DMA error: {:?}", e),
+        };
         assert!(
             !transfer.get_overrun_flag(),
             "DMA did not have time to read the ADC value before ADC was done with a new conversion"
@@ -81,7 +88,8 @@ fn main() -> ! {
         info!("read: {}", r);
         assert!(r == b.len());
 
-        let vdda = VDDA_CALIB * VrefCal::get().read() as u32 / ((b[2] + b[5]) / 2) as u32;
+        let vdda = Vref::sample_to_vdda((b[2] + b[5]) / 2, config::Resolution::Twelve)
+            .unwrap_or(VDDA_CALIB);
 
         info!("vdda: {}mV", vdda);
 
@@ -3,14 +3,14 @@
 
 use crate::hal::{
     adc::{
-        config::{Continuous, Resolution, SampleTime, Sequence},
-        AdcClaim, ClockSource, Temperature, Vref,
+        config::{Continuous, Resolution, SampleTime},
+        AdcClaim, ClockSource, SequenceBuilder, Temperature, Vref,
     },
     delay::SYSTDelayExt,
     gpio::GpioExt,
     pwr::PwrExt,
     rcc::{Config, RccExt},
-    signature::{VrefCal, VDDA_CALIB},
+    signature::VDDA_CALIB,
     stm32::Peripherals,
 };
 use stm32g4xx_hal as hal;
@@ -51,10 +51,12 @@ fn main() -> ! {
     adc.enable_vref(&dp.ADC12_COMMON);
     adc.set_auto_delay(true);
     adc.set_continuous(Continuous::Continuous);
-    adc.reset_sequence();
-    adc.configure_channel(&pa0, Sequence::One, SampleTime::Cycles_640_5);
-    adc.configure_channel(&Vref, Sequence::Two, SampleTime::Cycles_640_5);
-    adc.configure_channel(&Temperature, Sequence::Three, SampleTime::Cycles_640_5);
+    let sequence = SequenceBuilder::new(&pa0, SampleTime::Cycles_640_5)
+        .add(&Vref, SampleTime::Cycles_640_5)
+        .unwrap()
+        .add(&Temperature, SampleTime::Cycles_640_5)
+        .unwrap();
+    adc.set_sequence(&sequence);
     let adc = adc.enable();
 
     info!("Enter Loop");
@@ -68,7 +70,7 @@ fn main() -> ! {
         adc = adc.wait_for_conversion_sequence().unwrap_active();
         let vref_sample = adc.current_sample();
         let millivolts = Vref::sample_to_millivolts(vref_sample);
-        let vdda = VDDA_CALIB * VrefCal::get().read() as u32 / vref_sample as u32;
+        let vdda = Vref::sample_to_vdda(vref_sample, Resolution::Twelve).unwrap_or(VDDA_CALIB);
         info!("vref: {}mV", millivolts);
 
         adc = adc.wait_for_conversion_sequence().unwrap_active();
@@ -0,0 +1,118 @@
+//! ADC conversions on DMA1 and UART transmission on DMA2 running at the same time
+//!
+//! Both transfers use the first stream of their DMA, these are routed through different
+//! DMAMUX channels: 0 for DMA1 and 8 (6 on category 2 devices) for DMA2.
+
+#![no_std]
+#![no_main]
+
+mod utils;
+
+use crate::hal::{
+    adc::{
+        config::{Continuous, Dma as AdcDma, SampleTime, Sequence},
+        AdcClaim, ClockSource, Temperature,
+    },
+    delay::SYSTDelayExt,
+    dma::{config::DmaConfig, stream::DMAExt, DMAError, TransferExt},
+    gpio::GpioExt,
+    pwr::PwrExt,
+    rcc::{Config, RccExt},
+    serial::{FullConfig, SerialExt},
+    stm32::Peripherals,
+    time::RateExtU32,
+};
+use stm32g4xx_hal as hal;
+
+use cortex_m_rt::entry;
+use utils::logger::info;
+
+#[entry]
+fn main() -> ! {
+    utils::logger::init();
+
+    info!("start");
+
+    let dp = Peripherals::take().unwrap();
+    let cp = cortex_m::Peripherals::take().expect("cannot take core peripherals");
+
+    info!("rcc");
+    let rcc = dp.RCC.constrain();
+    let pwr = dp.PWR.constrain().freeze();
+    let mut rcc = rcc.freeze(Config::hsi(), pwr);
+
+    let dma1 = dp.DMA1.split(&rcc);
+    let dma2 = dp.DMA2.split(&rcc);
+    let config = DmaConfig::default()
+        .transfer_complete_interrupt(false)
+        .circular_buffer(false)
+        .memory_increment(true);
+
+    info!("Setup Gpio");
+    let gpioa = dp.GPIOA.split(&mut rcc);
+    let pa0 = gpioa.pa0.into_analog();
+    let tx = gpioa.pa2.into_alternate();
+    let rx = gpioa.pa3.into_alternate();
+
+    info!("Setup Adc1");
+    let mut delay = cp.SYST.delay(&rcc.clocks);
+    let mut adc = dp
+        .ADC1
+        .claim(ClockSource::SystemClock, &rcc, &mut delay, true);
+
+    adc.enable_temperature(&dp.ADC12_COMMON);
+    adc.set_continuous(Continuous::Continuous);
+    adc.reset_sequence();
+    adc.configure_channel(&pa0, Sequence::One, SampleTime::Cycles_640_5);
+    adc.configure_channel(&Temperature, Sequence::Two, SampleTime::Cycles_640_5);
+
+    info!("Setup Usart2");
+    let usart = dp
+        .USART2
+        .usart(
+            tx,
+            rx,
+            FullConfig::default().baudrate(115200.bps()),
+            &mut rcc,
+        )
+        .unwrap();
+    let (tx, _rx) = usart.split();
+
+    info!("Setup DMA");
+    // ADC1 on DMA1 channel 1
+    let adc_buffer = cortex_m::singleton!(: [u16; 16] = [0; 16]).unwrap();
+    let mut adc_transfer = dma1.0.into_circ_peripheral_to_memory_transfer(
+        adc.enable_dma(AdcDma::Continuous),
+        &mut adc_buffer[..],
+        config.circular_buffer(true),
+    );
+
+    // USART2 TX on DMA2 channel 1
+    let tx_buffer = cortex_m::singleton!(: [u8; 17] = *b"Hello from DMA2\r\n").unwrap();
+    let mut tx_transfer =
+        dma2.0
+            .into_memory_to_peripheral_transfer(tx.enable_dma(), &mut tx_buffer[..], config);
+
+    adc_transfer.start(|adc| adc.start_conversion());
+    tx_transfer.start(|_tx| {});
+
+    loop {
+        let mut b = [0_u16; 2];
+        match adc_transfer.read_exact(&mut b) {
+            Ok(_) => {}
+            Err(DMAError::Overflow) => {
+                info!("Samples were overwritten before they were read, skipping them");
+                continue;
+            }
+            Err(e) => panic!("DMA error: {:?}", e),
+        }
+
+        info!("pa0: {}, temperature: {}", b[0], b[1]);
+
+        // Both DMAs keep running, if the request lines were routed to the wrong DMAMUX
+        // channels one of them would stall here
+        if tx_transfer.get_transfer_complete_flag() {
+            tx_transfer.restart(|_tx| {});
+        }
+    }
+}
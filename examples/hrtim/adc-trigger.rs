@@ -20,7 +20,7 @@ fn main() -> ! {
 
     use hal::{
         adc::{
-            config::{Continuous, Dma as AdcDma, SampleTime, Sequence},
+            config::{ClockMode, Continuous, Dma as AdcDma, SampleTime, Sequence},
             AdcClaim, ClockSource, Temperature, Vref,
         },
         delay::SYSTDelayExt,
@@ -115,6 +115,8 @@ fn main() -> ! {
     let mut adc = dp
         .ADC1
         .claim(ClockSource::SystemClock, &rcc, &mut delay, true);
+    // The ADC clock may not exceed 60MHz
+    adc.set_clock_mode(ClockMode::Synchronous_Div_4);
 
     adc.set_external_trigger((
         adc::config::TriggerMode::RisingEdge,
@@ -144,7 +146,14 @@ fn main() -> ! {
 
     loop {
         let mut b = [0_u16; 4];
-        let r = transfer.read_exact(&mut b);
+        let r = match transfer.read_exact(&mut b) {
+            Ok(r) => r,
+            Err(dma::DMAError::Overflow) => {
+                info!("Samples were overwritten before they were read, skipping them");
+                continue;
+            }
+            Err(e) => panic!("DMA error: {:?}", e),
+        };
 
         info!("read: {}", r);
         assert!(r == b.len());
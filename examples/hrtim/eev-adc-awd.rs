@@ -0,0 +1,140 @@
+#![no_std]
+#![no_main]
+
+/// Example showcasing the use of the HRTIM peripheral together with the analog watchdog of an ADC to implement a cycle by cycle
+/// over voltage limit. Once the voltage on PA1 goes above the watchdog threshold, the output is set low thus limiting the pulse width.
+///
+/// Note that the analog watchdog flag is sticky, it is cleared by software at every period of the timer in this example.
+
+#[path = "../utils/mod.rs"]
+mod utils;
+
+use cortex_m_rt::entry;
+
+use defmt_rtt as _; // global logger
+use panic_probe as _;
+
+use utils::logger::info;
+
+#[entry]
+fn main() -> ! {
+    use hal::adc::{
+        config::{ClockMode, Continuous, SampleTime, Sequence},
+        AdcClaim, AnalogWatchdog, ClockSource,
+    };
+    use hal::delay::SYSTDelayExt;
+    use hal::gpio::gpioa::PA8;
+    use hal::gpio::Alternate;
+    use hal::gpio::AF13;
+    use hal::hrtim::compare_register::HrCompareRegister;
+    use hal::hrtim::external_event::{self, AdcAwdSource};
+    use hal::hrtim::timer::HrTimer;
+    use hal::hrtim::timer_eev_cfg::EevCfgs;
+    use hal::hrtim::HrPwmAdvExt;
+    use hal::hrtim::Pscl4;
+    use hal::hrtim::{control::HrControltExt, output::HrOutput};
+    use hal::prelude::*;
+    use hal::pwm;
+    use hal::pwr::PwrExt;
+    use hal::rcc;
+    use hal::stm32;
+    use stm32g4xx_hal as hal;
+
+    let dp = stm32::Peripherals::take().expect("cannot take peripherals");
+    let cp = cortex_m::Peripherals::take().expect("cannot take core peripherals");
+    // Set system frequency to 16MHz * 75/4/2 = 150MHz
+    // This would lead to HrTim running at 150MHz * 32 = 4.8GHz...
+    let pwr = dp.PWR.constrain().freeze();
+
+    let mut rcc = dp.RCC.freeze(
+        rcc::Config::pll().pll_cfg(rcc::PllConfig {
+            mux: rcc::PLLSrc::HSI,
+            n: rcc::PllNMul::MUL_75,
+            m: rcc::PllMDiv::DIV_4,
+            r: Some(rcc::PllRDiv::DIV_2),
+            ..Default::default()
+        }),
+        pwr,
+    );
+
+    let mut delay = cp.SYST.delay(&rcc.clocks);
+
+    let gpioa = dp.GPIOA.split(&mut rcc);
+    let pa1 = gpioa.pa1.into_analog();
+
+    let mut adc = dp
+        .ADC2
+        .claim(ClockSource::SystemClock, &rcc, &mut delay, true);
+    // The ADC clock may not exceed 60MHz
+    adc.set_clock_mode(ClockMode::Synchronous_Div_4);
+    adc.set_continuous(Continuous::Continuous);
+    adc.reset_sequence();
+    adc.configure_channel(&pa1, Sequence::One, SampleTime::Cycles_12_5);
+
+    // Analog watchdog 1 guarding channel 2(PA1) with a high threshold of 3/4 of the full scale
+    adc.set_watchdog1(Some(2), 0, 3 * 4096 / 4);
+
+    let (mut hr_control, _flt_inputs, eev_inputs) =
+        dp.HRTIM_COMMON.hr_control(&mut rcc).wait_for_calibration();
+
+    let eev_input4 = eev_inputs
+        .eev_input4
+        .bind(AdcAwdSource::<stm32::ADC2, 1>::new(&adc))
+        .edge_or_polarity(external_event::EdgeOrPolarity::Polarity(
+            pwm::Polarity::ActiveHigh,
+        ))
+        .finalize(&mut hr_control);
+
+    let mut hr_control = hr_control.constrain();
+
+    let adc = adc.enable();
+    let mut adc = adc.start_conversion();
+
+    // ...with a prescaler of 4 this gives us a HrTimer with a tick rate of 1.2GHz
+    // With max the max period set, this would be 1.2GHz/2^16 ~= 18kHz...
+    let prescaler = Pscl4;
+
+    let pin_a: PA8<Alternate<AF13>> = gpioa.pa8.into_alternate();
+
+    //        .               .  *            .
+    //        .  33%          .  *            .               .               .
+    //        .-----.         .--*            .-----.         .-----.         .-----
+    //out1    |     |         |  |            |     |         |     |         |
+    //        |     |         |  *            |     |         |     |         |
+    //   ------     -----------  --------------     -----------     -----------
+    //        .               .  *            .               .               .
+    //        .               .  *            .               .               .
+    //        .               .  *--------*   .               .               .
+    //awd1    .               .  |        |   .               .               .
+    //        .               .  |        |   .               .               .
+    //   -------------------------        ------------------------------------------
+    //        .               .  *            .               .               .
+    //        .               .  *            .               .               .
+    let (mut timer, (mut cr1, _cr2, _cr3, _cr4), mut out1) = dp
+        .HRTIM_TIMA
+        .pwm_advanced(pin_a, &mut rcc)
+        .prescaler(prescaler)
+        .eev_cfg(EevCfgs::default())
+        .period(0xFFFF)
+        .finalize(&mut hr_control);
+
+    out1.enable_rst_event(&cr1); // Set low on compare match with cr1
+    out1.enable_rst_event(&eev_input4);
+    out1.enable_set_event(&timer); // Set high at new period
+    cr1.set_duty(timer.get_period() / 3);
+
+    out1.enable();
+    timer.start(&mut hr_control);
+
+    info!("Started");
+
+    loop {
+        // Wait for the next period, then re-arm the watchdog
+        //
+        // SAFETY: Only reading the status register
+        let tima = unsafe { &*stm32::HRTIM_TIMA::ptr() };
+        while tima.timaisr.read().rep().bit_is_clear() {}
+        timer.clear_repetition_interrupt();
+        adc.clear_watchdog_flag(AnalogWatchdog::Awd1);
+    }
+}
@@ -50,6 +50,9 @@ fn main() -> ! {
         pwr,
     );
 
+    // The resulting HRTIM tick rate (with Pscl1) is known by rcc
+    assert_eq!(rcc.clocks.hrtim_clk.raw(), 4_800_000_000);
+
     let gpioa = dp.GPIOA.split(&mut rcc);
     let gpiob = dp.GPIOB.split(&mut rcc);
 
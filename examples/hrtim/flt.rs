@@ -2,18 +2,44 @@
 #![no_main]
 
 /// Example showcasing the use of the HRTIM peripheral together with a comparator to implement a current fault.
-/// Once the digital input goes high, the output is forced low and put into a fault state.
+/// Once the digital input goes high, the output is forced low and put into a fault state. The fault
+/// interrupt notifies the main loop which clears the fault.
 
 #[path = "../utils/mod.rs"]
 mod utils;
 
+use core::cell::RefCell;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use cortex_m::interrupt::Mutex;
 use cortex_m_rt::entry;
 
 use defmt_rtt as _; // global logger
 use panic_probe as _;
 
+use stm32g4xx_hal as hal;
+
+use hal::hrtim::fault::FltMonitor3;
+use hal::pwm::FaultMonitor;
+use hal::stm32::{interrupt, Interrupt};
 use utils::logger::info;
 
+static G_FAULT_MONITOR: Mutex<RefCell<Option<FltMonitor3>>> = Mutex::new(RefCell::new(None));
+static G_FAULT_OCCURRED: AtomicBool = AtomicBool::new(false);
+
+#[interrupt]
+fn HRTIM_TIM_FLT_IRQN() {
+    cortex_m::interrupt::free(|cs| {
+        if let Some(fault) = G_FAULT_MONITOR.borrow(cs).borrow_mut().as_mut() {
+            if fault.is_fault_active() {
+                // Stop listening until the main loop has cleared the fault
+                fault.unlisten();
+                G_FAULT_OCCURRED.store(true, Ordering::Relaxed);
+            }
+        }
+    });
+}
+
 #[entry]
 fn main() -> ! {
     use hal::gpio::gpioa::PA8;
@@ -26,12 +52,10 @@ fn main() -> ! {
     use hal::hrtim::Pscl4;
     use hal::hrtim::{control::HrControltExt, output::HrOutput};
     use hal::prelude::*;
-    use hal::pwm::FaultMonitor;
     use hal::pwr::PwrExt;
     use hal::rcc;
     use hal::stm32;
     use hal::time::ExtU32;
-    use stm32g4xx_hal as hal;
 
     let dp = stm32::Peripherals::take().expect("cannot take peripherals");
     let cp = stm32::CorePeripherals::take().expect("cannot take core");
@@ -109,13 +133,25 @@ fn main() -> ! {
 
     info!("Started");
 
+    let mut fault_3 = hr_control.fault_3;
+    fault_3.listen();
+    cortex_m::interrupt::free(|cs| *G_FAULT_MONITOR.borrow(cs).borrow_mut() = Some(fault_3));
+    unsafe {
+        cortex_m::peripheral::NVIC::unmask(Interrupt::HRTIM_TIM_FLT_IRQN);
+    }
+
     loop {
         for _ in 0..5 {
             delay.delay(500_u32.millis());
             info!("State: {}", out1.get_state());
         }
-        if hr_control.fault_3.is_fault_active() {
-            hr_control.fault_3.clear_fault(); // Clear fault every 5s
+        if G_FAULT_OCCURRED.swap(false, Ordering::Relaxed) {
+            cortex_m::interrupt::free(|cs| {
+                let mut fault = G_FAULT_MONITOR.borrow(cs).borrow_mut();
+                let fault = fault.as_mut().unwrap();
+                fault.clear_fault(); // Clear fault every 5s
+                fault.listen();
+            });
             out1.enable();
             info!("failt cleared, and output reenabled");
         }
@@ -0,0 +1,74 @@
+//! Share one I2C bus between two drivers
+//!
+//! The drivers are written against the embedded-hal 1.0 `I2c` trait. Each one gets its own
+//! `&RefCell` handle to the bus, which is what `embedded_hal_bus::i2c::RefCellDevice` does.
+#![deny(warnings)]
+#![deny(unsafe_code)]
+#![no_main]
+#![no_std]
+
+use core::cell::RefCell;
+
+use embedded_hal_one::i2c::I2c;
+use hal::i2c::Config;
+use hal::prelude::*;
+use hal::stm32;
+use hal::time::RateExtU32;
+use stm32g4xx_hal as hal;
+
+use cortex_m_rt::entry;
+use log::info;
+
+#[macro_use]
+mod utils;
+
+/// Device on a shared bus, only borrows the bus during a transaction
+struct SharedDevice<'a, BUS> {
+    bus: &'a RefCell<BUS>,
+    addr: u8,
+}
+
+impl<'a, BUS: I2c> SharedDevice<'a, BUS> {
+    fn read_register(&mut self, register: u8) -> Result<u8, BUS::Error> {
+        let mut value = [0];
+        self.bus
+            .borrow_mut()
+            .write_read(self.addr, &[register], &mut value)?;
+        Ok(value[0])
+    }
+}
+
+#[entry]
+fn main() -> ! {
+    utils::logger::init();
+
+    let dp = stm32::Peripherals::take().expect("cannot take peripherals");
+    let mut rcc = dp.RCC.constrain();
+    let gpiob = dp.GPIOB.split(&mut rcc);
+
+    let sda = gpiob.pb9.into_alternate_open_drain();
+    let scl = gpiob.pb8.into_alternate_open_drain();
+
+    let bus = RefCell::new(dp.I2C1.i2c(sda, scl, Config::new(400.kHz()), &mut rcc));
+
+    // SSD1306 display and MPU6050 IMU
+    let mut display = SharedDevice {
+        bus: &bus,
+        addr: 0x3c,
+    };
+    let mut imu = SharedDevice {
+        bus: &bus,
+        addr: 0x68,
+    };
+
+    loop {
+        match display.read_register(0x00) {
+            Ok(status) => info!("display status: {:#x}", status),
+            Err(err) => info!("display error: {:?}", err),
+        }
+        match imu.read_register(0x75) {
+            Ok(who_am_i) => info!("imu WHO_AM_I: {:#x}", who_am_i),
+            Err(err) => info!("imu error: {:?}", err),
+        }
+    }
+}
@@ -0,0 +1,66 @@
+//! Opamp in programmable gain mode sampled by the ADC without using an output pin
+
+#![no_std]
+#![no_main]
+
+use stm32g4xx_hal::adc::config::SampleTime;
+use stm32g4xx_hal::adc::AdcClaim;
+use stm32g4xx_hal::adc::ClockSource;
+use stm32g4xx_hal::gpio::gpioa::*;
+use stm32g4xx_hal::gpio::Analog;
+use stm32g4xx_hal::opamp::opamp1::IntoPga as _;
+use stm32g4xx_hal::opamp::NonInvertingGain;
+use stm32g4xx_hal::opamp::PgaModeInternal;
+use stm32g4xx_hal::prelude::*;
+use stm32g4xx_hal::pwr::PwrExt;
+
+use utils::logger::info;
+
+#[macro_use]
+mod utils;
+
+#[cortex_m_rt::entry]
+fn main() -> ! {
+    utils::logger::init();
+
+    // take peripherals
+    let dp = stm32g4xx_hal::stm32::Peripherals::take().unwrap();
+    let cp = cortex_m::Peripherals::take().expect("cannot take core peripherals");
+
+    // setup clock and power
+    let pwr = dp.PWR.constrain().freeze();
+    let config = stm32g4xx_hal::rcc::Config::hsi();
+    let mut rcc = dp.RCC.freeze(config, pwr);
+
+    // split gpio
+    let gpioa = dp.GPIOA.split(&mut rcc);
+    let pa1 = gpioa.pa1.into_analog();
+
+    // setup opamps
+    let (opamp1, ..) = dp.OPAMP.split(&mut rcc);
+
+    // Configure opamp1 with pa1 as non-inverting input and set gain to x16
+    let (opamp1, _none) = opamp1
+        .pga(
+            pa1,
+            PgaModeInternal::gain(NonInvertingGain::Gain16),
+            Option::<PA2<Analog>>::None, // Do not route output to any external pin, use internal AD instead
+        )
+        .into_internal_output();
+
+    let mut delay = cp.SYST.delay(&rcc.clocks);
+    let mut adc = dp
+        .ADC1
+        .claim(ClockSource::SystemClock, &rcc, &mut delay, true)
+        .enable();
+
+    loop {
+        // The output of opamp1 is internally connected to channel 13 of ADC1
+        let sample = adc.convert(&opamp1, SampleTime::Cycles_640_5);
+
+        let millivolts = adc.sample_to_millivolts(sample);
+        info!("opamp1 thus 16x pa1: {}mV", millivolts);
+
+        delay.delay_ms(100);
+    }
+}
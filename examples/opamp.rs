@@ -60,11 +60,13 @@ fn main() -> ! {
     );
 
     // Configure op with pa7 as non-inverting input and set gain to x4
-    let opamp2 = opamp2.pga(
-        pa7,
-        PgaModeInternal::gain(NonInvertingGain::Gain4),
-        Option::<PA6<Analog>>::None, // Do not route output to any external pin, use internal AD instead
-    );
+    let (opamp2, _none) = opamp2
+        .pga(
+            pa7,
+            PgaModeInternal::gain(NonInvertingGain::Gain4),
+            Option::<PA6<Analog>>::None, // Do not route output to any external pin, use internal AD instead
+        )
+        .into_internal_output();
 
     let mut delay = cp.SYST.delay(&rcc.clocks);
     let mut adc = dp
@@ -87,7 +89,7 @@ fn main() -> ! {
     #[allow(unreachable_code)]
     {
         let (_opamp1, _pa1, _mode) = _opamp1.disable();
-        let (_opamp2, _pa7, _mode) = opamp2.disable();
+        let (_opamp2, _mode) = opamp2.disable();
 
         loop {
             delay.delay_ms(100);
@@ -5,7 +5,7 @@
 
 extern crate cortex_m_rt as rt;
 
-use hal::dma::{config::DmaConfig, stream::DMAExt, TransferExt};
+use hal::dma::{config::DmaConfig, stream::DMAExt, DMAError, TransferExt};
 use hal::prelude::*;
 use hal::pwr::PwrExt;
 use hal::serial::*;
@@ -78,7 +78,14 @@ fn main() -> ! {
 
         let mut data = [0; 256];
         loop {
-            let data = transfer.read_available(&mut data);
+            let data = match transfer.read_available(&mut data) {
+                Ok(data) => data,
+                Err(DMAError::Overflow) => {
+                    info!("Received data was overwritten before it was read");
+                    continue;
+                }
+                Err(e) => panic!("DMA error: {:?}", e),
+            };
             if data.is_empty() {
                 break;
             }
@@ -3,29 +3,37 @@
 
 #![deny(missing_docs)]
 
-/*
-    Currently unused but this is the formula for using temperature calibration:
-    Temperature in °C = ( ( (TS_CAL2_TEMP-TS_CAL1_TEMP) / (TS_CAL2-TS_CAL1) ) * (TS_DATA-TS_CAL1) ) + 30°C
-*/
-
 //use crate::dma::traits::PeriAddress;
 pub use crate::time::U32Ext as _;
 use crate::{
-    dma::{mux::DmaMuxResources, traits::TargetAddress, PeripheralToMemory},
+    dma::{
+        mux::DmaMuxResources,
+        traits::{sealed::Sealed, BufferWord, DmaWord, TargetAddress},
+        PeripheralToMemory,
+    },
     gpio::*,
     opamp,
-    rcc::{Enable, Rcc, Reset},
-    signature::{VtempCal130, VtempCal30, VDDA_CALIB},
+    rcc::{Clocks, Enable, Rcc, Reset},
+    signature::{VrefCal, VtempCal130, VtempCal30, VDDA_CALIB},
     stm32,
+    time::Hertz,
 };
+#[cfg(feature = "async")]
+use core::cell::RefCell;
 use core::fmt;
+#[cfg(feature = "async")]
+use core::future::poll_fn;
 use core::marker::PhantomData;
+#[cfg(feature = "async")]
+use core::task::{Poll, Waker};
+#[cfg(feature = "async")]
+use cortex_m::interrupt::Mutex;
 use embedded_hal::{
     adc::{Channel, OneShot},
     blocking::delay::DelayUs,
 };
 
-use self::config::ExternalTrigger12;
+use self::config::{ExternalTrigger12, InjectedTrigger12};
 
 #[cfg(any(
     feature = "stm32g471",
@@ -36,7 +44,10 @@ use self::config::ExternalTrigger12;
     feature = "stm32g491",
     feature = "stm32g4a1",
 ))]
-use self::config::ExternalTrigger345;
+use self::config::{ExternalTrigger345, InjectedTrigger345};
+
+/// Maximum ADC clock frequency according to the datasheet
+const MAX_CLOCK_FREQUENCY: u32 = 60_000_000;
 
 /// Vref internal signal, used for calibration
 pub struct Vref;
@@ -47,15 +58,140 @@ impl Vref {
         let mx_s = resolution.to_max_sample();
         ((u32::from(sample) * vdda) / mx_s) as u16
     }
-    /// Converts a sample value to millivolts using calibrated VDDA and configured resolution
+    /// Converts a 12 bit sample value to millivolts assuming VDDA is at the factory
+    /// calibration voltage, see [`VDDA_CALIB`](crate::signature::VDDA_CALIB)
     #[inline(always)]
     pub fn sample_to_millivolts(sample: u16) -> u16 {
         Self::sample_to_millivolts_ext(sample, VDDA_CALIB, config::Resolution::Twelve)
     }
+
+    /// Computes the actual VDDA, in millivolts, from a sample of the `Vref` channel using the
+    /// factory calibration value [`VrefCal`](crate::signature::VrefCal)
+    ///
+    /// The result can be passed to `Adc::set_reference_voltage` so that `sample_to_millivolts`
+    /// of the adc uses the measured VDDA, and to the `Temperature` conversion functions.
+    /// Returns `None` if `sample` is 0, e.g. because the `Vref` channel is not enabled.
+    #[inline(always)]
+    pub fn sample_to_vdda(sample: u16, resolution: config::Resolution) -> Option<u32> {
+        // VREFINT_CAL was captured at 12 bits
+        let sample_12b = u32::from(Temperature::to_12b(sample, resolution));
+        (VDDA_CALIB * u32::from(VrefCal::get().read())).checked_div(sample_12b)
+    }
 }
 
 /// Vbat internal signal, used for monitoring the battery (if used)
+///
+/// The channel has to be enabled using `enable_vbat`. VBAT is connected through an internal 1/3
+/// divider which draws current from the battery, so disable the channel again between
+/// measurements.
 pub struct Vbat;
+impl Vbat {
+    /// Converts a sample value to the VBAT voltage in millivolts using `vdda` and `resolution`,
+    /// accounting for the internal 1/3 divider
+    #[inline(always)]
+    pub fn sample_to_millivolts_ext(sample: u16, vdda: u32, resolution: config::Resolution) -> u16 {
+        let mx_s = resolution.to_max_sample();
+        ((u32::from(sample) * vdda * 3) / mx_s) as u16
+    }
+    /// Converts a 12 bit sample value to the VBAT voltage in millivolts using the factory
+    /// calibration VDDA, accounting for the internal 1/3 divider
+    #[inline(always)]
+    pub fn sample_to_millivolts(sample: u16) -> u16 {
+        Self::sample_to_millivolts_ext(sample, VDDA_CALIB, config::Resolution::Twelve)
+    }
+}
+
+/// Analog watchdog
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum AnalogWatchdog {
+    /// Analog watchdog 1, 12 bit thresholds on one or all channels
+    Awd1,
+    /// Analog watchdog 2, 8 bit thresholds on a set of channels
+    Awd2,
+    /// Analog watchdog 3, 8 bit thresholds on a set of channels
+    Awd3,
+}
+
+/// Gain compensation coefficient which scales `measured` to `expected`, see
+/// `DynamicAdc::set_gain_compensation`
+///
+/// The coefficient is in 2.12 fixed point format, `4096` is a gain of 1.0. Returns `None` if
+/// `measured` is 0 or the gain is 4.0 or more, which can not be compensated.
+pub fn gain_compensation_coefficient(measured: u16, expected: u16) -> Option<u16> {
+    let measured = u32::from(measured);
+    let coeff = (u32::from(expected) * 4096 + measured / 2).checked_div(measured)?;
+    if coeff < (1 << 14) {
+        Some(coeff as u16)
+    } else {
+        None
+    }
+}
+
+/// ADC interrupt events
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Event {
+    /// The adc is ready to start conversions (ADRDY)
+    Ready,
+    /// End of the sampling phase of a regular conversion (EOSMP)
+    EndOfSampling,
+    /// End of a regular conversion (EOC)
+    EndOfConversion,
+    /// End of the regular sequence (EOS)
+    EndOfSequence,
+    /// Overrun, a conversion finished before the previous result was read (OVR)
+    Overrun,
+    /// End of an injected conversion (JEOC)
+    InjectedEndOfConversion,
+    /// End of the injected sequence (JEOS)
+    InjectedEndOfSequence,
+    /// The analog watchdog detected a sample outside of its thresholds (AWDx)
+    AnalogWatchdog(AnalogWatchdog),
+}
+
+impl Event {
+    /// Bit of the event in both ISR and IER
+    fn mask(self) -> u32 {
+        let bit = match self {
+            Event::Ready => 0,
+            Event::EndOfSampling => 1,
+            Event::EndOfConversion => 2,
+            Event::EndOfSequence => 3,
+            Event::Overrun => 4,
+            Event::InjectedEndOfConversion => 5,
+            Event::InjectedEndOfSequence => 6,
+            Event::AnalogWatchdog(AnalogWatchdog::Awd1) => 7,
+            Event::AnalogWatchdog(AnalogWatchdog::Awd2) => 8,
+            Event::AnalogWatchdog(AnalogWatchdog::Awd3) => 9,
+        };
+        1 << bit
+    }
+}
+
+/// Offset compensation slot, each slot subtracts or adds an offset to one channel
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum OffsetSlot {
+    /// OFR1
+    Offset1,
+    /// OFR2
+    Offset2,
+    /// OFR3
+    Offset3,
+    /// OFR4
+    Offset4,
+}
+
+/// Direction of the offset compensation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum OffsetSign {
+    /// Subtract the offset from the conversion result
+    Negative,
+    /// Add the offset to the conversion result
+    Positive,
+}
 
 /// Core temperature internal signal
 pub struct Temperature;
@@ -133,6 +269,140 @@ impl Temperature {
     }
 }
 
+/// Error returned by [`SequenceBuilder`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum SequenceError {
+    /// The sequence already holds the maximum of 16 conversions
+    Full,
+    /// The channel is already in the sequence with a different sample time, the sample time is
+    /// per channel and not per conversion
+    ConflictingSampleTime,
+}
+
+/// Regular conversion sequence which is programmed in one go using `DynamicAdc::set_sequence`
+///
+/// Channels are converted in the order they are added, so the sequence can not have gaps or
+/// positions that are configured twice. The same channel may be added more than once.
+///
+/// ```ignore
+/// let sequence = SequenceBuilder::new(&pa0, SampleTime::Cycles_640_5)
+///     .add(&Temperature, SampleTime::Cycles_640_5)?
+///     .add(&Vref, SampleTime::Cycles_640_5)?;
+/// adc.set_sequence(&sequence);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct SequenceBuilder<ADC> {
+    channels: [u8; 16],
+    sample_times: [config::SampleTime; 16],
+    len: u8,
+    _adc: PhantomData<ADC>,
+}
+
+impl<ADC> SequenceBuilder<ADC> {
+    /// Creates a sequence starting with `channel`
+    pub fn new<CHANNEL>(_channel: &CHANNEL, sample_time: config::SampleTime) -> Self
+    where
+        CHANNEL: Channel<ADC, ID = u8>,
+    {
+        let mut channels = [0; 16];
+        channels[0] = CHANNEL::channel();
+        SequenceBuilder {
+            channels,
+            sample_times: [sample_time; 16],
+            len: 1,
+            _adc: PhantomData,
+        }
+    }
+
+    /// Appends `channel` to the end of the sequence
+    pub fn add<CHANNEL>(
+        mut self,
+        _channel: &CHANNEL,
+        sample_time: config::SampleTime,
+    ) -> Result<Self, SequenceError>
+    where
+        CHANNEL: Channel<ADC, ID = u8>,
+    {
+        let channel = CHANNEL::channel();
+        if self.len == 16 {
+            return Err(SequenceError::Full);
+        }
+        if self
+            .iter()
+            .any(|(ch, st)| ch == channel && st != sample_time)
+        {
+            return Err(SequenceError::ConflictingSampleTime);
+        }
+
+        self.channels[usize::from(self.len)] = channel;
+        self.sample_times[usize::from(self.len)] = sample_time;
+        self.len += 1;
+        Ok(self)
+    }
+
+    /// Number of conversions in the sequence
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> u8 {
+        self.len
+    }
+
+    /// Channel id and sample time of every conversion, in sequence order
+    pub fn iter(&self) -> impl Iterator<Item = (u8, config::SampleTime)> + '_ {
+        self.channels
+            .iter()
+            .copied()
+            .zip(self.sample_times.iter().copied())
+            .take(usize::from(self.len))
+    }
+}
+
+/// Differential input pair
+///
+/// In differential mode the ADC converts the difference between channel `n` (positive input) and
+/// channel `n + 1` (negative input), see [`DifferentialPair`] for the supported pairs. The pair
+/// owns both inputs, so the negative input can not also be configured as a single-ended channel
+/// while the pair exists.
+///
+/// ```ignore
+/// let pair = Differential::new(gpioa.pa0.into_analog(), gpioa.pa1.into_analog());
+/// adc.configure_differential_channel(&pair, Sequence::One, SampleTime::Cycles_640_5);
+/// ```
+pub struct Differential<P, N> {
+    positive: P,
+    negative: N,
+}
+
+impl<P, N> Differential<P, N> {
+    /// Create a differential pair from a positive and negative input
+    pub fn new(positive: P, negative: N) -> Self {
+        Differential { positive, negative }
+    }
+
+    /// Release the positive and negative input
+    pub fn free(self) -> (P, N) {
+        (self.positive, self.negative)
+    }
+}
+
+/// Marker for [`Differential`] pairs that `ADC` can convert
+///
+/// Only implemented for pairs of pins where the negative input is on the channel following
+/// the channel of the positive input.
+pub trait DifferentialPair<ADC>: Channel<ADC, ID = u8> {}
+
+impl<ADC, P, N> Channel<ADC> for Differential<P, N>
+where
+    P: Channel<ADC, ID = u8>,
+    N: Channel<ADC, ID = u8>,
+{
+    type ID = u8;
+
+    fn channel() -> u8 {
+        P::channel()
+    }
+}
+
 macro_rules! adc_pins {
     ($($pin:ty => ($adc:ident, $chan:expr)),+ $(,)*) => {
         $(
@@ -144,21 +414,33 @@ macro_rules! adc_pins {
     };
 }
 
-macro_rules! adc_op_pga {
-    ($($opamp:ty => ($adc:ident, $chan:expr)),+ $(,)*) => {
+macro_rules! adc_differential_pairs {
+    ($(($positive:ty, $negative:ty) => $adc:ident),+ $(,)*) => {
         $(
-            impl<A, B> Channel<stm32::$adc> for $opamp {
-                type ID = u8;
-                fn channel() -> u8 { $chan }
-            }
+            impl DifferentialPair<stm32::$adc> for Differential<$positive, $negative> {}
         )+
     };
 }
 
-macro_rules! adc_op_follower {
-    ($($opamp:ty => ($adc:ident, $chan:expr)),+ $(,)*) => {
+/// Internal connection of an opamp output to an ADC channel, for every mode of the opamp
+///
+/// The output of the opamp is only connected to the ADC when it is not routed to its output
+/// pin, so this is only implemented for opamps in the `opamp::InternalOutput` state, see
+/// `into_internal_output` of the opamp.
+macro_rules! adc_opamp {
+    ($($opamp:ident => ($adc:ident, $chan:expr)),+ $(,)*) => {
         $(
-            impl<A> Channel<stm32::$adc> for $opamp {
+            impl<A> Channel<stm32::$adc> for opamp::$opamp::Follower<A, opamp::InternalOutput> {
+                type ID = u8;
+                fn channel() -> u8 { $chan }
+            }
+
+            impl<A, B> Channel<stm32::$adc> for opamp::$opamp::OpenLoop<A, B, opamp::InternalOutput> {
+                type ID = u8;
+                fn channel() -> u8 { $chan }
+            }
+
+            impl<A, B> Channel<stm32::$adc> for opamp::$opamp::Pga<A, B, opamp::InternalOutput> {
                 type ID = u8;
                 fn channel() -> u8 { $chan }
             }
@@ -392,6 +674,26 @@ pub mod config {
         }
     }
 
+    impl Clock {
+        /// Division factor of the prescaler
+        pub fn divisor(self) -> u32 {
+            match self {
+                Clock::Div_1 => 1,
+                Clock::Div_2 => 2,
+                Clock::Div_4 => 4,
+                Clock::Div_6 => 6,
+                Clock::Div_8 => 8,
+                Clock::Div_10 => 10,
+                Clock::Div_12 => 12,
+                Clock::Div_16 => 16,
+                Clock::Div_32 => 32,
+                Clock::Div_64 => 64,
+                Clock::Div_128 => 128,
+                Clock::Div_256 => 256,
+            }
+        }
+    }
+
     impl From<u8> for Clock {
         fn from(b: u8) -> Clock {
             match b {
@@ -459,7 +761,8 @@ pub mod config {
 
     /// Possible external triggers the ADC can listen to
     ///
-    /// This applies to ADC3, ADC4 and ADC5
+    /// This applies to the regular group of ADC1 and ADC2, see `ExternalTrigger345` for the
+    /// other ADCs
     #[derive(Debug, Clone, Copy, Default)]
     pub enum ExternalTrigger12 {
         /// TIM1 compare channel 1
@@ -529,8 +832,8 @@ pub mod config {
 
     /// Possible external triggers the ADC can listen to
     ///
-    /// This applies to ADC3, ADC4 and ADC5
-    ///
+    /// This applies to the regular group of ADC3, ADC4 and ADC5, which use a different mapping
+    /// than ADC1 and ADC2. `set_external_trigger` only accepts the enum matching the ADC.
     #[cfg(any(
         feature = "stm32g471",
         feature = "stm32g473",
@@ -694,6 +997,242 @@ pub mod config {
         }
     }
 
+    /// Possible external triggers the injected group of the ADC can listen to
+    ///
+    /// This applies to the injected group of ADC1 and ADC2, see `InjectedTrigger345` for the
+    /// other ADCs
+    #[derive(Debug, Clone, Copy, Default)]
+    pub enum InjectedTrigger12 {
+        /// TIM1 trigger out
+        #[default]
+        Tim_1_trgo,
+        /// TIM1 compare channel 4
+        Tim_1_cc_4,
+        /// TIM2 trigger out
+        Tim_2_trgo,
+        /// TIM2 compare channel 1
+        Tim_2_cc_1,
+        /// TIM3 compare channel 4
+        Tim_3_cc_4,
+        /// TIM4 trigger out
+        Tim_4_trgo,
+        /// External interupt line 15
+        Exti_15,
+        /// TIM8 compare channel 4
+        Tim_8_cc_4,
+        /// TIM1 trigger out 2
+        Tim_1_trgo_2,
+        /// TIM8 trigger out
+        Tim_8_trgo,
+        /// TIM8 trigger out 2
+        Tim_8_trgo_2,
+        /// TIM3 compare channel 3
+        Tim_3_cc_3,
+        /// TIM3 trigger out
+        Tim_3_trgo,
+        /// TIM3 compare channel 1
+        Tim_3_cc_1,
+        /// TIM6 trigger out
+        Tim_6_trgo,
+        /// TIM15 trigger out
+        Tim_15_trgo,
+        /// TIM20 trigger out
+        Tim_20_trgo,
+        /// TIM20 trigger out 2
+        Tim_20_trgo_2,
+        /// TIM20 compare channel 4
+        Tim_20_cc_4,
+        /// hrtim_adc_trg2
+        Hrtim_adc_trg_2,
+        /// hrtim_adc_trg4
+        Hrtim_adc_trg_4,
+        /// hrtim_adc_trg5
+        Hrtim_adc_trg_5,
+        /// hrtim_adc_trg6
+        Hrtim_adc_trg_6,
+        /// hrtim_adc_trg7
+        Hrtim_adc_trg_7,
+        /// hrtim_adc_trg8
+        Hrtim_adc_trg_8,
+        /// hrtim_adc_trg9
+        Hrtim_adc_trg_9,
+        /// hrtim_adc_trg10
+        Hrtim_adc_trg_10,
+        /// TIM16 compare channel 1
+        Tim_16_cc_1,
+        /// LP_timeout
+        Lp_timeout,
+        /// TIM7 trigger out
+        Tim_7_trgo,
+    }
+
+    impl From<InjectedTrigger12> for u8 {
+        fn from(et: InjectedTrigger12) -> u8 {
+            match et {
+                InjectedTrigger12::Tim_1_trgo => 0b00000,
+                InjectedTrigger12::Tim_1_cc_4 => 0b00001,
+                InjectedTrigger12::Tim_2_trgo => 0b00010,
+                InjectedTrigger12::Tim_2_cc_1 => 0b00011,
+                InjectedTrigger12::Tim_3_cc_4 => 0b00100,
+                InjectedTrigger12::Tim_4_trgo => 0b00101,
+                InjectedTrigger12::Exti_15 => 0b00110,
+                InjectedTrigger12::Tim_8_cc_4 => 0b00111,
+                InjectedTrigger12::Tim_1_trgo_2 => 0b01000,
+                InjectedTrigger12::Tim_8_trgo => 0b01001,
+                InjectedTrigger12::Tim_8_trgo_2 => 0b01010,
+                InjectedTrigger12::Tim_3_cc_3 => 0b01011,
+                InjectedTrigger12::Tim_3_trgo => 0b01100,
+                InjectedTrigger12::Tim_3_cc_1 => 0b01101,
+                InjectedTrigger12::Tim_6_trgo => 0b01110,
+                InjectedTrigger12::Tim_15_trgo => 0b01111,
+                InjectedTrigger12::Tim_20_trgo => 0b10000,
+                InjectedTrigger12::Tim_20_trgo_2 => 0b10001,
+                InjectedTrigger12::Tim_20_cc_4 => 0b10010,
+                InjectedTrigger12::Hrtim_adc_trg_2 => 0b10011,
+                InjectedTrigger12::Hrtim_adc_trg_4 => 0b10100,
+                InjectedTrigger12::Hrtim_adc_trg_5 => 0b10101,
+                InjectedTrigger12::Hrtim_adc_trg_6 => 0b10110,
+                InjectedTrigger12::Hrtim_adc_trg_7 => 0b10111,
+                InjectedTrigger12::Hrtim_adc_trg_8 => 0b11000,
+                InjectedTrigger12::Hrtim_adc_trg_9 => 0b11001,
+                InjectedTrigger12::Hrtim_adc_trg_10 => 0b11010,
+                InjectedTrigger12::Tim_16_cc_1 => 0b11011,
+                InjectedTrigger12::Lp_timeout => 0b11100,
+                InjectedTrigger12::Tim_7_trgo => 0b11101,
+                // Reserved => 0b11110
+                // Reserved => 0b11111
+            }
+        }
+    }
+
+    /// Possible external triggers the injected group of the ADC can listen to
+    ///
+    /// This applies to the injected group of ADC3, ADC4 and ADC5, which use a different mapping
+    /// than ADC1 and ADC2. `set_injected_external_trigger` only accepts the enum matching the ADC.
+    #[cfg(any(
+        feature = "stm32g471",
+        feature = "stm32g473",
+        feature = "stm32g474",
+        feature = "stm32g483",
+        feature = "stm32g484",
+        feature = "stm32g491",
+        feature = "stm32g4a1",
+    ))]
+    #[derive(Debug, Clone, Copy, Default)]
+    pub enum InjectedTrigger345 {
+        /// TIM1 trigger out
+        #[default]
+        Tim_1_trgo,
+        /// TIM1 compare channel 4
+        Tim_1_cc_4,
+        /// TIM2 trigger out
+        Tim_2_trgo,
+        /// TIM8 compare channel 2
+        Tim_8_cc_2,
+        /// TIM4 compare channel 3
+        Tim_4_cc_3,
+        /// TIM4 trigger out
+        Tim_4_trgo,
+        /// TIM4 compare channel 4
+        Tim_4_cc_4,
+        /// TIM8 compare channel 4
+        Tim_8_cc_4,
+        /// TIM1 trigger out 2
+        Tim_1_trgo_2,
+        /// TIM8 trigger out
+        Tim_8_trgo,
+        /// TIM8 trigger out 2
+        Tim_8_trgo_2,
+        /// TIM1 compare channel 3
+        Tim_1_cc_3,
+        /// TIM3 trigger out
+        Tim_3_trgo,
+        /// External interupt line 3
+        Exti_3,
+        /// TIM6 trigger out
+        Tim_6_trgo,
+        /// TIM15 trigger out
+        Tim_15_trgo,
+        /// TIM20 trigger out
+        Tim_20_trgo,
+        /// TIM20 trigger out 2
+        Tim_20_trgo_2,
+        /// TIM20 compare channel 2
+        Tim_20_cc_2,
+        /// hrtim_adc_trg2
+        Hrtim_adc_trg_2,
+        /// hrtim_adc_trg4
+        Hrtim_adc_trg_4,
+        /// hrtim_adc_trg5
+        Hrtim_adc_trg_5,
+        /// hrtim_adc_trg6
+        Hrtim_adc_trg_6,
+        /// hrtim_adc_trg7
+        Hrtim_adc_trg_7,
+        /// hrtim_adc_trg8
+        Hrtim_adc_trg_8,
+        /// hrtim_adc_trg9
+        Hrtim_adc_trg_9,
+        /// hrtim_adc_trg10
+        Hrtim_adc_trg_10,
+        /// hrtim_adc_trg1
+        Hrtim_adc_trg_1,
+        /// hrtim_adc_trg3
+        Hrtim_adc_trg_3,
+        /// LP_timeout
+        Lp_timeout,
+        /// TIM7 trigger out
+        Tim_7_trgo,
+    }
+
+    #[cfg(any(
+        feature = "stm32g471",
+        feature = "stm32g473",
+        feature = "stm32g474",
+        feature = "stm32g483",
+        feature = "stm32g484",
+        feature = "stm32g491",
+        feature = "stm32g4a1",
+    ))]
+    impl From<InjectedTrigger345> for u8 {
+        fn from(et: InjectedTrigger345) -> u8 {
+            match et {
+                InjectedTrigger345::Tim_1_trgo => 0b00000,
+                InjectedTrigger345::Tim_1_cc_4 => 0b00001,
+                InjectedTrigger345::Tim_2_trgo => 0b00010,
+                InjectedTrigger345::Tim_8_cc_2 => 0b00011,
+                InjectedTrigger345::Tim_4_cc_3 => 0b00100,
+                InjectedTrigger345::Tim_4_trgo => 0b00101,
+                InjectedTrigger345::Tim_4_cc_4 => 0b00110,
+                InjectedTrigger345::Tim_8_cc_4 => 0b00111,
+                InjectedTrigger345::Tim_1_trgo_2 => 0b01000,
+                InjectedTrigger345::Tim_8_trgo => 0b01001,
+                InjectedTrigger345::Tim_8_trgo_2 => 0b01010,
+                InjectedTrigger345::Tim_1_cc_3 => 0b01011,
+                InjectedTrigger345::Tim_3_trgo => 0b01100,
+                InjectedTrigger345::Exti_3 => 0b01101,
+                InjectedTrigger345::Tim_6_trgo => 0b01110,
+                InjectedTrigger345::Tim_15_trgo => 0b01111,
+                InjectedTrigger345::Tim_20_trgo => 0b10000,
+                InjectedTrigger345::Tim_20_trgo_2 => 0b10001,
+                InjectedTrigger345::Tim_20_cc_2 => 0b10010,
+                InjectedTrigger345::Hrtim_adc_trg_2 => 0b10011,
+                InjectedTrigger345::Hrtim_adc_trg_4 => 0b10100,
+                InjectedTrigger345::Hrtim_adc_trg_5 => 0b10101,
+                InjectedTrigger345::Hrtim_adc_trg_6 => 0b10110,
+                InjectedTrigger345::Hrtim_adc_trg_7 => 0b10111,
+                InjectedTrigger345::Hrtim_adc_trg_8 => 0b11000,
+                InjectedTrigger345::Hrtim_adc_trg_9 => 0b11001,
+                InjectedTrigger345::Hrtim_adc_trg_10 => 0b11010,
+                InjectedTrigger345::Hrtim_adc_trg_1 => 0b11011,
+                InjectedTrigger345::Hrtim_adc_trg_3 => 0b11100,
+                InjectedTrigger345::Lp_timeout => 0b11101,
+                InjectedTrigger345::Tim_7_trgo => 0b11110,
+                // Reserved => 0b11111
+            }
+        }
+    }
+
     /// Possible trigger modes
     #[derive(Debug, Clone, Copy)]
     pub enum TriggerMode {
@@ -723,6 +1262,9 @@ pub mod config {
         /// Right align output data
         Right,
         /// Left align output data
+        ///
+        /// The data is aligned to bit 15 of the data register, except for 6 bit
+        /// resolution where it is aligned to bit 7.
         Left,
     }
     impl From<Align> for bool {
@@ -748,6 +1290,24 @@ pub mod config {
         Discontinuous,
     }
 
+    /// Behaviour of the data register on an overrun (OVRMOD)
+    ///
+    /// An overrun occurs when a regular conversion finishes before the previous result was read
+    /// by the CPU or the DMA. The `OVR` flag is set in both modes. With DMA enabled the ADC stops
+    /// issuing DMA requests in both modes until the flag is cleared, so a transfer stalls instead
+    /// of silently skipping samples.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum OverrunMode {
+        /// Keep the old data and discard the new result, the reset value
+        ///
+        /// Results keep being discarded for as long as the `OVR` flag is set.
+        Preserve,
+        /// Overwrite the old data with the new result
+        ///
+        /// Conversions carry on, the data register always holds the latest result.
+        Overwrite,
+    }
+
     /// Number of channels to sample per trigger in discontinuous mode
     ///
     /// NOTE: This only applies to discontinuous
@@ -842,7 +1402,7 @@ pub mod config {
         }
 
         /// Set to differential by id
-        fn differential_by_id(&mut self, id: u8) {
+        pub(crate) fn differential_by_id(&mut self, id: u8) {
             self.0 |= 1 << id;
         }
 
@@ -861,24 +1421,87 @@ pub mod config {
         }
     }
 
-    /// Configuration for the adc.
-    /// There are some additional parameters on the adc peripheral that can be
-    /// added here when needed but this covers several basic usecases.
-    #[derive(Debug, Clone, Copy)]
-    pub struct AdcConfig<ET> {
-        pub(crate) clock_mode: ClockMode,
-        pub(crate) clock: Clock,
-        pub(crate) resolution: Resolution,
-        pub(crate) align: Align,
-        pub(crate) external_trigger: (TriggerMode, ET),
-        pub(crate) continuous: Continuous,
-        pub(crate) subgroup_len: SubGroupLength,
-        pub(crate) dma: Dma,
-        pub(crate) end_of_conversion_interrupt: Eoc,
-        pub(crate) overrun_interrupt: bool,
-        pub(crate) default_sample_time: SampleTime,
-        pub(crate) vdda: Option<u32>,
-        pub(crate) auto_delay: bool,
+    /// Hardware oversampling configuration
+    ///
+    /// Each result is the sum of `ratio` conversions shifted right by `shift` bits. Without a
+    /// shift, oversampling ratios above 16 at 12 bit resolution overflow the 16 bit data register.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct Oversampling {
+        pub(crate) ratio: u16,
+        pub(crate) shift: u8,
+        pub(crate) triggered: bool,
+        pub(crate) resumed: bool,
+        pub(crate) injected: bool,
+    }
+
+    impl Oversampling {
+        /// Oversample regular conversions `ratio` times and shift the sum right by `shift` bits
+        ///
+        /// `ratio` has to be a power of two in `2..=256` and `shift` at most 8
+        pub fn new(ratio: u16, shift: u8) -> Self {
+            assert!(ratio.is_power_of_two() && (2..=256).contains(&ratio));
+            assert!(shift <= 8);
+            Self {
+                ratio,
+                shift,
+                triggered: false,
+                resumed: false,
+                injected: false,
+            }
+        }
+
+        /// If `true`, each conversion of the oversampling burst needs a new trigger,
+        /// otherwise a single trigger starts all conversions of the burst
+        pub fn triggered(mut self, triggered: bool) -> Self {
+            self.triggered = triggered;
+            self
+        }
+
+        /// If `true`, a regular oversampling burst interrupted by an injected conversion is
+        /// resumed, otherwise it is restarted from the beginning
+        pub fn resumed(mut self, resumed: bool) -> Self {
+            self.resumed = resumed;
+            self
+        }
+
+        /// Also oversample injected conversions
+        pub fn injected(mut self, injected: bool) -> Self {
+            self.injected = injected;
+            self
+        }
+
+        /// Maximum result with oversampling at `resolution`
+        pub fn max_sample(&self, resolution: Resolution) -> u32 {
+            (resolution.to_max_sample() * u32::from(self.ratio)) >> self.shift
+        }
+
+        /// OVSR register value
+        pub(crate) fn ratio_bits(&self) -> u8 {
+            (self.ratio.trailing_zeros() - 1) as u8
+        }
+    }
+
+    /// Configuration for the adc.
+    /// There are some additional parameters on the adc peripheral that can be
+    /// added here when needed but this covers several basic usecases.
+    #[derive(Debug, Clone, Copy)]
+    pub struct AdcConfig<ET> {
+        pub(crate) clock_mode: ClockMode,
+        pub(crate) clock: Clock,
+        pub(crate) resolution: Resolution,
+        pub(crate) align: Align,
+        pub(crate) external_trigger: (TriggerMode, ET),
+        pub(crate) continuous: Continuous,
+        pub(crate) subgroup_len: SubGroupLength,
+        pub(crate) dma: Dma,
+        pub(crate) end_of_conversion_interrupt: Eoc,
+        pub(crate) overrun_interrupt: bool,
+        pub(crate) overrun_mode: OverrunMode,
+        pub(crate) default_sample_time: SampleTime,
+        pub(crate) vdda: Option<u32>,
+        pub(crate) auto_delay: bool,
+        pub(crate) sample_time_plus: bool,
+        pub(crate) oversampling: Option<Oversampling>,
 
         /// Sets the differential input type of the Adc
         pub difsel: DifferentialSelection,
@@ -943,6 +1566,13 @@ pub mod config {
             self
         }
 
+        /// Select what happens to the data register on an overrun, see [`OverrunMode`]
+        #[inline(always)]
+        pub fn overrun_mode(mut self, mode: OverrunMode) -> Self {
+            self.overrun_mode = mode;
+            self
+        }
+
         /// change the default_sample_time field
         #[inline(always)]
         pub fn default_sample_time(mut self, default_sample_time: SampleTime) -> Self {
@@ -967,12 +1597,37 @@ pub mod config {
             self
         }
 
-        /// Enable of disable the auto delay function
+        /// Enable or disable the auto delay function
+        ///
+        /// With auto delay enabled the ADC waits until the previous result has been read,
+        /// either by software or by DMA, before it starts a new conversion. Triggers arriving
+        /// while the ADC waits are ignored, so no overrun can occur. This also applies in
+        /// continuous mode, where the next conversion starts once the result has been read.
+        /// Note that a stalled DMA transfer, for example while halted by a debugger, stalls the
+        /// conversions as well.
         #[inline(always)]
         pub fn auto_delay(mut self, delay: bool) -> Self {
             self.auto_delay = delay;
             self
         }
+
+        /// Add one ADC clock cycle to the shortest sample time (SMPPLUS)
+        ///
+        /// This turns `SampleTime::Cycles_2_5` into 3.5 cycles for all channels using it, which
+        /// makes the total conversion time an even number of ADC clock cycles. The other sample
+        /// times are not affected.
+        #[inline(always)]
+        pub fn sample_time_plus(mut self, enable: bool) -> Self {
+            self.sample_time_plus = enable;
+            self
+        }
+
+        /// Enable hardware oversampling
+        #[inline(always)]
+        pub fn oversampling(mut self, oversampling: Oversampling) -> Self {
+            self.oversampling = Some(oversampling);
+            self
+        }
     }
 
     impl AdcConfig<ExternalTrigger12> {
@@ -1023,12 +1678,47 @@ pub mod config {
                 dma: Dma::Disabled,
                 end_of_conversion_interrupt: Eoc::Disabled,
                 overrun_interrupt: false,
+                overrun_mode: OverrunMode::Preserve,
                 default_sample_time: SampleTime::Cycles_640_5,
                 vdda: None,
                 difsel: DifferentialSelection::default(),
                 auto_delay: false,
+                sample_time_plus: false,
+                oversampling: None,
+            }
+        }
+    }
+
+    impl<ET> AdcConfig<ET> {
+        /// Largest sample with the configured resolution and oversampling
+        pub(crate) fn max_sample(&self) -> u32 {
+            match self.oversampling {
+                Some(ovs) => ovs.max_sample(self.resolution),
+                None => self.resolution.to_max_sample(),
+            }
+        }
+
+        /// Converts a left aligned sample to a right aligned one, right aligned samples and
+        /// oversampled samples are returned as is
+        pub(crate) fn sample_to_right_aligned(&self, sample: u16) -> u16 {
+            if let (Align::Left, None) = (self.align, self.oversampling) {
+                match self.resolution {
+                    Resolution::Twelve => sample >> 4,
+                    Resolution::Ten => sample >> 6,
+                    Resolution::Eight => sample >> 8,
+                    // 6 bit samples are left aligned within the lower byte
+                    Resolution::Six => sample >> 2,
+                }
+            } else {
+                sample
             }
         }
+
+        /// Converts a sample to millivolts with `vdda` as full scale
+        pub(crate) fn sample_to_millivolts(&self, sample: u16, vdda: u32) -> u16 {
+            let sample = self.sample_to_right_aligned(sample);
+            ((u32::from(sample) * vdda) / self.max_sample()) as u16
+        }
     }
 }
 
@@ -1254,6 +1944,10 @@ pub struct DynamicAdc<ADC: TriggerType> {
     adc_reg: ADC,
     /// VDDA in millivolts calculated from the factory calibration and vrefint
     calibrated_vdda: u32,
+    /// Clock source selected in the RCC
+    clock_source: ClockSource,
+    /// Clock frequencies at the time the ADC was claimed
+    clocks: Clocks,
 }
 impl<ADC: TriggerType> fmt::Debug for DynamicAdc<ADC> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -1284,6 +1978,9 @@ where
 }
 
 /// ADC Clock Source selection
+///
+/// This is the asynchronous kernel clock of the ADC, which is divided by `config::Clock`. It is
+/// not used in the synchronous clock modes, see `config::ClockMode`.
 #[derive(Debug, Clone, Copy)]
 pub enum ClockSource {
     /// Use the System Clock as Clock Source
@@ -1304,6 +2001,9 @@ impl From<ClockSource> for u8 {
 /// used to create an ADC instance from the stm32::Adc
 pub trait AdcClaim<TYPE: TriggerType> {
     /// create a disabled ADC instance from the stm32::Adc
+    ///
+    /// The default config clocks the ADC from HCLK, select a slower `config::ClockMode` or
+    /// `config::Clock` before enabling if HCLK is above 60MHz.
     fn claim(
         self,
         cs: ClockSource,
@@ -1313,6 +2013,8 @@ pub trait AdcClaim<TYPE: TriggerType> {
     ) -> Adc<TYPE, Disabled>;
 
     /// create an enabled ADC instance from the stm32::Adc
+    ///
+    /// Panics if `config` results in an ADC clock above 60MHz
     fn claim_and_configure(
         self,
         cs: ClockSource,
@@ -1323,14 +2025,116 @@ pub trait AdcClaim<TYPE: TriggerType> {
     ) -> Adc<TYPE, Configured>;
 }
 
+/// Two ADCs of a common group in dual regular simultaneous mode
+///
+/// The master and slave ADC convert their regular sequences at the same time, started by the
+/// master. Both results are packed into the common data register (CDR) and transferred by a single
+/// DMA stream on the request line of the master, one [`DualSample`] per pair. The slave is only
+/// accessible through the master, as required by the hardware.
+pub struct DualAdc<MASTER: TriggerType, SLAVE: TriggerType> {
+    master: DynamicAdc<MASTER>,
+    slave: DynamicAdc<SLAVE>,
+}
+
+impl<MASTER: TriggerType, SLAVE: TriggerType> DualAdc<MASTER, SLAVE> {
+    /// Split a word read from the common data register into the `(master, slave)` samples
+    #[inline(always)]
+    pub fn unpack(word: u32) -> (u16, u16) {
+        DualSample::from(word).into()
+    }
+}
+
+/// The samples of the master and slave ADC of a [`DualAdc`], converted at the same time
+///
+/// This is the element type of a DMA transfer from a [`DualAdc`]. It has the layout of the
+/// common data register (CDR), so each pair is transferred as one 32 bit word.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[repr(C)]
+pub struct DualSample {
+    /// Sample of the master ADC, the lower half of CDR
+    pub master: u16,
+    /// Sample of the slave ADC, the upper half of CDR
+    pub slave: u16,
+}
+
+impl From<u32> for DualSample {
+    #[inline(always)]
+    fn from(word: u32) -> Self {
+        DualSample {
+            master: word as u16,
+            slave: (word >> 16) as u16,
+        }
+    }
+}
+
+impl From<DualSample> for (u16, u16) {
+    #[inline(always)]
+    fn from(sample: DualSample) -> Self {
+        (sample.master, sample.slave)
+    }
+}
+
+impl Sealed for DualSample {}
+
+impl DmaWord for DualSample {
+    const SIZE: u8 = 2;
+}
+
+// SAFETY: DualSample is two u16 without padding, so any bit pattern is valid
+unsafe impl embedded_dma::Word for DualSample {}
+
+/// The raw content of CDR
+impl BufferWord<DualSample> for u32 {}
+
+/// Only the master sample, the DMA truncates CDR to its lower half
+impl BufferWord<DualSample> for u16 {}
+
+/// used to combine two ADCs from the common ADC peripheral into a [`DualAdc`]
+pub trait DualAdcClaim<MASTER: TriggerType, SLAVE: TriggerType> {
+    /// Configure `master` and `slave` for dual regular simultaneous mode with DMA through the
+    /// common data register and enable them
+    ///
+    /// The regular sequences of both ADCs have to be configured beforehand and be of the same
+    /// length. The external trigger of the slave is not used. Panics if `dma` is `Dma::Disabled`
+    /// or if the sequence lengths differ.
+    fn claim_dual(
+        &self,
+        master: Adc<MASTER, Disabled>,
+        slave: Adc<SLAVE, Disabled>,
+        dma: config::Dma,
+    ) -> DualAdc<MASTER, SLAVE>;
+}
+
 trait AdcConfig {
     fn configure_clock_source(cs: ClockSource, rcc: &Rcc);
 }
 
+/// Waker of the task waiting for `Adc::convert_async` of an ADC
+#[cfg(feature = "async")]
+struct ConversionWaker {
+    waker: Option<Waker>,
+    /// The end of conversion interrupt was enabled by the waiting task, so `on_interrupt` has
+    /// to disable it again. It is left alone when it was enabled through the configuration.
+    armed: bool,
+}
+
+/// Runs the closure when dropped, also when a future holding it is dropped before completion
+#[cfg(feature = "async")]
+struct OnDrop<F: FnMut()>(F);
+
+#[cfg(feature = "async")]
+impl<F: FnMut()> Drop for OnDrop<F> {
+    fn drop(&mut self) {
+        (self.0)()
+    }
+}
+
 /// Specifies what External trigger type the ADC uses
 pub trait TriggerType {
     /// Specifies what External trigger type the ADC uses
     type ExternalTrigger: fmt::Debug;
+    /// Specifies what External trigger type the injected group of the ADC uses
+    type InjectedTrigger: fmt::Debug;
 }
 
 #[inline(always)]
@@ -1369,7 +2173,7 @@ macro_rules! adc {
             self.adc.enable_vbat(common)
         }
 
-        /// Enables the vbat internal channel
+        /// Disables the vbat internal channel
         #[inline(always)]
         pub fn disable_vbat(&self, common: &stm32::$common_type) {
             self.adc.disable_vbat(common)
@@ -1480,10 +2284,11 @@ macro_rules! adc {
     (additionals: $adc_type:ident => ($common_type:ident)) => {
     };
 
-    ($($adc_type:ident => ($trigger_type:ident, $configure_clocks_fn_name:ident, $mux:expr, ($common_type:ident) )),+ $(,)*) => {
+    ($($adc_type:ident => ($trigger_type:ident, $injected_trigger_type:ident, $configure_clocks_fn_name:ident, $mux:expr, ($common_type:ident) )),+ $(,)*) => {
         $(
             impl TriggerType for stm32::$adc_type {
                 type ExternalTrigger = $trigger_type;
+                type InjectedTrigger = $injected_trigger_type;
             }
 
             impl AdcConfig for stm32::$adc_type {
@@ -1494,10 +2299,98 @@ macro_rules! adc {
             }
 
             impl DynamicAdc<stm32::$adc_type> {
-                /// Converts a sample value to millivolts using calibrated VDDA and configured resolution
+                /// Waker of the task waiting for `Adc::convert_async`
+                #[cfg(feature = "async")]
+                fn waker() -> &'static Mutex<RefCell<ConversionWaker>> {
+                    static WAKER: Mutex<RefCell<ConversionWaker>> = Mutex::new(RefCell::new(ConversionWaker {
+                        waker: None,
+                        armed: false,
+                    }));
+                    &WAKER
+                }
+
+                /// Handles the end of conversion interrupt for `Adc::convert_async`
+                ///
+                /// Call this from the interrupt handler of the ADC, `ADC1_2` is shared by ADC1 and
+                /// ADC2 so call it for both of them there. The interrupt also has to be unmasked:
+                ///
+                /// ```ignore
+                /// unsafe { cortex_m::peripheral::NVIC::unmask(stm32::Interrupt::ADC1_2) };
+                ///
+                /// #[interrupt]
+                /// fn ADC1_2() {
+                ///     DynamicAdc::<stm32::ADC1>::on_interrupt();
+                ///     DynamicAdc::<stm32::ADC2>::on_interrupt();
+                /// }
+                /// ```
+                ///
+                /// The end of conversion interrupt is only disabled again if `convert_async`
+                /// enabled it. If it was enabled through `AdcConfig::end_of_conversion_interrupt`
+                /// the flag has to be cleared by reading the result, as usual.
+                #[cfg(feature = "async")]
+                pub fn on_interrupt() {
+                    // NOTE(unsafe) only the end of conversion interrupt enable is modified, and
+                    // only when `convert_async` enabled it
+                    let adc = unsafe { &*stm32::$adc_type::ptr() };
+                    if adc.isr.read().eoc().bit_is_set() {
+                        cortex_m::interrupt::free(|cs| {
+                            let mut slot = Self::waker().borrow(cs).borrow_mut();
+                            if slot.armed {
+                                // The flag is cleared by reading the result, so stop listening
+                                // to not retrigger the interrupt
+                                adc.ier.modify(|_, w| w.eocie().clear_bit());
+                                slot.armed = false;
+                            }
+                            if let Some(waker) = slot.waker.take() {
+                                waker.wake();
+                            }
+                        });
+                    }
+                }
+
+                /// Disables the end of conversion interrupt again if `convert_async` enabled it
+                /// and forgets the waiting task
+                #[cfg(feature = "async")]
+                fn disarm() {
+                    // NOTE(unsafe) only the end of conversion interrupt enable is modified, and
+                    // only when `convert_async` enabled it
+                    let adc = unsafe { &*stm32::$adc_type::ptr() };
+                    cortex_m::interrupt::free(|cs| {
+                        let mut slot = Self::waker().borrow(cs).borrow_mut();
+                        if slot.armed {
+                            adc.ier.modify(|_, w| w.eocie().clear_bit());
+                            slot.armed = false;
+                        }
+                        slot.waker = None;
+                    });
+                }
+
+                /// Converts a sample value to millivolts using calibrated VDDA, configured resolution,
+                /// alignment and oversampling
                 #[inline(always)]
                 pub fn sample_to_millivolts(&self, sample: u16) -> u16 {
-                    Vref::sample_to_millivolts_ext(sample, self.calibrated_vdda, self.config.resolution)
+                    self.config.sample_to_millivolts(sample, self.calibrated_vdda)
+                }
+
+                /// Converts a left aligned sample to a right aligned one, right aligned samples
+                /// and oversampled samples are returned as is
+                #[inline(always)]
+                pub fn sample_to_right_aligned(&self, sample: u16) -> u16 {
+                    self.config.sample_to_right_aligned(sample)
+                }
+
+                /// Sets the VDDA, in millivolts, used by `sample_to_millivolts`
+                ///
+                /// Use `Vref::sample_to_vdda` to measure it.
+                #[inline(always)]
+                pub fn set_reference_voltage(&mut self, vdda_mv: u32) {
+                    self.calibrated_vdda = vdda_mv;
+                }
+
+                /// Returns the VDDA, in millivolts, used by `sample_to_millivolts`
+                #[inline(always)]
+                pub fn reference_voltage(&self) -> u32 {
+                    self.calibrated_vdda
                 }
 
                 /// Disables the Voltage Regulator and release the ADC
@@ -1522,10 +2415,15 @@ macro_rules! adc {
                     }
                 }
 
-                /// Puts a Disabled Adc into Powered Mode
+                /// Puts a Disabled Adc into Deep Power Down Mode
+                ///
+                /// The voltage regulator is turned off and DEEPPWD is set, which gives the lowest
+                /// current consumption. The configuration registers are kept, but the calibration
+                /// is lost. It is redone when the adc is enabled again.
                 #[inline(always)]
                 pub fn power_down(&mut self) {
                     self.disable_vreg();
+                    self.enable_deeppwd_down();
                 }
 
                 /// Enables the Deep Power Down Modus
@@ -1583,9 +2481,17 @@ macro_rules! adc {
                 /// Enables the adc
                 #[inline(always)]
                 pub fn enable(&mut self) {
+                    #[cfg(debug_assertions)]
+                    self.check_clock_frequency();
+                    let adc_clk = self.clock_frequency().raw();
+
                     self.calibrate_all();
                     self.apply_config(self.config);
 
+                    // ADEN may only be set 4 ADC clock cycles after the calibration is done
+                    let sys_clk = self.clocks.sys_clk.raw();
+                    cortex_m::asm::delay(4 * (sys_clk / adc_clk + 1));
+
                     self.adc_reg.isr.modify(|_, w| w.adrdy().set_bit());
                     self.adc_reg.cr.modify(|_, w| w.aden().set_bit());
 
@@ -1617,9 +2523,12 @@ macro_rules! adc {
                     self.set_dma(config.dma);
                     self.set_end_of_conversion_interrupt(config.end_of_conversion_interrupt);
                     self.set_overrun_interrupt(config.overrun_interrupt);
+                    self.set_overrun_mode(config.overrun_mode);
                     self.set_default_sample_time(config.default_sample_time);
                     self.set_channel_input_type(config.difsel);
                     self.set_auto_delay(config.auto_delay);
+                    self.set_sample_time_plus(config.sample_time_plus);
+                    self.set_oversampling(config.oversampling);
 
                     if let Some(vdda) = config.vdda {
                         self.calibrated_vdda = vdda;
@@ -1647,15 +2556,27 @@ macro_rules! adc {
                 }
 
                 /// Sets the sampling resolution
+                ///
+                /// Panics if a conversion is ongoing
                 #[inline(always)]
                 pub fn set_resolution(&mut self, resolution: config::Resolution) {
+                    let cr = self.adc_reg.cr.read();
+                    assert!(cr.adstart().bit_is_clear());
+                    assert!(cr.jadstart().bit_is_clear());
+
                     self.config.resolution = resolution;
                     self.adc_reg.cfgr.modify(|_, w| w.res().bits(resolution.into()));
                 }
 
                 /// Sets the DR register alignment to left or right
+                ///
+                /// Panics if a conversion is ongoing
                 #[inline(always)]
                 pub fn set_align(&mut self, align: config::Align) {
+                    let cr = self.adc_reg.cr.read();
+                    assert!(cr.adstart().bit_is_clear());
+                    assert!(cr.jadstart().bit_is_clear());
+
                     self.config.align = align;
                     self.adc_reg.cfgr.modify(|_, w| w.align().bit(align.into()));
                 }
@@ -1670,13 +2591,89 @@ macro_rules! adc {
                     });
                 }
 
-                /// Sets auto delay to true or false
+                /// Sets which external trigger starts the injected group and if it is disabled,
+                /// rising, falling or both
+                ///
+                /// Only the trigger fields of JSQR are written, the injected sequence is left as is.
+                ///
+                /// Panics if an injected conversion is ongoing
+                #[inline(always)]
+                pub fn set_injected_external_trigger(&mut self, (edge, jextsel): (config::TriggerMode, $injected_trigger_type)) {
+                    assert!(self.adc_reg.cr.read().jadstart().bit_is_clear());
+
+                    self.adc_reg.jsqr.modify(|_, w| unsafe { w
+                        .jextsel().bits(jextsel.into())
+                        .jexten().bits(edge.into())
+                    });
+                }
+
+                /// Sets auto delay to true or false, see [`config::AdcConfig::auto_delay`]
+                ///
+                /// Panics if a conversion is ongoing
                 #[inline(always)]
                 pub fn set_auto_delay(&mut self, delay: bool) {
+                    let cr = self.adc_reg.cr.read();
+                    assert!(cr.adstart().bit_is_clear());
+                    assert!(cr.jadstart().bit_is_clear());
+
                     self.config.auto_delay = delay;
                     self.adc_reg.cfgr.modify(|_, w| w.autdly().bit(delay) );
                 }
 
+                /// Sets the overrun mode, see [`config::OverrunMode`]
+                ///
+                /// Panics if a conversion is ongoing
+                #[inline(always)]
+                pub fn set_overrun_mode(&mut self, mode: config::OverrunMode) {
+                    assert!(self.adc_reg.cr.read().adstart().bit_is_clear());
+
+                    self.config.overrun_mode = mode;
+                    self.adc_reg.cfgr.modify(|_, w| w.ovrmod().bit(mode == config::OverrunMode::Overwrite));
+                }
+
+                /// Sets SMPPLUS, see [`config::AdcConfig::sample_time_plus`]
+                ///
+                /// Panics if a conversion is ongoing
+                #[inline(always)]
+                pub fn set_sample_time_plus(&mut self, enable: bool) {
+                    let cr = self.adc_reg.cr.read();
+                    assert!(cr.adstart().bit_is_clear());
+                    assert!(cr.jadstart().bit_is_clear());
+
+                    self.config.sample_time_plus = enable;
+                    self.adc_reg.smpr1.modify(|_, w| w.smpplus().bit(enable));
+                }
+
+                /// Enables hardware oversampling, or disables it if `None`
+                ///
+                /// Panics if a conversion is ongoing
+                #[inline(always)]
+                pub fn set_oversampling(&mut self, oversampling: Option<config::Oversampling>) {
+                    let cr = self.adc_reg.cr.read();
+                    assert!(cr.adstart().bit_is_clear());
+                    assert!(cr.jadstart().bit_is_clear());
+
+                    self.config.oversampling = oversampling;
+                    self.adc_reg.cfgr2.modify(|_, w| match oversampling {
+                        // SAFETY: `Oversampling::new` makes sure the shift is at most 8
+                        Some(ovs) => unsafe { w
+                            .rovse().set_bit()
+                            .jovse().bit(ovs.injected)
+                            .ovsr().bits(ovs.ratio_bits())
+                            .ovss().bits(ovs.shift)
+                            .trovs().bit(ovs.triggered)
+                            .rovsm().bit(ovs.resumed)
+                        },
+                        None => w.rovse().clear_bit().jovse().clear_bit(),
+                    });
+                }
+
+                /// Maximum sample value with the configured resolution and oversampling
+                #[inline(always)]
+                pub fn max_sample(&self) -> u32 {
+                    self.config.max_sample()
+                }
+
                 /// Enables and disables dis-/continuous mode
                 #[inline(always)]
                 pub fn set_continuous(&mut self, continuous: config::Continuous) {
@@ -1735,6 +2732,39 @@ macro_rules! adc {
                     self.adc_reg.ier.modify(|_, w| w.ovrie().bit(enable));
                 }
 
+                /// Starts listening for an `event`
+                ///
+                /// Note that the end of conversion, end of sequence and overrun interrupts are
+                /// set according to the `AdcConfig` again when the adc is enabled.
+                #[inline(always)]
+                pub fn listen(&mut self, event: Event) {
+                    // SAFETY: the mask is a single valid interrupt enable bit
+                    self.adc_reg.ier.modify(|r, w| unsafe { w.bits(r.bits() | event.mask()) });
+                }
+
+                /// Stops listening for an `event`
+                #[inline(always)]
+                pub fn unlisten(&mut self, event: Event) {
+                    // SAFETY: the mask is a single valid interrupt enable bit
+                    self.adc_reg.ier.modify(|r, w| unsafe { w.bits(r.bits() & !event.mask()) });
+                }
+
+                /// Returns `true` if the flag of `event` is set
+                #[inline(always)]
+                pub fn is_pending(&self, event: Event) -> bool {
+                    self.adc_reg.isr.read().bits() & event.mask() != 0
+                }
+
+                /// Clears the flag of `event`
+                ///
+                /// If the flag is not cleared, the interrupt will immediately retrigger after
+                /// the ISR has finished.
+                #[inline(always)]
+                pub fn clear_interrupt(&mut self, event: Event) {
+                    // SAFETY: the flags are cleared by writing 1, other bits are written as 0
+                    self.adc_reg.isr.write(|w| unsafe { w.bits(event.mask()) });
+                }
+
                 /// Sets the default sample time that is used for one-shot conversions.
                 /// [configure_channel](#method.configure_channel) and [start_conversion](#method.start_conversion) can be \
                 /// used for configurations where different sampling times are required per channel.
@@ -1818,8 +2848,39 @@ macro_rules! adc {
                     self.calibrate(config::InputType::SingleEnded);
                 }
 
+                /// Returns the frequency of the ADC clock, given the clock source, clock mode
+                /// and prescaler
+                ///
+                /// Panics if `ClockSource::PLL_P` is selected but the PLL P output is not enabled.
+                #[inline(always)]
+                pub fn clock_frequency(&self) -> Hertz {
+                    match self.config.clock_mode {
+                        config::ClockMode::Asynchronous => {
+                            let source = match self.clock_source {
+                                ClockSource::SystemClock => self.clocks.sys_clk,
+                                ClockSource::PLL_P => self.clocks.pll_clk.p.expect("PLL P output is not enabled"),
+                            };
+                            source / self.config.clock.divisor()
+                        }
+                        config::ClockMode::Synchronous_Div_1 => self.clocks.ahb_clk,
+                        config::ClockMode::Synchronous_Div_2 => self.clocks.ahb_clk / 2,
+                        config::ClockMode::Synchronous_Div_4 => self.clocks.ahb_clk / 4,
+                    }
+                }
+
+                /// Panics if the ADC clock is above the 60MHz allowed by the datasheet
+                fn check_clock_frequency(&self) {
+                    let adc_clk = self.clock_frequency().raw();
+                    assert!(
+                        adc_clk <= MAX_CLOCK_FREQUENCY,
+                        "ADC clock of {}Hz is above 60MHz, select a slower ClockMode or Clock prescaler",
+                        adc_clk
+                    );
+                }
+
                 /// Configure a channel for sampling.
                 /// It will make sure the sequence is at least as long as the `sequence` provided.
+                /// Consider using `set_sequence` which makes sure the sequence has no gaps.
                 /// # Arguments
                 /// * `channel` - channel to configure
                 /// * `sequence` - where in the sequence to sample the channel. Also called rank in some STM docs/code
@@ -1840,7 +2901,56 @@ macro_rules! adc {
                         }
                     });
 
+                    self.configure_sequence_slot(CHANNEL::channel(), sequence, sample_time);
+                }
+
+                /// Programs the regular sequence of `sequence` in one go, replacing the
+                /// current sequence
+                ///
+                /// This is the preferred way of setting up a sequence of more than one channel.
+                ///
+                /// Panics if a conversion is ongoing
+                pub fn set_sequence(&mut self, sequence: &SequenceBuilder<stm32::$adc_type>) {
+                    let cr = self.adc_reg.cr.read();
+                    assert!(cr.adstart().bit_is_clear());
+                    assert!(cr.jadstart().bit_is_clear());
+
+                    for (i, (channel, sample_time)) in sequence.iter().enumerate() {
+                        self.configure_sequence_slot(channel, config::Sequence::from(i as u8), sample_time);
+                    }
+                    self.adc_reg.sqr1.modify(|_, w| w.l().bits(sequence.len() - 1));
+                }
+
+                /// Changes the sample time of `channel` without touching the sequence
+                ///
+                /// Panics if a conversion is ongoing
+                pub fn set_channel_sample_time<CHANNEL>(&mut self, _channel: &CHANNEL, sample_time: config::SampleTime)
+                where
+                    CHANNEL: Channel<stm32::$adc_type, ID=u8>
+                {
+                    let cr = self.adc_reg.cr.read();
+                    assert!(cr.adstart().bit_is_clear());
+                    assert!(cr.jadstart().bit_is_clear());
+
+                    self.write_sample_time(CHANNEL::channel(), sample_time);
+                }
+
+                /// Returns the sample time currently configured for `channel`
+                pub fn channel_sample_time<CHANNEL>(&self, _channel: &CHANNEL) -> config::SampleTime
+                where
+                    CHANNEL: Channel<stm32::$adc_type, ID=u8>
+                {
                     let channel = CHANNEL::channel();
+                    let bits = if channel < 10 {
+                        self.adc_reg.smpr1.read().bits() >> (3 * channel)
+                    } else {
+                        self.adc_reg.smpr2.read().bits() >> (3 * (channel - 10))
+                    };
+                    config::SampleTime::from((bits & 0b111) as u8)
+                }
+
+                /// Sets `channel` at position `sequence` and its sample time
+                fn configure_sequence_slot(&mut self, channel: u8, sequence: config::Sequence, sample_time: config::SampleTime) {
 
                     //Set the channel in the right sequence field
                     match sequence {
@@ -1862,7 +2972,11 @@ macro_rules! adc {
                         config::Sequence::Sixteen  => self.adc_reg.sqr4.modify(|_, w| unsafe {w.sq16().bits(channel) }),
                     }
 
-                    //Set the sample time for the channel
+                    self.write_sample_time(channel, sample_time);
+                }
+
+                /// Sets the sample time of `channel` in SMPR1/SMPR2
+                fn write_sample_time(&mut self, channel: u8, sample_time: config::SampleTime) {
                     let st = u8::from(sample_time);
                     match channel {
                         0 => self.adc_reg.smpr1.modify(|_, w| w.smp0().bits(st) ),
@@ -1887,6 +3001,35 @@ macro_rules! adc {
                         _ => unimplemented!(),
                     }
                 }
+                /// Configure a differential channel pair for sampling
+                ///
+                /// Sets the positive channel to differential mode, runs the differential
+                /// calibration and then configures the channel like `configure_channel`.
+                /// Use `sample_to_signed` to interpret the results.
+                ///
+                /// Panics if the ADC is enabled.
+                pub fn configure_differential_channel<P, N>(&mut self, pair: &Differential<P, N>, sequence: config::Sequence, sample_time: config::SampleTime)
+                where
+                    Differential<P, N>: DifferentialPair<stm32::$adc_type>,
+                {
+                    assert!(!self.is_enabled());
+
+                    let mut difsel = self.config.difsel;
+                    difsel.differential_by_id(<Differential<P, N> as Channel<stm32::$adc_type>>::channel());
+                    self.set_channel_input_type(difsel);
+                    self.calibrate(config::InputType::Differential);
+
+                    self.configure_channel(pair, sequence, sample_time);
+                }
+
+                /// Converts a right aligned differential sample to a signed value in counts at
+                /// the configured resolution and oversampling, 0 meaning both inputs are at the same voltage
+                #[inline(always)]
+                pub fn sample_to_signed(&self, sample: u16) -> i16 {
+                    let midpoint = (self.max_sample() + 1) / 2;
+                    (i32::from(sample) - midpoint as i32) as i16
+                }
+
                 /// Synchronously convert a single sample
                 /// Note that it reconfigures the adc sequence and doesn't restore it
                 pub fn convert<PIN>(&mut self, pin: &PIN, sample_time: config::SampleTime) -> u16
@@ -1921,17 +3064,288 @@ macro_rules! adc {
                     result
                 }
 
-                /// Resets the end-of-conversion flag
-                #[inline(always)]
-                pub fn clear_end_of_conversion_flag(&mut self) {
-                    //defmt::error!("Clear!");
-                    self.adc_reg.isr.modify(|_, w| w.eoc().set_bit());
-                }
-
-                /// Block until the conversion is completed and return to configured
-                pub fn wait_for_conversion_sequence(&mut self) {
-                    while !self.adc_reg.isr.read().eoc().bit_is_set() {}
-                }
+                /// Synchronously convert every channel of `sequence` once, in order, into `results`
+                /// Note that it reconfigures the adc sequence and doesn't restore it
+                ///
+                /// Panics if `results` is shorter than the sequence
+                pub fn convert_sequence(&mut self, sequence: &SequenceBuilder<stm32::$adc_type>, results: &mut [u16]) {
+                    let saved_config = self.config;
+                    self.adc_reg.cfgr.modify(|_, w| w
+                        .dmaen().clear_bit() //Disable dma
+                        .cont().clear_bit() //Disable continuous mode
+                        .exten().bits(config::TriggerMode::Disabled.into()) //Disable trigger
+                    );
+                    self.adc_reg.ier.modify(|_, w| w
+                        .eocie().clear_bit() //Disable end of conversion interrupt
+                    );
+
+                    self.enable();
+                    self.read_sequence(sequence, results);
+                    self.disable();
+
+                    //Reset the config
+                    self.apply_config(saved_config);
+                }
+
+                /// Runs `sequence` once and reads every result as soon as it is ready
+                ///
+                /// The sequence is converted once in single, software triggered mode without DMA
+                /// and the end of conversion interrupt, whatever the configuration. Auto delay is
+                /// enabled during the sequence, so the next conversion only starts once the
+                /// previous result has been read and none can be lost to an overrun. The
+                /// configuration registers are restored afterwards.
+                fn read_sequence(&mut self, sequence: &SequenceBuilder<stm32::$adc_type>, results: &mut [u16]) {
+                    let len = usize::from(sequence.len());
+                    assert!(results.len() >= len, "Result buffer is shorter than the sequence");
+
+                    let cfgr = self.adc_reg.cfgr.read().bits();
+                    let ier = self.adc_reg.ier.read().bits();
+                    self.set_sequence(sequence);
+                    self.adc_reg.cfgr.modify(|_, w| w
+                        .dmaen().clear_bit() //Disable dma
+                        .cont().clear_bit() //Disable continuous mode
+                        .discen().clear_bit() //Disable discontinuous mode
+                        .exten().bits(config::TriggerMode::Disabled.into()) //Disable trigger
+                        .autdly().set_bit()
+                    );
+                    self.adc_reg.ier.modify(|_, w| w
+                        .eocie().clear_bit() //Disable end of conversion interrupt
+                    );
+                    self.clear_end_of_conversion_flag();
+                    self.start_conversion();
+
+                    for result in &mut results[..len] {
+                        self.wait_for_conversion_sequence();
+                        // Reading the data register clears the end of conversion flag
+                        *result = self.current_sample();
+                    }
+
+                    while self.is_conversion_active() {}
+
+                    // SAFETY: the previous register values are restored
+                    self.adc_reg.cfgr.write(|w| unsafe { w.bits(cfgr) });
+                    self.adc_reg.ier.write(|w| unsafe { w.bits(ier) });
+                }
+
+                /// Scale a value in counts at the configured resolution to 12 bits
+                fn counts_to_12bit(&self, counts: u16) -> u16 {
+                    let shift = match self.config.resolution {
+                        config::Resolution::Twelve => 0,
+                        config::Resolution::Ten => 2,
+                        config::Resolution::Eight => 4,
+                        config::Resolution::Six => 6,
+                    };
+                    counts << shift
+                }
+
+                /// Configure and enable analog watchdog 1 to guard `channel`, or all regular
+                /// channels if `None`
+                ///
+                /// `low` and `high` are in counts at the configured resolution. The watchdog flag
+                /// is set when a conversion is outside of `low..=high`.
+                ///
+                /// NOTE: The channel selection can only be changed while no conversion is ongoing,
+                /// use `set_watchdog_thresholds` to change the thresholds at any time
+                pub fn set_watchdog1(&mut self, channel: Option<u8>, low: u16, high: u16) {
+                    assert!(!self.is_conversion_active());
+                    self.set_watchdog_thresholds(AnalogWatchdog::Awd1, low, high);
+                    self.adc_reg.cfgr.modify(|_, w| unsafe {
+                        w.awd1ch()
+                            .bits(channel.unwrap_or(0))
+                            .awd1sgl()
+                            .bit(channel.is_some())
+                            .awd1en()
+                            .set_bit()
+                    });
+                }
+
+                /// Configure and enable analog watchdog 2 to guard the channels in `channels`,
+                /// bit n for channel n
+                ///
+                /// `low` and `high` are in counts at the configured resolution, this watchdog only
+                /// compares the 8 most significant bits of the 12 bit range.
+                ///
+                /// NOTE: The channel selection can only be changed while no conversion is ongoing
+                pub fn set_watchdog2(&mut self, channels: u32, low: u16, high: u16) {
+                    assert!(!self.is_conversion_active());
+                    self.set_watchdog_thresholds(AnalogWatchdog::Awd2, low, high);
+                    self.adc_reg.awd2cr.write(|w| unsafe { w.bits(channels) });
+                }
+
+                /// Configure and enable analog watchdog 3, see `set_watchdog2`
+                pub fn set_watchdog3(&mut self, channels: u32, low: u16, high: u16) {
+                    assert!(!self.is_conversion_active());
+                    self.set_watchdog_thresholds(AnalogWatchdog::Awd3, low, high);
+                    self.adc_reg.awd3cr.write(|w| unsafe { w.bits(channels) });
+                }
+
+                /// Disable an analog watchdog
+                pub fn disable_watchdog(&mut self, watchdog: AnalogWatchdog) {
+                    assert!(!self.is_conversion_active());
+                    match watchdog {
+                        AnalogWatchdog::Awd1 => self.adc_reg.cfgr.modify(|_, w| w.awd1en().clear_bit()),
+                        AnalogWatchdog::Awd2 => self.adc_reg.awd2cr.reset(),
+                        AnalogWatchdog::Awd3 => self.adc_reg.awd3cr.reset(),
+                    }
+                }
+
+                /// Set the thresholds of an analog watchdog, in counts at the configured resolution
+                ///
+                /// This may be done while conversions are ongoing, the new thresholds are used
+                /// from the next conversion.
+                pub fn set_watchdog_thresholds(&mut self, watchdog: AnalogWatchdog, low: u16, high: u16) {
+                    let low = self.counts_to_12bit(low);
+                    let high = self.counts_to_12bit(high);
+                    match watchdog {
+                        AnalogWatchdog::Awd1 => self.adc_reg.tr1.modify(|_, w| {
+                            w.lt1().bits(low).ht1().bits(high)
+                        }),
+                        AnalogWatchdog::Awd2 => self.adc_reg.tr2.modify(|_, w| unsafe {
+                            w.lt2().bits((low >> 4) as u8).ht2().bits((high >> 4) as u8)
+                        }),
+                        AnalogWatchdog::Awd3 => self.adc_reg.tr3.modify(|_, w| unsafe {
+                            w.lt3().bits((low >> 4) as u8).ht3().bits((high >> 4) as u8)
+                        }),
+                    }
+                }
+
+                /// Enable/disable the interrupt of an analog watchdog
+                pub fn set_watchdog_interrupt(&mut self, watchdog: AnalogWatchdog, enable: bool) {
+                    match watchdog {
+                        AnalogWatchdog::Awd1 => self.adc_reg.ier.modify(|_, w| w.awd1ie().bit(enable)),
+                        AnalogWatchdog::Awd2 => self.adc_reg.ier.modify(|_, w| w.awd2ie().bit(enable)),
+                        AnalogWatchdog::Awd3 => self.adc_reg.ier.modify(|_, w| w.awd3ie().bit(enable)),
+                    }
+                }
+
+                /// Returns `true` if the analog watchdog has seen a conversion outside of its
+                /// thresholds since its flag was last cleared
+                pub fn is_watchdog_flag_set(&self, watchdog: AnalogWatchdog) -> bool {
+                    let isr = self.adc_reg.isr.read();
+                    match watchdog {
+                        AnalogWatchdog::Awd1 => isr.awd1().bit_is_set(),
+                        AnalogWatchdog::Awd2 => isr.awd2().bit_is_set(),
+                        AnalogWatchdog::Awd3 => isr.awd3().bit_is_set(),
+                    }
+                }
+
+                /// Clear the flag of an analog watchdog
+                pub fn clear_watchdog_flag(&mut self, watchdog: AnalogWatchdog) {
+                    match watchdog {
+                        AnalogWatchdog::Awd1 => self.adc_reg.isr.write(|w| w.awd1().set_bit()),
+                        AnalogWatchdog::Awd2 => self.adc_reg.isr.write(|w| w.awd2().set_bit()),
+                        AnalogWatchdog::Awd3 => self.adc_reg.isr.write(|w| w.awd3().set_bit()),
+                    }
+                }
+
+                /// Configure offset compensation `slot` for `channel`
+                ///
+                /// `offset` is in counts at the configured resolution. The offset is applied to
+                /// the result after oversampling, and before the result is compared by the analog
+                /// watchdogs. If `saturate` is `false` a negative result is sign extended, so the
+                /// sample can be read as an `i16`. With `saturate` the result is clamped to
+                /// `0..=max_sample` instead.
+                ///
+                /// NOTE: The gain compensation, see `set_gain_compensation`, is applied after the
+                /// offset compensation, see the data management chapter of RM0440.
+                ///
+                /// Panics if a conversion is ongoing or `offset` does not fit the resolution
+                pub fn set_offset<CHANNEL>(&mut self, slot: OffsetSlot, _channel: &CHANNEL, offset: u16, saturate: bool, sign: OffsetSign)
+                where
+                    CHANNEL: Channel<stm32::$adc_type, ID=u8>
+                {
+                    let cr = self.adc_reg.cr.read();
+                    assert!(cr.adstart().bit_is_clear());
+                    assert!(cr.jadstart().bit_is_clear());
+                    assert!(u32::from(offset) <= self.config.resolution.to_max_sample());
+
+                    let channel = CHANNEL::channel();
+                    let offset = self.counts_to_12bit(offset);
+                    let positive = sign == OffsetSign::Positive;
+                    match slot {
+                        OffsetSlot::Offset1 => self.adc_reg.ofr1.write(|w| w
+                            .offset1().bits(offset).offset1_ch().bits(channel)
+                            .saten().bit(saturate).offsetpos().bit(positive)
+                            .offset1_en().set_bit()),
+                        OffsetSlot::Offset2 => self.adc_reg.ofr2.write(|w| w
+                            .offset2().bits(offset).offset2_ch().bits(channel)
+                            .saten().bit(saturate).offsetpos().bit(positive)
+                            .offset2_en().set_bit()),
+                        OffsetSlot::Offset3 => self.adc_reg.ofr3.write(|w| w
+                            .offset3().bits(offset).offset3_ch().bits(channel)
+                            .saten().bit(saturate).offsetpos().bit(positive)
+                            .offset3_en().set_bit()),
+                        OffsetSlot::Offset4 => self.adc_reg.ofr4.write(|w| w
+                            .offset4().bits(offset).offset4_ch().bits(channel)
+                            .saten().bit(saturate).offsetpos().bit(positive)
+                            .offset4_en().set_bit()),
+                    }
+                }
+
+                /// Disable offset compensation `slot`
+                ///
+                /// Panics if a conversion is ongoing
+                pub fn disable_offset(&mut self, slot: OffsetSlot) {
+                    let cr = self.adc_reg.cr.read();
+                    assert!(cr.adstart().bit_is_clear());
+                    assert!(cr.jadstart().bit_is_clear());
+
+                    match slot {
+                        OffsetSlot::Offset1 => self.adc_reg.ofr1.modify(|_, w| w.offset1_en().clear_bit()),
+                        OffsetSlot::Offset2 => self.adc_reg.ofr2.modify(|_, w| w.offset2_en().clear_bit()),
+                        OffsetSlot::Offset3 => self.adc_reg.ofr3.modify(|_, w| w.offset3_en().clear_bit()),
+                        OffsetSlot::Offset4 => self.adc_reg.ofr4.modify(|_, w| w.offset4_en().clear_bit()),
+                    }
+                }
+
+                /// Enable gain compensation of all channels with the 2.12 fixed point
+                /// coefficient `coeff`, `4096` being a gain of 1.0
+                ///
+                /// See [`gain_compensation_coefficient`] to compute the coefficient.
+                ///
+                /// Panics if a conversion is ongoing or `coeff` does not fit in 14 bits
+                pub fn set_gain_compensation(&mut self, coeff: u16) {
+                    let cr = self.adc_reg.cr.read();
+                    assert!(cr.adstart().bit_is_clear());
+                    assert!(cr.jadstart().bit_is_clear());
+                    assert!(coeff < (1 << 14));
+
+                    // SAFETY: checked above that coeff fits in the field
+                    self.adc_reg.gcomp.write(|w| unsafe { w.gcompcoeff().bits(coeff) });
+                    self.adc_reg.cfgr2.modify(|_, w| w.gcomp().set_bit());
+                }
+
+                /// Disable gain compensation
+                ///
+                /// Panics if a conversion is ongoing
+                pub fn disable_gain_compensation(&mut self) {
+                    let cr = self.adc_reg.cr.read();
+                    assert!(cr.adstart().bit_is_clear());
+                    assert!(cr.jadstart().bit_is_clear());
+
+                    self.adc_reg.cfgr2.modify(|_, w| w.gcomp().clear_bit());
+                }
+
+                /// Returns the gain compensation coefficient if gain compensation is enabled
+                pub fn gain_compensation(&self) -> Option<u16> {
+                    if self.adc_reg.cfgr2.read().gcomp().bit_is_set() {
+                        Some(self.adc_reg.gcomp.read().gcompcoeff().bits())
+                    } else {
+                        None
+                    }
+                }
+
+                /// Resets the end-of-conversion flag
+                #[inline(always)]
+                pub fn clear_end_of_conversion_flag(&mut self) {
+                    //defmt::error!("Clear!");
+                    self.adc_reg.isr.modify(|_, w| w.eoc().set_bit());
+                }
+
+                /// Block until the conversion is completed and return to configured
+                pub fn wait_for_conversion_sequence(&mut self) {
+                    while !self.adc_reg.isr.read().eoc().bit_is_set() {}
+                }
 
                 /// get current sample
                 #[inline(always)]
@@ -1977,7 +3391,7 @@ macro_rules! adc {
                     common.ccr.modify(|_, w| w.vbatsel().set_bit());
                 }
 
-                /// Enables the vbat internal channel
+                /// Disables the vbat internal channel
                 #[inline(always)]
                 pub fn disable_vbat(&self, common: &stm32::$common_type) {
                     common.ccr.modify(|_, w| w.vbatsel().clear_bit());
@@ -2060,6 +3474,8 @@ macro_rules! adc {
                         config: config::AdcConfig::default(),
                         adc_reg: self,
                         calibrated_vdda: VDDA_CALIB,
+                        clock_source: cs,
+                        clocks: rcc.clocks,
                     };
 
                     let adc: Adc::<stm32::$adc_type, PoweredDown> = Adc {
@@ -2071,10 +3487,13 @@ macro_rules! adc {
                 }
 
                 /// claims and configures the Adc
+                ///
+                /// Panics if `config` results in an ADC clock above 60MHz
                 #[inline(always)]
                 fn claim_and_configure(self, cs: ClockSource, rcc: &Rcc, config: config::AdcConfig<$trigger_type>, delay: &mut impl DelayUs<u8>, reset :bool) -> Adc<stm32::$adc_type, Configured> {
                     let mut adc = self.claim(cs, rcc, delay, reset);
                     adc.adc.config = config;
+                    adc.adc.check_clock_frequency();
 
                     // If the user specified a VDDA, use that over the internally determined value.
                     if let Some(vdda) = config.vdda {
@@ -2091,10 +3510,182 @@ macro_rules! adc {
                 pub fn sample_to_millivolts(&self, sample: u16) -> u16 {
                     self.adc.sample_to_millivolts(sample)
                 }
+
+                /// Converts a left aligned sample to a right aligned one, see
+                /// `DynamicAdc::sample_to_right_aligned`
+                #[inline(always)]
+                pub fn sample_to_right_aligned(&self, sample: u16) -> u16 {
+                    self.adc.sample_to_right_aligned(sample)
+                }
+
+                /// Sets the VDDA, in millivolts, used by `sample_to_millivolts`
+                ///
+                /// Use `Vref::sample_to_vdda` to measure it.
+                #[inline(always)]
+                pub fn set_reference_voltage(&mut self, vdda_mv: u32) {
+                    self.adc.set_reference_voltage(vdda_mv)
+                }
+
+                /// Starts listening for an `event`, see `DynamicAdc::listen`
+                #[inline(always)]
+                pub fn listen(&mut self, event: Event) {
+                    self.adc.listen(event)
+                }
+
+                /// Stops listening for an `event`
+                #[inline(always)]
+                pub fn unlisten(&mut self, event: Event) {
+                    self.adc.unlisten(event)
+                }
+
+                /// Returns `true` if the flag of `event` is set
+                #[inline(always)]
+                pub fn is_pending(&self, event: Event) -> bool {
+                    self.adc.is_pending(event)
+                }
+
+                /// Clears the flag of `event`
+                #[inline(always)]
+                pub fn clear_interrupt(&mut self, event: Event) {
+                    self.adc.clear_interrupt(event)
+                }
+
+                /// Returns the VDDA, in millivolts, used by `sample_to_millivolts`
+                #[inline(always)]
+                pub fn reference_voltage(&self) -> u32 {
+                    self.adc.reference_voltage()
+                }
+
+                /// Returns the frequency of the ADC clock, see `DynamicAdc::clock_frequency`
+                #[inline(always)]
+                pub fn clock_frequency(&self) -> Hertz {
+                    self.adc.clock_frequency()
+                }
+
+                /// Returns the sample time currently configured for `channel`
+                #[inline(always)]
+                pub fn channel_sample_time<CHANNEL>(&self, channel: &CHANNEL) -> config::SampleTime
+                where
+                    CHANNEL: Channel<stm32::$adc_type, ID=u8>
+                {
+                    self.adc.channel_sample_time(channel)
+                }
+
+                /// Converts a right aligned differential sample to a signed value, see
+                /// `DynamicAdc::sample_to_signed`
+                #[inline(always)]
+                pub fn sample_to_signed(&self, sample: u16) -> i16 {
+                    self.adc.sample_to_signed(sample)
+                }
+
+                /// Configure and enable analog watchdog 1, see `DynamicAdc::set_watchdog1`
+                ///
+                /// Panics if a conversion is ongoing.
+                #[inline(always)]
+                pub fn set_watchdog1(&mut self, channel: Option<u8>, low: u16, high: u16) {
+                    self.adc.set_watchdog1(channel, low, high)
+                }
+
+                /// Configure and enable analog watchdog 2, see `DynamicAdc::set_watchdog2`
+                ///
+                /// Panics if a conversion is ongoing.
+                #[inline(always)]
+                pub fn set_watchdog2(&mut self, channels: u32, low: u16, high: u16) {
+                    self.adc.set_watchdog2(channels, low, high)
+                }
+
+                /// Configure and enable analog watchdog 3, see `DynamicAdc::set_watchdog2`
+                ///
+                /// Panics if a conversion is ongoing.
+                #[inline(always)]
+                pub fn set_watchdog3(&mut self, channels: u32, low: u16, high: u16) {
+                    self.adc.set_watchdog3(channels, low, high)
+                }
+
+                /// Disable an analog watchdog
+                ///
+                /// Panics if a conversion is ongoing.
+                #[inline(always)]
+                pub fn disable_watchdog(&mut self, watchdog: AnalogWatchdog) {
+                    self.adc.disable_watchdog(watchdog)
+                }
+
+                /// Set the thresholds of an analog watchdog, see `DynamicAdc::set_watchdog_thresholds`
+                ///
+                /// This may be done while conversions are ongoing.
+                #[inline(always)]
+                pub fn set_watchdog_thresholds(&mut self, watchdog: AnalogWatchdog, low: u16, high: u16) {
+                    self.adc.set_watchdog_thresholds(watchdog, low, high)
+                }
+
+                /// Enable/disable the interrupt of an analog watchdog
+                #[inline(always)]
+                pub fn set_watchdog_interrupt(&mut self, watchdog: AnalogWatchdog, enable: bool) {
+                    self.adc.set_watchdog_interrupt(watchdog, enable)
+                }
+
+                /// Returns `true` if the analog watchdog flag is set
+                #[inline(always)]
+                pub fn is_watchdog_flag_set(&self, watchdog: AnalogWatchdog) -> bool {
+                    self.adc.is_watchdog_flag_set(watchdog)
+                }
+
+                /// Clear the flag of an analog watchdog
+                #[inline(always)]
+                pub fn clear_watchdog_flag(&mut self, watchdog: AnalogWatchdog) {
+                    self.adc.clear_watchdog_flag(watchdog)
+                }
+
+                /// Configure offset compensation `slot` for `channel`, see `DynamicAdc::set_offset`
+                ///
+                /// Panics if a conversion is ongoing.
+                #[inline(always)]
+                pub fn set_offset<CHANNEL>(&mut self, slot: OffsetSlot, channel: &CHANNEL, offset: u16, saturate: bool, sign: OffsetSign)
+                where
+                    CHANNEL: Channel<stm32::$adc_type, ID=u8>
+                {
+                    self.adc.set_offset(slot, channel, offset, saturate, sign)
+                }
+
+                /// Disable offset compensation `slot`
+                ///
+                /// Panics if a conversion is ongoing.
+                #[inline(always)]
+                pub fn disable_offset(&mut self, slot: OffsetSlot) {
+                    self.adc.disable_offset(slot)
+                }
+
+                /// Enable gain compensation, see `DynamicAdc::set_gain_compensation`
+                ///
+                /// Panics if a conversion is ongoing.
+                #[inline(always)]
+                pub fn set_gain_compensation(&mut self, coeff: u16) {
+                    self.adc.set_gain_compensation(coeff)
+                }
+
+                /// Disable gain compensation
+                ///
+                /// Panics if a conversion is ongoing.
+                #[inline(always)]
+                pub fn disable_gain_compensation(&mut self) {
+                    self.adc.disable_gain_compensation()
+                }
+
+                /// Returns the gain compensation coefficient if gain compensation is enabled
+                #[inline(always)]
+                pub fn gain_compensation(&self) -> Option<u16> {
+                    self.adc.gain_compensation()
+                }
             }
 
             impl Adc<stm32::$adc_type, PoweredDown> {
                 /// Powers-up an powered-down Adc
+                ///
+                /// This waits 25us for the voltage regulator to start up (T_ADCVREG_STUP is
+                /// 20us). Both the sequence and the offsets are kept while powered down. The
+                /// adc is calibrated again when it is enabled, which takes another 2 * 116
+                /// ADC clock cycles (about 4us at 60MHz) plus the enable time. So expect a
+                /// total wake-up latency of around 30us.
                 #[inline(always)]
                 pub fn power_up(mut self, delay: &mut impl DelayUs<u8>) -> Adc<stm32::$adc_type, Disabled> {
                     self.adc.power_up(delay);
@@ -2105,7 +3696,7 @@ macro_rules! adc {
                     }
                 }
 
-                /// Puts a Disabled Adc into Powered Mode
+                /// Puts a Disabled Adc into Deep Power Down Mode
                 #[inline(always)]
                 pub fn power_down(mut adc: Adc<stm32::$adc_type, Disabled>) -> Self {
                     adc.adc.power_down();
@@ -2139,10 +3730,6 @@ macro_rules! adc {
                     if dynadc.is_enabled() {
                         dynadc.disable();
                     }
-                    if dynadc.is_deeppwd_enabled() {
-                        dynadc.disable_deeppwd_down();
-                    }
-
                     dynadc.power_down();
 
                     Adc {
@@ -2203,6 +3790,9 @@ macro_rules! adc {
                 }
 
                 /// Puts a disabled Adc into PoweredDown Mode
+                ///
+                /// Use [`Adc::disable`] to get a disabled Adc from a configured one. See
+                /// [`DynamicAdc::power_down`] for what is kept while powered down.
                 #[inline(always)]
                 pub fn power_down(mut self) -> Adc<stm32::$adc_type, PoweredDown> {
                     self.adc.power_down();
@@ -2243,12 +3833,31 @@ macro_rules! adc {
                     self.adc.set_external_trigger( (edge, extsel.into()) )
                 }
 
+                /// Sets which external trigger starts the injected group and if it is disabled,
+                /// rising, falling or both
+                #[inline(always)]
+                pub fn set_injected_external_trigger<T: Into<$injected_trigger_type>>(&mut self, (edge, jextsel): (config::TriggerMode, T)) {
+                    self.adc.set_injected_external_trigger( (edge, jextsel.into()) )
+                }
+
                 /// Sets auto delay to true or false
                 #[inline(always)]
                 pub fn set_auto_delay(&mut self, delay: bool) {
                     self.adc.set_auto_delay(delay)
                 }
 
+                /// Sets the overrun mode, see [`config::OverrunMode`]
+                #[inline(always)]
+                pub fn set_overrun_mode(&mut self, mode: config::OverrunMode) {
+                    self.adc.set_overrun_mode(mode)
+                }
+
+                /// Enables hardware oversampling, or disables it if `None`
+                #[inline(always)]
+                pub fn set_oversampling(&mut self, oversampling: Option<config::Oversampling>) {
+                    self.adc.set_oversampling(oversampling)
+                }
+
                 /// Enables and disables continuous mode
                 #[inline(always)]
                 pub fn set_continuous(&mut self, continuous: config::Continuous) {
@@ -2289,6 +3898,21 @@ macro_rules! adc {
                     self.adc.set_default_sample_time(sample_time)
                 }
 
+                /// Changes the sample time of `channel`, see `DynamicAdc::set_channel_sample_time`
+                #[inline(always)]
+                pub fn set_channel_sample_time<CHANNEL>(&mut self, channel: &CHANNEL, sample_time: config::SampleTime)
+                where
+                    CHANNEL: Channel<stm32::$adc_type, ID=u8>
+                {
+                    self.adc.set_channel_sample_time(channel, sample_time)
+                }
+
+                /// Sets SMPPLUS, see [`config::AdcConfig::sample_time_plus`]
+                #[inline(always)]
+                pub fn set_sample_time_plus(&mut self, enable: bool) {
+                    self.adc.set_sample_time_plus(enable)
+                }
+
                 /// Sets the differential selection per channel.
                 #[inline(always)]
                 pub fn set_channel_input_type(&mut self, df: config::DifferentialSelection) {
@@ -2321,6 +3945,7 @@ macro_rules! adc {
 
                 /// Configure a channel for sampling.
                 /// It will make sure the sequence is at least as long as the `sequence` provided.
+                /// Consider using `set_sequence` which makes sure the sequence has no gaps.
                 /// # Arguments
                 /// * `channel` - channel to configure
                 /// * `sequence` - where in the sequence to sample the channel. Also called rank in some STM docs/code
@@ -2334,6 +3959,23 @@ macro_rules! adc {
                     self.adc.configure_channel(channel, sequence, sample_time)
                 }
 
+                /// Programs the regular sequence of `sequence` in one go, see
+                /// `DynamicAdc::set_sequence`
+                #[inline(always)]
+                pub fn set_sequence(&mut self, sequence: &SequenceBuilder<stm32::$adc_type>) {
+                    self.adc.set_sequence(sequence)
+                }
+
+                /// Configure a differential channel pair for sampling, see
+                /// `DynamicAdc::configure_differential_channel`
+                #[inline(always)]
+                pub fn configure_differential_channel<P, N>(&mut self, pair: &Differential<P, N>, sequence: config::Sequence, sample_time: config::SampleTime)
+                where
+                    Differential<P, N>: DifferentialPair<stm32::$adc_type>,
+                {
+                    self.adc.configure_differential_channel(pair, sequence, sample_time)
+                }
+
                 /// Synchronously convert a single sample
                 /// Note that it reconfigures the adc sequence and doesn't restore it
                 #[inline(always)]
@@ -2343,6 +3985,17 @@ macro_rules! adc {
                 {
                     self.adc.convert(pin, sample_time)
                 }
+
+                /// Synchronously convert every channel of `sequence` once, in order, into `results`
+                /// Note that it reconfigures the adc sequence and doesn't restore it
+                ///
+                /// This is meant for occasional reads of a few channels without setting up DMA.
+                ///
+                /// Panics if `results` is shorter than the sequence
+                #[inline(always)]
+                pub fn convert_sequence(&mut self, sequence: &SequenceBuilder<stm32::$adc_type>, results: &mut [u16]) {
+                    self.adc.convert_sequence(sequence, results)
+                }
             }
 
             impl Adc<stm32::$adc_type, Configured> {
@@ -2377,6 +4030,86 @@ macro_rules! adc {
                     self.adc.current_sample()
                 }
 
+                /// Starts a single conversion of `channel` without waiting for the result
+                ///
+                /// Poll `is_conversion_done` or `listen` for `Event::EndOfConversion` and fetch the
+                /// result using `read_result`. The regular sequence is overwritten to only contain
+                /// `channel` and is not restored. The ADC should be configured for single,
+                /// software triggered conversions without DMA.
+                #[inline(always)]
+                pub fn start_conversion_of<PIN>(&mut self, channel: &PIN, sample_time: config::SampleTime)
+                where
+                    PIN: Channel<stm32::$adc_type, ID=u8>
+                {
+                    self.adc.reset_sequence();
+                    self.adc.configure_channel(channel, config::Sequence::One, sample_time);
+                    self.adc.clear_end_of_conversion_flag();
+                    self.adc.start_conversion();
+                }
+
+                /// Returns `true` if a conversion result is ready to be read
+                #[inline(always)]
+                pub fn is_conversion_done(&self) -> bool {
+                    self.adc.adc_reg.isr.read().eoc().bit_is_set()
+                }
+
+                /// Sets auto delay to true or false, see [`config::AdcConfig::auto_delay`]
+                ///
+                /// Panics if a conversion is ongoing
+                #[inline(always)]
+                pub fn set_auto_delay(&mut self, delay: bool) {
+                    self.adc.set_auto_delay(delay)
+                }
+
+                /// Sets the overrun mode, see [`config::OverrunMode`]
+                ///
+                /// Panics if a conversion is ongoing
+                #[inline(always)]
+                pub fn set_overrun_mode(&mut self, mode: config::OverrunMode) {
+                    self.adc.set_overrun_mode(mode)
+                }
+
+                /// Sets the sampling resolution
+                ///
+                /// Panics if a conversion is ongoing
+                #[inline(always)]
+                pub fn set_resolution(&mut self, resolution: config::Resolution) {
+                    self.adc.set_resolution(resolution)
+                }
+
+                /// Sets the DR register alignment to left or right
+                ///
+                /// Panics if a conversion is ongoing
+                #[inline(always)]
+                pub fn set_align(&mut self, align: config::Align) {
+                    self.adc.set_align(align)
+                }
+
+                /// Changes the sample time of `channel`, see `DynamicAdc::set_channel_sample_time`
+                ///
+                /// Panics if a conversion is ongoing
+                #[inline(always)]
+                pub fn set_channel_sample_time<CHANNEL>(&mut self, channel: &CHANNEL, sample_time: config::SampleTime)
+                where
+                    CHANNEL: Channel<stm32::$adc_type, ID=u8>
+                {
+                    self.adc.set_channel_sample_time(channel, sample_time)
+                }
+
+                /// Sets SMPPLUS, see [`config::AdcConfig::sample_time_plus`]
+                ///
+                /// Panics if a conversion is ongoing
+                #[inline(always)]
+                pub fn set_sample_time_plus(&mut self, enable: bool) {
+                    self.adc.set_sample_time_plus(enable)
+                }
+
+                /// Reads the result of the last conversion, which also clears the end of conversion flag
+                #[inline(always)]
+                pub fn read_result(&mut self) -> u16 {
+                    self.adc.current_sample()
+                }
+
                 /// Synchronously convert a single sample
                 /// Note that it reconfigures the adc sequence and doesn't restore it
                 #[inline(always)]
@@ -2393,6 +4126,61 @@ macro_rules! adc {
 
                     self.adc.current_sample()
                 }
+
+                /// Synchronously convert every channel of `sequence` once, in order, into `results`
+                /// Note that it reconfigures the adc sequence and doesn't restore it
+                ///
+                /// This is meant for occasional reads of a few channels without setting up DMA.
+                /// Continuous and discontinuous mode, DMA and the external trigger are suspended
+                /// while the sequence is converted.
+                ///
+                /// Panics if `results` is shorter than the sequence
+                pub fn convert_sequence(&mut self, sequence: &SequenceBuilder<stm32::$adc_type>, results: &mut [u16]) {
+                    self.adc.read_sequence(sequence, results)
+                }
+
+                /// Asynchronously convert a single sample
+                ///
+                /// This uses the end of conversion interrupt, so `DynamicAdc::on_interrupt` has to be
+                /// called from the ADC interrupt handler and the interrupt has to be unmasked in the
+                /// NVIC. Note that it reconfigures the adc sequence and doesn't restore it.
+                ///
+                /// The end of conversion interrupt is disabled again when the future completes or
+                /// is dropped, unless it was enabled through the configuration.
+                #[cfg(feature = "async")]
+                pub async fn convert_async<PIN>(&mut self, pin: &PIN, sample_time: config::SampleTime) -> u16
+                where
+                    PIN: Channel<stm32::$adc_type, ID=u8>
+                {
+                    self.adc.reset_sequence();
+                    self.adc.configure_channel(pin, config::Sequence::One, sample_time);
+                    self.adc.clear_interrupt(Event::EndOfConversion);
+                    self.adc.start_conversion();
+
+                    let _disarm = OnDrop(DynamicAdc::<stm32::$adc_type>::disarm);
+
+                    poll_fn(|cx| {
+                        cortex_m::interrupt::free(|cs| {
+                            let mut slot = DynamicAdc::<stm32::$adc_type>::waker().borrow(cs).borrow_mut();
+                            match slot.waker.as_ref() {
+                                Some(w) if w.will_wake(cx.waker()) => {}
+                                _ => slot.waker = Some(cx.waker().clone()),
+                            }
+
+                            if self.adc.is_pending(Event::EndOfConversion) {
+                                Poll::Ready(self.adc.current_sample())
+                            } else {
+                                if self.adc.adc_reg.ier.read().eocie().bit_is_clear() {
+                                    // Disabled again by `on_interrupt` or `disarm`
+                                    self.adc.listen(Event::EndOfConversion);
+                                    slot.armed = true;
+                                }
+                                Poll::Pending
+                            }
+                        })
+                    })
+                    .await
+                }
             }
 
             impl Conversion<stm32::$adc_type> {
@@ -2475,10 +4263,13 @@ macro_rules! adc {
                     self.adc.start_conversion()
                 }
 
-                /// Cancels an ongoing conversion
+                /// Cancels an ongoing conversion, does nothing if no conversion is ongoing
                 #[inline(always)]
                 pub fn cancel_conversion(&mut self) {
-                    self.adc.cancel_conversion()
+                    // ADSTP may only be set while ADSTART is set
+                    if self.adc.is_conversion_active() {
+                        self.adc.cancel_conversion()
+                    }
                 }
 
                 /// Stop the Adc
@@ -2487,6 +4278,27 @@ macro_rules! adc {
                     self.adc.disable()
                 }
 
+                /// Stops any ongoing conversion and clears DMAEN, without changing the type state
+                #[inline(always)]
+                pub(crate) fn stop_dma_requests(&mut self) {
+                    self.cancel_conversion();
+                    self.adc.set_dma(config::Dma::Disabled);
+                }
+
+                /// Stops any ongoing conversion, waits for it to stop and disables the DMA
+                /// requests, so the sequence can be reconfigured
+                ///
+                /// See `Transfer::abort` for stopping an in-flight DMA transfer.
+                #[inline(always)]
+                pub fn disable_dma(mut self) -> Adc<stm32::$adc_type, Configured> {
+                    self.stop_dma_requests();
+
+                    Adc {
+                        adc: self.adc,
+                        _status: PhantomData,
+                    }
+                }
+
                 /// Disable the Adc
                 #[inline(always)]
                 pub fn disable(mut self) -> Adc<stm32::$adc_type, Disabled> {
@@ -2510,6 +4322,14 @@ macro_rules! adc {
                 pub fn clear_overrun_flag(&mut self) {
                     self.adc.clear_overrun_flag();
                 }
+
+                /// Enable/disable overrun interrupt
+                ///
+                /// This is triggered when the AD finishes a conversion before the last value was read by the DMA
+                #[inline(always)]
+                pub fn set_overrun_interrupt(&mut self, enable: bool) {
+                    self.adc.set_overrun_interrupt(enable)
+                }
             }
 
             unsafe impl TargetAddress<PeripheralToMemory> for Adc<stm32::$adc_type, DMA> {
@@ -2548,7 +4368,7 @@ macro_rules! adc {
     feature = "stm32g491",
     feature = "stm32g4a1",
 ))]
-adc!(ADC1 => (ExternalTrigger12, configure_clock_source12, DmaMuxResources::ADC1, (ADC12_COMMON) ));
+adc!(ADC1 => (ExternalTrigger12, InjectedTrigger12, configure_clock_source12, DmaMuxResources::ADC1, (ADC12_COMMON) ));
 
 #[cfg(any(
     feature = "stm32g431",
@@ -2561,7 +4381,7 @@ adc!(ADC1 => (ExternalTrigger12, configure_clock_source12, DmaMuxResources::ADC1
     feature = "stm32g491",
     feature = "stm32g4a1",
 ))]
-adc!(ADC2 => (ExternalTrigger12, configure_clock_source12, DmaMuxResources::ADC2, (ADC12_COMMON) ));
+adc!(ADC2 => (ExternalTrigger12, InjectedTrigger12, configure_clock_source12, DmaMuxResources::ADC2, (ADC12_COMMON) ));
 
 #[cfg(any(
     feature = "stm32g471",
@@ -2572,7 +4392,7 @@ adc!(ADC2 => (ExternalTrigger12, configure_clock_source12, DmaMuxResources::ADC2
     feature = "stm32g491",
     feature = "stm32g4a1",
 ))]
-adc!(ADC3 => (ExternalTrigger345, configure_clock_source345, DmaMuxResources::ADC3, (ADC345_COMMON) ));
+adc!(ADC3 => (ExternalTrigger345, InjectedTrigger345, configure_clock_source345, DmaMuxResources::ADC3, (ADC345_COMMON) ));
 
 #[cfg(any(
     feature = "stm32g473",
@@ -2580,7 +4400,7 @@ adc!(ADC3 => (ExternalTrigger345, configure_clock_source345, DmaMuxResources::AD
     feature = "stm32g483",
     feature = "stm32g484",
 ))]
-adc!(ADC4 => (ExternalTrigger345, configure_clock_source345, DmaMuxResources::ADC4, (ADC345_COMMON) ));
+adc!(ADC4 => (ExternalTrigger345, InjectedTrigger345, configure_clock_source345, DmaMuxResources::ADC4, (ADC345_COMMON) ));
 
 #[cfg(any(
     feature = "stm32g473",
@@ -2588,7 +4408,159 @@ adc!(ADC4 => (ExternalTrigger345, configure_clock_source345, DmaMuxResources::AD
     feature = "stm32g483",
     feature = "stm32g484",
 ))]
-adc!(ADC5 => (ExternalTrigger345, configure_clock_source345, DmaMuxResources::ADC5, (ADC345_COMMON) ));
+adc!(ADC5 => (ExternalTrigger345, InjectedTrigger345, configure_clock_source345, DmaMuxResources::ADC5, (ADC345_COMMON) ));
+
+macro_rules! adc_dual {
+    ($master:ident, $slave:ident => ($common_type:ident, $mux:expr)) => {
+        impl DualAdcClaim<stm32::$master, stm32::$slave> for stm32::$common_type {
+            fn claim_dual(
+                &self,
+                master: Adc<stm32::$master, Disabled>,
+                slave: Adc<stm32::$slave, Disabled>,
+                dma: config::Dma,
+            ) -> DualAdc<stm32::$master, stm32::$slave> {
+                let dmacfg = match dma {
+                    config::Dma::Disabled => {
+                        panic!("Requesting Enabling DMA with DisableDma parameter")
+                    }
+                    config::Dma::Single => false,
+                    config::Dma::Continuous => true,
+                };
+
+                let mut master = master.adc;
+                let mut slave = slave.adc;
+
+                // Each pair in CDR is one sample of each ADC, so both have to convert the same
+                // number of channels
+                assert_eq!(
+                    master.sequence_length(),
+                    slave.sequence_length(),
+                    "master and slave sequences must have the same length"
+                );
+
+                // The DMA requests come from the common peripheral, not the individual ADCs
+                master.set_dma(config::Dma::Disabled);
+                slave.set_dma(config::Dma::Disabled);
+
+                // SAFETY: 0b00110 is regular simultaneous mode, 0b10 is MDMA for 12 and 10 bit data
+                // which also works for lower resolutions
+                self.ccr.modify(|_, w| unsafe {
+                    w.dual()
+                        .bits(0b00110)
+                        .mdma()
+                        .bits(0b10)
+                        .dmacfg()
+                        .bit(dmacfg)
+                });
+
+                slave.enable();
+                master.enable();
+
+                DualAdc { master, slave }
+            }
+        }
+
+        impl DualAdc<stm32::$master, stm32::$slave> {
+            /// Starts the conversion of both ADCs
+            #[inline(always)]
+            pub fn start_conversion(&mut self) {
+                self.master.start_conversion()
+            }
+
+            /// Cancels an ongoing conversion of both ADCs, does nothing if no conversion is ongoing
+            #[inline(always)]
+            pub fn cancel_conversion(&mut self) {
+                // ADSTP may only be set while ADSTART is set
+                if self.master.is_conversion_active() {
+                    self.master.cancel_conversion()
+                }
+            }
+
+            /// Returns if a conversion is active
+            #[inline(always)]
+            pub fn is_conversion_active(&self) -> bool {
+                self.master.is_conversion_active()
+            }
+
+            /// Read overrun flag of either ADC
+            #[inline(always)]
+            pub fn get_overrun_flag(&self) -> bool {
+                self.master.get_overrun_flag() || self.slave.get_overrun_flag()
+            }
+
+            /// Resets the overrun flags
+            #[inline(always)]
+            pub fn clear_overrun_flag(&mut self) {
+                self.master.clear_overrun_flag();
+                self.slave.clear_overrun_flag();
+            }
+
+            /// Disable both ADCs and return them to independent mode
+            pub fn release(
+                mut self,
+                common: &stm32::$common_type,
+            ) -> (Adc<stm32::$master, Disabled>, Adc<stm32::$slave, Disabled>) {
+                self.master.disable();
+                self.slave.disable();
+
+                // SAFETY: 0b00000 is independent mode and 0b00 disables MDMA
+                common.ccr.modify(|_, w| unsafe {
+                    w.dual()
+                        .bits(0b00000)
+                        .mdma()
+                        .bits(0b00)
+                        .dmacfg()
+                        .clear_bit()
+                });
+
+                (
+                    Adc {
+                        adc: self.master,
+                        _status: PhantomData,
+                    },
+                    Adc {
+                        adc: self.slave,
+                        _status: PhantomData,
+                    },
+                )
+            }
+        }
+
+        unsafe impl TargetAddress<PeripheralToMemory> for DualAdc<stm32::$master, stm32::$slave> {
+            #[inline(always)]
+            fn address(&self) -> u32 {
+                // NOTE(unsafe) only the address of the read only CDR register is used
+                let common = unsafe { &*stm32::$common_type::ptr() };
+                &common.cdr as *const _ as u32
+            }
+
+            type MemSize = DualSample;
+
+            const REQUEST_LINE: Option<u8> = Some($mux as u8);
+        }
+    };
+}
+
+#[cfg(any(
+    feature = "stm32g431",
+    feature = "stm32g441",
+    feature = "stm32g471",
+    feature = "stm32g473",
+    feature = "stm32g474",
+    feature = "stm32g483",
+    feature = "stm32g484",
+    feature = "stm32g491",
+    feature = "stm32g4a1",
+))]
+adc_dual!(ADC1, ADC2 => (ADC12_COMMON, DmaMuxResources::ADC1));
+
+#[cfg(any(
+    feature = "stm32g473",
+    feature = "stm32g474",
+    feature = "stm32g483",
+    feature = "stm32g484",
+))]
+adc_dual!(ADC3, ADC4 => (ADC345_COMMON, DmaMuxResources::ADC3));
 
 #[cfg(any(feature = "stm32g431", feature = "stm32g441", feature = "stm32g471",))]
 adc_pins!(
@@ -2630,6 +4602,35 @@ adc_pins!(
     Vref => (ADC1, 18),
 );
 
+#[cfg(any(feature = "stm32g431", feature = "stm32g441", feature = "stm32g471",))]
+adc_differential_pairs!(
+    (gpioa::PA0<Analog>, gpioa::PA1<Analog>) => ADC1,
+    (gpioa::PA1<Analog>, gpioa::PA2<Analog>) => ADC1,
+    (gpioa::PA2<Analog>, gpioa::PA3<Analog>) => ADC1,
+    (gpioc::PC0<Analog>, gpioc::PC1<Analog>) => ADC1,
+    (gpioc::PC1<Analog>, gpioc::PC2<Analog>) => ADC1,
+    (gpioc::PC2<Analog>, gpioc::PC3<Analog>) => ADC1,
+    (gpioc::PC3<Analog>, gpiof::PF0<Analog>) => ADC1,
+    (gpiof::PF0<Analog>, gpiob::PB12<Analog>) => ADC1,
+    (gpiob::PB12<Analog>, gpiob::PB1<Analog>) => ADC1,
+    (gpiob::PB11<Analog>, gpiob::PB0<Analog>) => ADC1,
+
+    (gpioa::PA0<Analog>, gpioa::PA1<Analog>) => ADC2,
+    (gpioa::PA1<Analog>, gpioa::PA6<Analog>) => ADC2,
+    (gpioa::PA6<Analog>, gpioa::PA7<Analog>) => ADC2,
+    (gpioa::PA7<Analog>, gpioc::PC4<Analog>) => ADC2,
+    (gpioc::PC4<Analog>, gpioc::PC0<Analog>) => ADC2,
+    (gpioc::PC0<Analog>, gpioc::PC1<Analog>) => ADC2,
+    (gpioc::PC1<Analog>, gpioc::PC2<Analog>) => ADC2,
+    (gpioc::PC2<Analog>, gpioc::PC3<Analog>) => ADC2,
+    (gpioc::PC3<Analog>, gpiof::PF1<Analog>) => ADC2,
+    (gpiof::PF1<Analog>, gpioc::PC5<Analog>) => ADC2,
+    (gpioc::PC5<Analog>, gpiob::PB2<Analog>) => ADC2,
+    (gpiob::PB2<Analog>, gpioa::PA5<Analog>) => ADC2,
+    (gpioa::PA5<Analog>, gpiob::PB11<Analog>) => ADC2,
+    (gpiob::PB11<Analog>, gpiob::PB15<Analog>) => ADC2,
+);
+
 #[cfg(any(
     feature = "stm32g473",
     feature = "stm32g474",
@@ -2727,42 +4728,104 @@ adc_pins!(
     Vref => (ADC5, 18),
 );
 
-// See https://www.st.com/resource/en/reference_manual/rm0440-stm32g4-series-advanced-armbased-32bit-mcus-stmicroelectronics.pdf#page=782
-adc_op_pga!(
-    // TODO: Add all opamp types: OpenLoop, Follower(for all opamps)
-    // TODO: Should we restrict type parameters A and B?
-    // TODO: Also allow AD-channels shared by pins
-    opamp::opamp1::Pga<A, B> => (ADC1, 13),
-    opamp::opamp2::Pga<A, B> => (ADC2, 16),
-
-    opamp::opamp3::Pga<A, B> => (ADC2, 18),
+#[cfg(any(
+    feature = "stm32g473",
+    feature = "stm32g474",
+    feature = "stm32g483",
+    feature = "stm32g484",
+))]
+adc_differential_pairs!(
+    (gpioa::PA0<Analog>, gpioa::PA1<Analog>) => ADC1,
+    (gpioa::PA1<Analog>, gpioa::PA2<Analog>) => ADC1,
+    (gpioa::PA2<Analog>, gpioa::PA3<Analog>) => ADC1,
+    (gpioa::PA3<Analog>, gpiob::PB14<Analog>) => ADC1,
+    (gpiob::PB14<Analog>, gpioc::PC0<Analog>) => ADC1,
+    (gpioc::PC0<Analog>, gpioc::PC1<Analog>) => ADC1,
+    (gpioc::PC1<Analog>, gpioc::PC2<Analog>) => ADC1,
+    (gpioc::PC2<Analog>, gpioc::PC3<Analog>) => ADC1,
+    (gpioc::PC3<Analog>, gpiof::PF0<Analog>) => ADC1,
+    (gpiof::PF0<Analog>, gpiob::PB12<Analog>) => ADC1,
+    (gpiob::PB12<Analog>, gpiob::PB1<Analog>) => ADC1,
+    (gpiob::PB11<Analog>, gpiob::PB0<Analog>) => ADC1,
+
+    (gpioa::PA0<Analog>, gpioa::PA1<Analog>) => ADC2,
+    (gpioa::PA1<Analog>, gpioa::PA6<Analog>) => ADC2,
+    (gpioa::PA6<Analog>, gpioa::PA7<Analog>) => ADC2,
+    (gpioa::PA7<Analog>, gpioc::PC4<Analog>) => ADC2,
+    (gpioc::PC4<Analog>, gpioc::PC0<Analog>) => ADC2,
+    (gpioc::PC0<Analog>, gpioc::PC1<Analog>) => ADC2,
+    (gpioc::PC1<Analog>, gpioc::PC2<Analog>) => ADC2,
+    (gpioc::PC2<Analog>, gpioc::PC3<Analog>) => ADC2,
+    (gpioc::PC3<Analog>, gpiof::PF1<Analog>) => ADC2,
+    (gpiof::PF1<Analog>, gpioc::PC5<Analog>) => ADC2,
+    (gpioc::PC5<Analog>, gpiob::PB2<Analog>) => ADC2,
+    (gpiob::PB2<Analog>, gpioa::PA5<Analog>) => ADC2,
+    (gpioa::PA5<Analog>, gpiob::PB11<Analog>) => ADC2,
+    (gpiob::PB11<Analog>, gpiob::PB15<Analog>) => ADC2,
+
+    (gpiob::PB1<Analog>, gpioe::PE9<Analog>) => ADC3,
+    (gpioe::PE9<Analog>, gpioe::PE13<Analog>) => ADC3,
+    (gpioe::PE13<Analog>, gpioe::PE7<Analog>) => ADC3,
+    (gpioe::PE7<Analog>, gpiob::PB13<Analog>) => ADC3,
+    (gpiob::PB13<Analog>, gpioe::PE8<Analog>) => ADC3,
+    (gpioe::PE8<Analog>, gpiod::PD10<Analog>) => ADC3,
+    (gpiod::PD10<Analog>, gpiod::PD11<Analog>) => ADC3,
+    (gpiod::PD11<Analog>, gpiod::PD12<Analog>) => ADC3,
+    (gpiod::PD12<Analog>, gpiod::PD13<Analog>) => ADC3,
+    (gpiod::PD13<Analog>, gpiod::PD14<Analog>) => ADC3,
+    (gpiod::PD14<Analog>, gpiob::PB0<Analog>) => ADC3,
+    (gpioe::PE10<Analog>, gpioe::PE11<Analog>) => ADC3,
+    (gpioe::PE11<Analog>, gpioe::PE12<Analog>) => ADC3,
+
+    (gpioe::PE14<Analog>, gpioe::PE15<Analog>) => ADC4,
+    (gpioe::PE15<Analog>, gpiob::PB12<Analog>) => ADC4,
+    (gpiob::PB12<Analog>, gpiob::PB14<Analog>) => ADC4,
+    (gpiob::PB14<Analog>, gpiob::PB15<Analog>) => ADC4,
+    (gpiob::PB15<Analog>, gpioe::PE8<Analog>) => ADC4,
+    (gpioe::PE8<Analog>, gpiod::PD10<Analog>) => ADC4,
+    (gpiod::PD10<Analog>, gpiod::PD11<Analog>) => ADC4,
+    (gpiod::PD11<Analog>, gpiod::PD12<Analog>) => ADC4,
+    (gpiod::PD12<Analog>, gpiod::PD13<Analog>) => ADC4,
+    (gpiod::PD13<Analog>, gpiod::PD14<Analog>) => ADC4,
+    (gpiod::PD14<Analog>, gpiod::PD8<Analog>) => ADC4,
+    (gpiod::PD8<Analog>, gpiod::PD9<Analog>) => ADC4,
+    (gpiod::PD9<Analog>, gpioe::PE10<Analog>) => ADC4,
+    (gpioe::PE10<Analog>, gpioe::PE11<Analog>) => ADC4,
+    (gpioe::PE11<Analog>, gpioe::PE12<Analog>) => ADC4,
+
+    (gpioa::PA8<Analog>, gpioa::PA9<Analog>) => ADC5,
+    (gpioe::PE8<Analog>, gpiod::PD10<Analog>) => ADC5,
+    (gpiod::PD10<Analog>, gpiod::PD11<Analog>) => ADC5,
+    (gpiod::PD11<Analog>, gpiod::PD12<Analog>) => ADC5,
+    (gpiod::PD12<Analog>, gpiod::PD13<Analog>) => ADC5,
+    (gpiod::PD13<Analog>, gpiod::PD14<Analog>) => ADC5,
+    (gpiod::PD14<Analog>, gpiod::PD8<Analog>) => ADC5,
+    (gpiod::PD8<Analog>, gpiod::PD9<Analog>) => ADC5,
+    (gpiod::PD9<Analog>, gpioe::PE10<Analog>) => ADC5,
+    (gpioe::PE10<Analog>, gpioe::PE11<Analog>) => ADC5,
+    (gpioe::PE11<Analog>, gpioe::PE12<Analog>) => ADC5,
 );
 
-adc_op_follower!(
-    opamp::opamp1::Follower<A> => (ADC1, 13),
-    opamp::opamp2::Follower<A> => (ADC2, 16),
-
-    opamp::opamp3::Follower<A> => (ADC2, 18),
+// See https://www.st.com/resource/en/reference_manual/rm0440-stm32g4-series-advanced-armbased-32bit-mcus-stmicroelectronics.pdf#page=782
+adc_opamp!(
+    // TODO: Also allow AD-channels shared by pins
+    opamp1 => (ADC1, 13),
+    opamp2 => (ADC2, 16),
+    opamp3 => (ADC2, 18),
 );
 
+// The opamp driver only provides opamp 4-6 for these parts
 #[cfg(any(
     feature = "stm32g473",
     feature = "stm32g474",
     feature = "stm32g483",
     feature = "stm32g484",
-    feature = "stm32g491",
-    feature = "stm32g4a1",
 ))]
-adc_op_pga!(
-    opamp::opamp3::Pga<A, B> => (ADC3, 13),
-    opamp::opamp4::Pga<A, B> => (ADC5, 5),
-    opamp::opamp5::Pga<A, B> => (ADC5, 3),
-    opamp::opamp6::Pga<A, B> => (ADC4, 17),
-);
-
-#[cfg(any(feature = "stm32g491", feature = "stm32g4a1",))]
-adc_op_pga!(
-    opamp::opamp6::Pga<A, B> => (ADC3, 17),
+adc_opamp!(
+    opamp3 => (ADC3, 13),
+    opamp4 => (ADC5, 5),
+    opamp5 => (ADC5, 3),
+    opamp6 => (ADC4, 17),
 );
 
 #[cfg(any(feature = "stm32g491", feature = "stm32g4a1",))]
@@ -2824,3 +4887,78 @@ adc_pins!(
     Vref => (ADC1, 18),
     Vref => (ADC3, 18),
 );
+
+#[cfg(any(feature = "stm32g491", feature = "stm32g4a1",))]
+adc_differential_pairs!(
+    (gpioa::PA0<Analog>, gpioa::PA1<Analog>) => ADC1,
+    (gpioa::PA1<Analog>, gpioa::PA2<Analog>) => ADC1,
+    (gpioa::PA2<Analog>, gpioa::PA3<Analog>) => ADC1,
+    (gpioa::PA3<Analog>, gpiob::PB14<Analog>) => ADC1,
+    (gpiob::PB14<Analog>, gpioc::PC0<Analog>) => ADC1,
+    (gpioc::PC0<Analog>, gpioc::PC1<Analog>) => ADC1,
+    (gpioc::PC1<Analog>, gpioc::PC2<Analog>) => ADC1,
+    (gpioc::PC2<Analog>, gpioc::PC3<Analog>) => ADC1,
+    (gpioc::PC3<Analog>, gpiof::PF0<Analog>) => ADC1,
+    (gpiof::PF0<Analog>, gpiob::PB12<Analog>) => ADC1,
+    (gpiob::PB12<Analog>, gpiob::PB1<Analog>) => ADC1,
+    (gpiob::PB11<Analog>, gpiob::PB0<Analog>) => ADC1,
+
+    (gpioa::PA0<Analog>, gpioa::PA1<Analog>) => ADC2,
+    (gpioa::PA1<Analog>, gpioa::PA6<Analog>) => ADC2,
+    (gpioa::PA6<Analog>, gpioa::PA7<Analog>) => ADC2,
+    (gpioa::PA7<Analog>, gpioc::PC4<Analog>) => ADC2,
+    (gpioc::PC4<Analog>, gpioc::PC0<Analog>) => ADC2,
+    (gpioc::PC0<Analog>, gpioc::PC1<Analog>) => ADC2,
+    (gpioc::PC1<Analog>, gpioc::PC2<Analog>) => ADC2,
+    (gpioc::PC2<Analog>, gpioc::PC3<Analog>) => ADC2,
+    (gpioc::PC3<Analog>, gpiof::PF1<Analog>) => ADC2,
+    (gpiof::PF1<Analog>, gpioc::PC5<Analog>) => ADC2,
+    (gpioc::PC5<Analog>, gpiob::PB2<Analog>) => ADC2,
+    (gpiob::PB2<Analog>, gpioa::PA5<Analog>) => ADC2,
+    (gpioa::PA5<Analog>, gpiob::PB11<Analog>) => ADC2,
+    (gpiob::PB11<Analog>, gpiob::PB15<Analog>) => ADC2,
+
+    (gpiob::PB1<Analog>, gpioe::PE9<Analog>) => ADC3,
+    (gpioe::PE9<Analog>, gpioe::PE13<Analog>) => ADC3,
+    (gpioe::PE13<Analog>, gpioe::PE7<Analog>) => ADC3,
+    (gpioe::PE7<Analog>, gpiob::PB13<Analog>) => ADC3,
+    (gpiob::PB13<Analog>, gpioe::PE8<Analog>) => ADC3,
+    (gpioe::PE8<Analog>, gpiod::PD10<Analog>) => ADC3,
+    (gpiod::PD10<Analog>, gpiod::PD11<Analog>) => ADC3,
+    (gpiod::PD11<Analog>, gpiod::PD12<Analog>) => ADC3,
+    (gpiod::PD12<Analog>, gpiod::PD13<Analog>) => ADC3,
+    (gpiod::PD13<Analog>, gpiod::PD14<Analog>) => ADC3,
+    (gpiod::PD14<Analog>, gpiob::PB0<Analog>) => ADC3,
+    (gpioe::PE10<Analog>, gpioe::PE11<Analog>) => ADC3,
+    (gpioe::PE11<Analog>, gpioe::PE12<Analog>) => ADC3,
+);
+
+#[cfg(test)]
+mod tests {
+    use super::config::*;
+    use super::gain_compensation_coefficient;
+
+    #[test]
+    fn gain_compensation() {
+        assert_eq!(gain_compensation_coefficient(1000, 1000), Some(4096));
+        assert_eq!(gain_compensation_coefficient(2000, 1000), Some(2048));
+        assert_eq!(gain_compensation_coefficient(0, 1000), None);
+        assert_eq!(gain_compensation_coefficient(1000, 4000), None);
+    }
+
+    #[test]
+    fn six_bit_samples() {
+        let right = AdcConfig::<ExternalTrigger12>::default().resolution(Resolution::Six);
+        let left = right.align(Align::Left);
+
+        // Left aligned 6 bit samples occupy bits 7:2
+        assert_eq!(left.sample_to_right_aligned(63 << 2), 63);
+        assert_eq!(right.sample_to_right_aligned(63), 63);
+
+        assert_eq!(left.sample_to_millivolts(63 << 2, 3300), 3300);
+        assert_eq!(right.sample_to_millivolts(63, 3300), 3300);
+        assert_eq!(left.sample_to_millivolts(32 << 2, 3300), 1676);
+        assert_eq!(right.sample_to_millivolts(32, 3300), 1676);
+        assert_eq!(left.sample_to_millivolts(0, 3300), 0);
+    }
+}
@@ -5,16 +5,25 @@
 //! This code has been taken from the stm32g0xx-hal project and modified slightly to support
 //! STM32G4xx MCUs.
 
+#[cfg(feature = "async")]
+use core::cell::RefCell;
+#[cfg(feature = "async")]
+use core::future::poll_fn;
 use core::marker::PhantomData;
+#[cfg(feature = "async")]
+use core::task::{Poll, Waker};
+
+#[cfg(feature = "async")]
+use cortex_m::interrupt::Mutex;
 
 use crate::dac;
-use crate::exti::{Event as ExtiEvent, ExtiExt};
+use crate::exti::Event as ExtiEvent;
 use crate::gpio::{
     gpioa::{PA0, PA1, PA11, PA12, PA2, PA3, PA4, PA5, PA6, PA7},
     gpiob::{PB0, PB1, PB14, PB15, PB2, PB6, PB7, PB8, PB9},
     gpioc::PC2,
     gpiof::PF4,
-    Analog, OpenDrain, Output, PushPull, SignalEdge, AF2, AF3, AF8,
+    Alternate, AlternateOD, Analog, OpenDrain, Output, PushPull, SignalEdge, AF2, AF3, AF8,
 };
 
 #[cfg(any(
@@ -34,6 +43,7 @@ use crate::gpio::{
 use crate::gpio::gpioc::{PC0, PC1};
 use crate::gpio::gpioe::{PE7, PE8};
 use crate::gpio::gpiof::PF1;
+use crate::pwm::Polarity;
 use crate::rcc::{Clocks, Rcc};
 use crate::stm32::{COMP, EXTI};
 
@@ -54,6 +64,81 @@ pub trait EnabledState {}
 impl EnabledState for Enabled {}
 impl EnabledState for Locked {}
 
+type ExtiRegisters = crate::stm32::exti::RegisterBlock;
+
+/// Set the edge triggers and interrupt mask of the EXTI line of a comparator
+///
+/// Unlike `ExtiExt::listen`, which only supports the GPIO lines, this handles the comparator
+/// lines 21 to 33. The triggers of the other edge are cleared.
+fn exti_listen(exti: &ExtiRegisters, line: u8, edge: SignalEdge) {
+    let (rising, falling) = match edge {
+        SignalEdge::Rising => (true, false),
+        SignalEdge::Falling => (false, true),
+        SignalEdge::RisingFalling => (true, true),
+    };
+    let set = |bits: u32, mask: u32, enable: bool| {
+        if enable {
+            bits | mask
+        } else {
+            bits & !mask
+        }
+    };
+    // SAFETY: Only the bits of this line are modified, all values are valid
+    cortex_m::interrupt::free(|_| unsafe {
+        if line < 32 {
+            let mask = 1 << line;
+            exti.rtsr1
+                .modify(|r, w| w.bits(set(r.bits(), mask, rising)));
+            exti.ftsr1
+                .modify(|r, w| w.bits(set(r.bits(), mask, falling)));
+            exti.imr1.modify(|r, w| w.bits(r.bits() | mask));
+        } else {
+            let mask = 1 << (line - 32);
+            exti.rtsr2
+                .modify(|r, w| w.bits(set(r.bits(), mask, rising)));
+            exti.ftsr2
+                .modify(|r, w| w.bits(set(r.bits(), mask, falling)));
+            exti.imr2.modify(|r, w| w.bits(r.bits() | mask));
+        }
+    });
+}
+
+/// Clear the edge triggers, interrupt mask and pending flag of the EXTI line of a comparator
+fn exti_unlisten(exti: &ExtiRegisters, line: u8) {
+    // SAFETY: Only the bits of this line are cleared, all values are valid
+    cortex_m::interrupt::free(|_| unsafe {
+        if line < 32 {
+            let mask = !(1 << line);
+            exti.imr1.modify(|r, w| w.bits(r.bits() & mask));
+            exti.rtsr1.modify(|r, w| w.bits(r.bits() & mask));
+            exti.ftsr1.modify(|r, w| w.bits(r.bits() & mask));
+        } else {
+            let mask = !(1 << (line - 32));
+            exti.imr2.modify(|r, w| w.bits(r.bits() & mask));
+            exti.rtsr2.modify(|r, w| w.bits(r.bits() & mask));
+            exti.ftsr2.modify(|r, w| w.bits(r.bits() & mask));
+        }
+    });
+    exti_unpend(exti, line);
+}
+
+fn exti_is_pending(exti: &ExtiRegisters, line: u8) -> bool {
+    if line < 32 {
+        exti.pr1.read().bits() & (1 << line) != 0
+    } else {
+        exti.pr2.read().bits() & (1 << (line - 32)) != 0
+    }
+}
+
+fn exti_unpend(exti: &ExtiRegisters, line: u8) {
+    // SAFETY: The pending registers are write 1 to clear, writing 0 has no effect
+    if line < 32 {
+        exti.pr1.write(|w| unsafe { w.bits(1 << line) });
+    } else {
+        exti.pr2.write(|w| unsafe { w.bits(1 << (line - 32)) });
+    }
+}
+
 macro_rules! impl_comp {
     ($($t:ident: $reg_t:ident, $reg:ident,)+) => {$(
         pub struct $t {
@@ -90,11 +175,19 @@ impl_comp! {
 
 // TODO: Split COMP in PAC
 
+/// State shared between `Comparator::wait_for_edge` and `COMPx::on_interrupt`
+#[cfg(feature = "async")]
+struct EdgeState {
+    waker: Option<Waker>,
+    triggered: bool,
+}
+
 #[derive(Copy, Clone, Eq, PartialEq)]
 pub struct Config {
     //power_mode: PowerMode,
     hysteresis: Hysteresis,
     inverted: bool,
+    blanking: BlankingSource,
     //output_xor: bool,
 }
 
@@ -103,6 +196,7 @@ impl Default for Config {
         Self {
             hysteresis: Hysteresis::None,
             inverted: false,
+            blanking: BlankingSource::None,
             //power_mode: PowerMode::HighSpeed,
             //output_xor: false,
         }
@@ -110,6 +204,7 @@ impl Default for Config {
 }
 
 impl Config {
+    /// Set the input hysteresis (HYST), defaults to [`Hysteresis::None`]
     pub fn hysteresis(mut self, hysteresis: Hysteresis) -> Self {
         self.hysteresis = hysteresis;
         self
@@ -120,14 +215,50 @@ impl Config {
         self
     }
 
-    pub fn output_polarity(mut self, inverted: bool) -> Self {
-        self.inverted = inverted;
+    /// Set the output polarity (POL)
+    ///
+    /// `Polarity::ActiveLow` is the same as `output_inverted`
+    pub fn output_polarity(mut self, polarity: Polarity) -> Self {
+        self.inverted = polarity == Polarity::ActiveLow;
+        self
+    }
+
+    /// Set the blanking source (BLANKSEL), defaults to [`BlankingSource::None`]
+    pub fn blanking(mut self, blanking: BlankingSource) -> Self {
+        self.blanking = blanking;
         self
     }
 }
 
+/// Comparator blanking source
+///
+/// The comparator output is masked for as long as the selected timer output is high, this
+/// is typically used to hide the leading edge current spike after a switch turns on.
+///
+/// Which timer output each selection maps to differs between the comparators, see the table
+/// "COMPx blanking sources" in RM0440.
+///
+/// NOTE: The HRTIM outputs are not among the blanking sources. For a comparator used as an
+/// HRTIM external event, use the blanking modes of `hrtim::timer_eev_cfg::EventFilter` instead.
+#[derive(Copy, Clone, Eq, PartialEq)]
+pub enum BlankingSource {
+    /// No blanking, this is the reset value
+    None = 0b000,
+    Source1 = 0b001,
+    Source2 = 0b010,
+    Source3 = 0b011,
+    Source4 = 0b100,
+    Source5 = 0b101,
+    Source6 = 0b110,
+    Source7 = 0b111,
+}
+
+/// Comparator input hysteresis
+///
+/// The values are typical, see the datasheet for the actual range.
 #[derive(Copy, Clone, Eq, PartialEq)]
 pub enum Hysteresis {
+    /// No hysteresis, this is the reset value
     None = 0b000,
     H10mV = 0b001,
     H20mV = 0b010,
@@ -144,6 +275,22 @@ pub trait PositiveInput<C> {
 }
 
 /// Comparator negative input
+///
+/// Implemented for
+/// * the negative input pins of each comparator
+/// * [`RefintInput`], Vrefint and fractions of it
+/// * a reference to an enabled DAC channel (`&Dac1Ch1` etc.) for a software adjustable threshold,
+///   see the `comp_w_dac` example. Which DAC channels can be used is given by INMSEL in RM0440:
+///
+/// | Comparator | 0b100    | 0b101    |
+/// |------------|----------|----------|
+/// | COMP1      | DAC3_CH1 | DAC1_CH1 |
+/// | COMP2      | DAC3_CH2 | DAC1_CH2 |
+/// | COMP3      | DAC3_CH1 | DAC1_CH1 |
+/// | COMP4      | DAC3_CH2 | DAC1_CH1 |
+/// | COMP5      | DAC4_CH1 | DAC1_CH2 |
+/// | COMP6      | DAC4_CH2 | DAC2_CH1 |
+/// | COMP7      | DAC4_CH1 | DAC2_CH1 |
 pub trait NegativeInput<C> {
     /// Does this input use the internal reference Vrefint
     ///
@@ -380,8 +527,27 @@ pub trait ComparatorExt<COMP> {
     ) -> Comparator<COMP, Disabled>;
 }
 
+/// Comparator usable as one half of a [`ComparatorWindow`]
+pub trait WindowComparator: ComparatorExt<Self> + Sized {
+    /// Enables the comparator
+    fn enable(comp: Comparator<Self, Disabled>) -> Comparator<Self, Enabled>;
+
+    /// Returns the value of the output of the comparator
+    fn output(comp: &Comparator<Self, Enabled>) -> bool;
+}
+
 macro_rules! impl_comparator {
     ($COMP:ty, $comp:ident, $Event:expr) => {
+        impl WindowComparator for $COMP {
+            fn enable(comp: Comparator<Self, Disabled>) -> Comparator<Self, Enabled> {
+                comp.enable()
+            }
+
+            fn output(comp: &Comparator<Self, Enabled>) -> bool {
+                comp.output()
+            }
+        }
+
         impl ComparatorExt<$COMP> for $COMP {
             fn comparator<P: PositiveInput<$COMP>, N: NegativeInput<$COMP>>(
                 self,
@@ -404,6 +570,8 @@ macro_rules! impl_comparator {
                         .bit(negative_input.use_resistor_divider())
                         .pol()
                         .bit(config.inverted)
+                        .blanksel()
+                        .bits(config.blanking as u8)
                 });
 
                 Comparator {
@@ -433,10 +601,52 @@ macro_rules! impl_comparator {
                     _enabled: PhantomData,
                 }
             }
+        }
 
-            /// Enables raising the `ADC_COMP` interrupt at the specified output signal edge
-            pub fn listen(&self, edge: SignalEdge, exti: &EXTI) {
-                exti.listen($Event, edge);
+        impl $COMP {
+            /// Handles the EXTI interrupt of the comparator for `Comparator::wait_for_edge`
+            ///
+            /// Call this from the interrupt handler of the comparator, `COMP1_2_3` is shared by
+            /// COMP1 to COMP3 and `COMP4_5_6` by COMP4 to COMP6 so call it for all of them there.
+            /// The interrupt also has to be unmasked:
+            ///
+            /// ```ignore
+            /// unsafe { cortex_m::peripheral::NVIC::unmask(stm32::Interrupt::COMP1_2_3) };
+            ///
+            /// #[interrupt]
+            /// fn COMP1_2_3() {
+            ///     COMP1::on_interrupt();
+            ///     COMP2::on_interrupt();
+            ///     COMP3::on_interrupt();
+            /// }
+            /// ```
+            ///
+            /// NOTE: This clears the pending flag, so it can not be combined with
+            /// `Comparator::enable_latch`.
+            #[cfg(feature = "async")]
+            pub fn on_interrupt() {
+                // NOTE(unsafe) only the pending flag of the EXTI line of this comparator is
+                // accessed
+                let exti = unsafe { &*EXTI::ptr() };
+                if exti_is_pending(exti, $Event as u8) {
+                    exti_unpend(exti, $Event as u8);
+                    cortex_m::interrupt::free(|cs| {
+                        let mut state = Self::edge_state().borrow(cs).borrow_mut();
+                        state.triggered = true;
+                        if let Some(waker) = state.waker.take() {
+                            waker.wake();
+                        }
+                    });
+                }
+            }
+
+            #[cfg(feature = "async")]
+            fn edge_state() -> &'static Mutex<RefCell<EdgeState>> {
+                static STATE: Mutex<RefCell<EdgeState>> = Mutex::new(RefCell::new(EdgeState {
+                    waker: None,
+                    triggered: false,
+                }));
+                &STATE
             }
         }
 
@@ -445,6 +655,60 @@ macro_rules! impl_comparator {
             pub fn output(&self) -> bool {
                 self.regs.csr().read().value().bit_is_set()
             }
+
+            /// Latch the output of the comparator going active
+            ///
+            /// The comparator itself has no latch, so this uses the pending flag of its EXTI
+            /// line. Once the output goes active (high, after `Config::output_polarity`) the
+            /// latch stays set until `clear_latch` is called, even if the output has since
+            /// gone inactive again.
+            ///
+            /// NOTE: This also unmasks the EXTI line, so the interrupt of the comparator will
+            /// fire if it is enabled in the NVIC, see `listen`.
+            pub fn enable_latch(&mut self, exti: &EXTI) {
+                exti_listen(exti, $Event as u8, SignalEdge::Rising);
+                exti_unpend(exti, $Event as u8);
+            }
+
+            /// Returns `true` if the output has gone active since the latch was last cleared
+            pub fn is_latched(&self, exti: &EXTI) -> bool {
+                exti_is_pending(exti, $Event as u8)
+            }
+
+            /// Clear the latch, see `enable_latch`
+            pub fn clear_latch(&mut self, exti: &EXTI) {
+                exti_unpend(exti, $Event as u8);
+            }
+
+            /// Wait for the output signal `edge`
+            ///
+            /// This uses the EXTI interrupt of the comparator, so `COMPx::on_interrupt` has to be
+            /// called from the interrupt handler and the interrupt has to be unmasked in the NVIC.
+            /// The EXTI line is left listening for `edge` once the edge has occurred.
+            #[cfg(feature = "async")]
+            pub async fn wait_for_edge(&mut self, edge: SignalEdge, exti: &EXTI) {
+                cortex_m::interrupt::free(|cs| {
+                    <$COMP>::edge_state().borrow(cs).borrow_mut().triggered = false;
+                });
+                exti_unpend(exti, $Event as u8);
+                exti_listen(exti, $Event as u8, edge);
+
+                poll_fn(|cx| {
+                    cortex_m::interrupt::free(|cs| {
+                        let mut state = <$COMP>::edge_state().borrow(cs).borrow_mut();
+                        if state.triggered {
+                            state.triggered = false;
+                            return Poll::Ready(());
+                        }
+                        match state.waker.as_ref() {
+                            Some(w) if w.will_wake(cx.waker()) => {}
+                            _ => state.waker = Some(cx.waker().clone()),
+                        }
+                        Poll::Pending
+                    })
+                })
+                .await
+            }
         }
 
         impl Comparator<$COMP, Enabled> {
@@ -457,6 +721,13 @@ macro_rules! impl_comparator {
                 }
             }
 
+            /// Change the blanking source, see [`BlankingSource`]
+            pub fn set_blanking_source(&mut self, source: BlankingSource) {
+                self.regs
+                    .csr()
+                    .modify(|_, w| unsafe { w.blanksel().bits(source as u8) });
+            }
+
             /// Disables the comparator
             pub fn disable(self) -> Comparator<$COMP, Disabled> {
                 self.regs.csr().modify(|_, w| w.en().clear_bit());
@@ -468,26 +739,39 @@ macro_rules! impl_comparator {
         }
 
         impl<ED> Comparator<$COMP, ED> {
+            /// Enables raising the EXTI interrupt of the comparator at the specified output
+            /// signal edge
+            ///
+            /// The interrupt is `COMP1_2_3` for COMP1 to COMP3, `COMP4_5_6` for COMP4 to COMP6
+            /// and `COMP7` for COMP7. It also has to be unmasked in the NVIC.
+            pub fn listen(&self, edge: SignalEdge, exti: &EXTI) {
+                exti_listen(exti, $Event as u8, edge);
+            }
+
             /// Disables raising interrupts for the output signal
             pub fn unlisten(&self, exti: &EXTI) {
-                exti.unlisten($Event);
+                exti_unlisten(exti, $Event as u8);
             }
 
             /// Returns `true` if the output signal interrupt is pending for the `edge`
             pub fn is_pending(&self, exti: &EXTI) -> bool {
-                exti.is_pending($Event)
+                exti_is_pending(exti, $Event as u8)
             }
 
             /// Unpends the output signal interrupt
             pub fn unpend(&self, exti: &EXTI) {
-                exti.unpend($Event);
+                exti_unpend(exti, $Event as u8);
             }
 
             /// Configures a GPIO pin to output the signal of the comparator
             ///
+            /// Only pins with a comparator output alternate function are accepted. Pins in push-pull
+            /// or open-drain output mode are switched to the corresponding alternate function, pins
+            /// already in that alternate function are used as is. The configured pin is returned.
+            ///
             /// Multiple GPIO pins may be configured as the output simultaneously.
-            pub fn output_pin<P: OutputPin<$COMP>>(&self, pin: P) {
-                pin.setup();
+            pub fn output_pin<P: OutputPin<$COMP>>(&self, pin: P) -> P::Configured {
+                pin.setup()
             }
         }
     };
@@ -587,22 +871,116 @@ impl ComparatorSplit for COMP {
     }
 }
 
+/// Two comparators checking whether a signal is inside a voltage window
+///
+/// Unlike some other STM32 families the G4 has no WINMODE bit to internally connect the
+/// positive inputs of a comparator pair, and no two comparators share a positive input pin.
+/// The signal therefore has to be connected to the positive input pin of both comparators
+/// on the board.
+///
+/// ```ignore
+/// // PA1 (COMP1) and PA7 (COMP2) tied together
+/// let window = ComparatorWindow::new(
+///     (comp1, comp2),
+///     (&pa1, &pa7),
+///     RefintInput::VRefintM14,
+///     RefintInput::VRefintM34,
+///     Config::default(),
+///     &rcc.clocks,
+/// );
+/// if window.is_inside() { /* ... */ }
+/// ```
+pub struct ComparatorWindow<CL, CH> {
+    low: Comparator<CL, Enabled>,
+    high: Comparator<CH, Enabled>,
+}
+
+impl<CL: WindowComparator, CH: WindowComparator> ComparatorWindow<CL, CH> {
+    /// Configures and enables both comparators
+    ///
+    /// `input` is the pair of positive inputs, one per comparator, which both have to carry the
+    /// signal to supervise. `low_ref` and `high_ref` are the lower and upper window thresholds.
+    /// The output polarity of `config` is ignored, both comparators use non inverted outputs.
+    pub fn new<PL, PH, NL, NH>(
+        comp_pair: (CL, CH),
+        input: (PL, PH),
+        low_ref: NL,
+        high_ref: NH,
+        config: Config,
+        clocks: &Clocks,
+    ) -> Self
+    where
+        PL: PositiveInput<CL>,
+        PH: PositiveInput<CH>,
+        NL: NegativeInput<CL>,
+        NH: NegativeInput<CH>,
+    {
+        let config = Config {
+            inverted: false,
+            ..config
+        };
+        let (comp_low, comp_high) = comp_pair;
+        let (input_low, input_high) = input;
+        let low = comp_low.comparator(input_low, low_ref, config, clocks);
+        let high = comp_high.comparator(input_high, high_ref, config, clocks);
+
+        ComparatorWindow {
+            low: CL::enable(low),
+            high: CH::enable(high),
+        }
+    }
+
+    /// Returns `true` if the signal is above the upper threshold
+    pub fn is_above(&self) -> bool {
+        CH::output(&self.high)
+    }
+
+    /// Returns `true` if the signal is below the lower threshold
+    pub fn is_below(&self) -> bool {
+        !CL::output(&self.low)
+    }
+
+    /// Returns `true` if the signal is between the lower and the upper threshold
+    pub fn is_inside(&self) -> bool {
+        !self.is_above() && !self.is_below()
+    }
+
+    /// Releases the two comparators, (lower, upper)
+    pub fn free(self) -> (Comparator<CL, Enabled>, Comparator<CH, Enabled>) {
+        (self.low, self.high)
+    }
+}
+
+/// GPIO pin which can output the signal of the comparator `COMP`
 pub trait OutputPin<COMP> {
-    fn setup(self);
+    /// The pin in its alternate function mode
+    type Configured;
+
+    fn setup(self) -> Self::Configured;
 }
 
 #[allow(unused_macros)] // TODO: add support for more devices
 macro_rules! output_pin {
-    ($COMP:ident, $pin:ident, $AF:ident, $mode_t:ident, $into:ident) => {
+    ($COMP:ident, $pin:ident, $AF:ident, $mode_t:ident, $into:ident, $Alt:ident) => {
         impl OutputPin<$COMP> for $pin<Output<$mode_t>> {
-            fn setup(self) {
-                self.$into::<$AF>();
+            type Configured = $pin<$Alt<$AF>>;
+
+            fn setup(self) -> Self::Configured {
+                self.$into::<$AF>()
+            }
+        }
+
+        impl OutputPin<$COMP> for $pin<$Alt<$AF>> {
+            type Configured = Self;
+
+            fn setup(self) -> Self {
+                self
             }
         }
     };
     ($($COMP:ident: $pin:ident, $AF:ident,)+) => {$(
-        output_pin!($COMP, $pin, $AF, PushPull, into_alternate);
-        output_pin!($COMP, $pin, $AF, OpenDrain, into_alternate_open_drain);
+        output_pin!($COMP, $pin, $AF, PushPull, into_alternate, Alternate);
+        output_pin!($COMP, $pin, $AF, OpenDrain, into_alternate_open_drain, AlternateOD);
     )+};
 }
 
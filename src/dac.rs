@@ -8,10 +8,14 @@
 use core::marker::PhantomData;
 use core::mem::MaybeUninit;
 
+use crate::dma::mux::DmaMuxResources;
+use crate::dma::traits::TargetAddress;
+use crate::dma::MemoryToPeripheral;
+
 use crate::gpio::gpioa::{PA4, PA5, PA6};
 use crate::gpio::DefaultMode;
 use crate::rcc::{self, *};
-use crate::stm32::{DAC1, DAC2, DAC3, DAC4, RCC};
+use crate::stm32::{DAC1, DAC2, DAC3, DAC4};
 use hal::blocking::delay::DelayUs;
 
 pub trait DacOut<V> {
@@ -53,6 +57,8 @@ pub struct Enabled;
 //pub struct EnabledUnbuffered;
 /// Enabled DAC wave generator (type state)
 pub struct WaveGenerator;
+/// Enabled DAC fed by DMA on every trigger (type state)
+pub struct DMA;
 /// Disabled DAC (type state)
 pub struct Disabled;
 
@@ -60,6 +66,7 @@ pub trait ED {}
 impl ED for Enabled {}
 //impl ED for EnabledUnbuffered {}
 impl ED for WaveGenerator {}
+impl ED for DMA {}
 impl ED for Disabled {}
 
 macro_rules! impl_dac {
@@ -154,16 +161,12 @@ impl_pin_for_dac!(
     (Dac4Ch1<M_INT_SIG, Disabled>, Dac4Ch2<M_INT_SIG, Disabled>)
 );
 
-pub fn dac<DAC, PINS>(_dac: DAC, _pins: PINS, _rcc: &mut Rcc) -> PINS::Output
+pub fn dac<DAC, PINS>(_dac: DAC, _pins: PINS, rcc: &mut Rcc) -> PINS::Output
 where
     DAC: rcc::Enable + rcc::Reset,
     PINS: Pins<DAC>,
 {
-    unsafe {
-        let rcc_ptr = &(*RCC::ptr());
-        DAC::enable(rcc_ptr);
-        DAC::reset(rcc_ptr);
-    }
+    rcc.enable_and_reset::<DAC>();
 
     #[allow(clippy::uninit_assumed_init)]
     unsafe {
@@ -184,7 +187,10 @@ macro_rules! dac_helper {
         $wave:ident,
         $mamp:ident,
         $ten:ident,
-        $swtrig:ident
+        $tsel:ident,
+        $dmaen:ident,
+        $swtrig:ident,
+        $mux:ident
     ),)+) => {
         $(
             impl<const MODE_BITS: u8> $CX<MODE_BITS, Disabled> {
@@ -201,6 +207,27 @@ macro_rules! dac_helper {
                     }
                 }
 
+                /// Enable the channel and request a new value from the DMA on every trigger
+                ///
+                /// `trigger` is the TSEL value selecting the trigger, the available triggers
+                /// differ between the DAC instances, see the DAC interconnection table in the
+                /// reference manual. 0 selects the software trigger, see `trigger`.
+                pub fn enable_dma(self, trigger: u8) -> $CX<MODE_BITS, DMA> {
+                    let dac = unsafe { &(*<$DAC>::ptr()) };
+
+                    dac.dac_mcr.modify(|_, w| unsafe { w.$mode().bits(MODE_BITS) });
+                    dac.dac_cr.modify(|_, w| unsafe {
+                        w.$tsel().bits(trigger);
+                        w.$ten().set_bit();
+                        w.$dmaen().set_bit();
+                        w.$en().set_bit()
+                    });
+
+                    $CX {
+                        _enabled: PhantomData,
+                    }
+                }
+
                 pub fn enable_generator(self, config: GeneratorConfig) -> $CX<MODE_BITS, WaveGenerator> {
                     let dac = unsafe { &(*<$DAC>::ptr()) };
 
@@ -258,7 +285,7 @@ macro_rules! dac_helper {
                 pub fn disable(self) -> $CX<MODE_BITS, Disabled> {
                     let dac = unsafe { &(*<$DAC>::ptr()) };
                     dac.dac_cr.modify(|_, w| unsafe {
-                        w.$en().clear_bit().$wave().bits(0).$ten().clear_bit()
+                        w.$en().clear_bit().$wave().bits(0).$ten().clear_bit().$dmaen().clear_bit()
                     });
 
                     $CX {
@@ -288,12 +315,35 @@ macro_rules! dac_helper {
                     dac.dac_swtrgr.write(|w| { w.$swtrig().set_bit() });
                 }
             }
+
+            /// DMA state implementation
+            impl<const MODE_BITS: u8> $CX<MODE_BITS, DMA> {
+                /// Software trigger, loads the next value from the DMA if the software trigger
+                /// was selected in `enable_dma`
+                pub fn trigger(&mut self) {
+                    let dac = unsafe { &(*<$DAC>::ptr()) };
+                    dac.dac_swtrgr.write(|w| { w.$swtrig().set_bit() });
+                }
+            }
+
+            unsafe impl<const MODE_BITS: u8> TargetAddress<MemoryToPeripheral> for $CX<MODE_BITS, DMA> {
+                #[inline(always)]
+                fn address(&self) -> u32 {
+                    // The 12 bit right aligned data holding register of the channel
+                    unsafe { &(*<$DAC>::ptr()) }.$dhrx.as_ptr() as u32
+                }
+
+                // The DAC registers only support 32 bit accesses
+                type MemSize = u32;
+
+                const REQUEST_LINE: Option<u8> = Some(DmaMuxResources::$mux as u8);
+            }
         )+
     };
 }
 
 macro_rules! dac {
-    ($($DAC:ident ch1: $DACxCH1:ident $(, ch2: $DACxCH2:ident)*)+) => {$(
+    ($($DAC:ident ch1: $DACxCH1:ident: $MUX1:ident $(, ch2: $DACxCH2:ident: $MUX2:ident)*)+) => {$(
         dac_helper!{$DACxCH1: $DAC: (
             en1,
             cen1,
@@ -306,7 +356,10 @@ macro_rules! dac {
             wave1,
             mamp1,
             ten1,
-            swtrig1
+            tsel1,
+            dmaen1,
+            swtrig1,
+            $MUX1
         ),
         $($DACxCH2: $DAC: (
             en2,
@@ -320,7 +373,10 @@ macro_rules! dac {
             wave2,
             mamp2,
             ten2,
-            swtrig2
+            tsel2,
+            dmaen2,
+            swtrig2,
+            $MUX2
         ),)*}
     )+};
 }
@@ -347,8 +403,8 @@ macro_rules! impl_dac_ext {
 impl_dac_ext!(DAC1, DAC2, DAC3, DAC4,);
 
 dac!(
-    DAC1 ch1: Dac1Ch1, ch2: Dac1Ch2
-    DAC2 ch1: Dac2Ch1
-    DAC3 ch1: Dac3Ch1, ch2: Dac3Ch2
-    DAC4 ch1: Dac4Ch1, ch2: Dac4Ch2
+    DAC1 ch1: Dac1Ch1: DAC1_CH1, ch2: Dac1Ch2: DAC1_CH2
+    DAC2 ch1: Dac2Ch1: DAC2_CH1
+    DAC3 ch1: Dac3Ch1: DAC3_CH1, ch2: Dac3Ch2: DAC3_CH2
+    DAC4 ch1: Dac4Ch1: DAC4_CH1, ch2: Dac4Ch2: DAC4_CH2
 );
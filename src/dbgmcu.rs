@@ -0,0 +1,102 @@
+//! Debug MCU configuration
+//!
+//! Allows stopping watchdogs, timers and the I2C SMBus timeouts while the core is halted by a
+//! debugger. Without this the independent watchdog will reset the chip at the first breakpoint.
+//!
+//! ```ignore
+//! let mut dbgmcu = dp.DBGMCU.constrain();
+//! dbgmcu.stop_on_debug(DebugStop::Iwdg, true);
+//! ```
+use crate::stm32::DBGMCU;
+
+/// Extension trait that constrains the `DBGMCU` peripheral
+pub trait DbgMcuExt {
+    /// Constrains the `DBGMCU` peripheral so it plays nicely with the other abstractions
+    fn constrain(self) -> DbgMcu;
+}
+
+impl DbgMcuExt for DBGMCU {
+    fn constrain(self) -> DbgMcu {
+        DbgMcu(self)
+    }
+}
+
+/// Peripheral which can be stopped while the core is halted
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum DebugStop {
+    Tim1,
+    Tim2,
+    Tim3,
+    Tim4,
+    Tim5,
+    Tim6,
+    Tim7,
+    Tim8,
+    Tim15,
+    Tim16,
+    Tim17,
+    Tim20,
+    /// All of the HRTIM
+    Hrtim,
+    LpTim1,
+    Rtc,
+    Wwdg,
+    Iwdg,
+    /// SMBus timeout of I2C1
+    I2c1,
+    /// SMBus timeout of I2C2
+    I2c2,
+    /// SMBus timeout of I2C3
+    I2c3,
+    /// SMBus timeout of I2C4
+    I2c4,
+}
+
+/// Constrained `DBGMCU` peripheral
+pub struct DbgMcu(DBGMCU);
+
+impl DbgMcu {
+    /// Stop (freeze) `peripheral` while the core is halted by a debugger if `stop` is `true`,
+    /// otherwise keep it running
+    pub fn stop_on_debug(&mut self, peripheral: DebugStop, stop: bool) {
+        let dbg = &self.0;
+        match peripheral {
+            DebugStop::Tim1 => dbg.apb2_fz.modify(|_, w| w.dbg_tim1_stop().bit(stop)),
+            DebugStop::Tim2 => dbg.apb1l_fz.modify(|_, w| w.dbg_timer2_stop().bit(stop)),
+            DebugStop::Tim3 => dbg.apb1l_fz.modify(|_, w| w.dbg_tim3_stop().bit(stop)),
+            DebugStop::Tim4 => dbg.apb1l_fz.modify(|_, w| w.dbg_tim4_stop().bit(stop)),
+            DebugStop::Tim5 => dbg.apb1l_fz.modify(|_, w| w.dbg_tim5_stop().bit(stop)),
+            DebugStop::Tim6 => dbg.apb1l_fz.modify(|_, w| w.dbg_timer6_stop().bit(stop)),
+            DebugStop::Tim7 => dbg.apb1l_fz.modify(|_, w| w.dbg_tim7_stop().bit(stop)),
+            DebugStop::Tim8 => dbg.apb2_fz.modify(|_, w| w.dbg_tim8_stop().bit(stop)),
+            DebugStop::Tim15 => dbg.apb2_fz.modify(|_, w| w.dbg_tim15_stop().bit(stop)),
+            DebugStop::Tim16 => dbg.apb2_fz.modify(|_, w| w.dbg_tim16_stop().bit(stop)),
+            DebugStop::Tim17 => dbg.apb2_fz.modify(|_, w| w.dbg_tim17_stop().bit(stop)),
+            DebugStop::Tim20 => dbg.apb2_fz.modify(|_, w| w.dbg_tim20_stop().bit(stop)),
+            DebugStop::Hrtim => dbg.apb2_fz.modify(|_, w| {
+                w.dbg_hrtim0_stop()
+                    .bit(stop)
+                    .dbg_hrtim1_stop()
+                    .bit(stop)
+                    .dbg_hrtim2_stop()
+                    .bit(stop)
+                    .dbg_hrtim3_stop()
+                    .bit(stop)
+            }),
+            DebugStop::LpTim1 => dbg.apb1l_fz.modify(|_, w| w.dbg_lptimer_stop().bit(stop)),
+            DebugStop::Rtc => dbg.apb1l_fz.modify(|_, w| w.dbg_rtc_stop().bit(stop)),
+            DebugStop::Wwdg => dbg.apb1l_fz.modify(|_, w| w.dbg_wwdg_stop().bit(stop)),
+            DebugStop::Iwdg => dbg.apb1l_fz.modify(|_, w| w.dbg_iwdg_stop().bit(stop)),
+            DebugStop::I2c1 => dbg.apb1l_fz.modify(|_, w| w.dbg_i2c1_stop().bit(stop)),
+            DebugStop::I2c2 => dbg.apb1l_fz.modify(|_, w| w.dbg_i2c2_stop().bit(stop)),
+            DebugStop::I2c3 => dbg.apb1l_fz.modify(|_, w| w.dbg_i2c3_stop().bit(stop)),
+            DebugStop::I2c4 => dbg.apb1h_fz.modify(|_, w| w.dbg_i2c4_stop().bit(stop)),
+        }
+    }
+
+    /// Releases the `DBGMCU` peripheral
+    pub fn release(self) -> DBGMCU {
+        self.0
+    }
+}
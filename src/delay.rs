@@ -7,6 +7,10 @@
 //! useful if you're using [RTIC](https://rtic.rs)'s schedule API, which occupies
 //! the `SYST` peripheral.
 //!
+//! Both implement the embedded-hal 0.2 `DelayMs`/`DelayUs` traits as well as the embedded-hal
+//! 1.0 `DelayNs` trait. Delays shorter than a microsecond are rounded up to 1us, e.g.
+//! `delay_ns(10)` waits 1us, or 150 cycles at a 150MHz core clock.
+//!
 //! # Examples
 //!
 //! ## Delay
@@ -152,3 +156,21 @@ impl_delay_from_count_down_timer! {
     (DelayMs, delay_ms, 1_000),
     (DelayUs, delay_us, 1)
 }
+
+impl<T> embedded_hal_one::delay::DelayNs for DelayFromCountDownTimer<T>
+where
+    T: CountDown<Time = MicroSecond>,
+{
+    /// The timer has a resolution of 1us, so `ns` is rounded up to the next whole microsecond
+    fn delay_ns(&mut self, ns: u32) {
+        DelayUs::delay_us(self, ns / 1000 + u32::from(ns % 1000 != 0));
+    }
+
+    fn delay_us(&mut self, us: u32) {
+        DelayUs::delay_us(self, us);
+    }
+
+    fn delay_ms(&mut self, ms: u32) {
+        DelayMs::delay_ms(self, ms);
+    }
+}
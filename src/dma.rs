@@ -16,9 +16,10 @@
 //! https://github.com/stm32-rs/stm32h7xx-hal/blob/master/src/dma/mod.rs
 
 use core::fmt::Debug;
+use embedded_dma::StaticReadBuffer;
 
 pub mod config;
-pub(crate) mod mux;
+pub mod mux;
 pub mod stream; // DMA MUX // DMA1 and DMA2
 pub mod traits;
 pub mod transfer;
@@ -35,6 +36,20 @@ pub enum DMAError {
     SmallBuffer,
     /// DMA started transfer on the inactive buffer while the user was processing it.
     Overflow,
+    /// The DMA hit a bus error (TEIF) and disabled the stream.
+    TransferError,
+}
+
+/// DMA stream event, each with an interrupt enable and a flag
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Event {
+    /// The whole buffer has been transferred (TCIF)
+    TransferComplete,
+    /// The first half of the buffer has been transferred (HTIF)
+    HalfTransfer,
+    /// A bus error occurred, the stream has been disabled by hardware (TEIF)
+    TransferError,
 }
 
 /// Possible DMA's directions.
@@ -99,6 +114,60 @@ where
     }
 }
 
+/// Source buffer of a memory to memory transfer
+///
+/// In memory to memory mode the DMA reads from the address normally used for
+/// the peripheral, so the source buffer takes the place of the peripheral in
+/// the [`Transfer`].
+pub struct MemorySource<BUF> {
+    buf: BUF,
+    address: u32,
+    len: usize,
+}
+
+impl<BUF> MemorySource<BUF>
+where
+    BUF: StaticReadBuffer,
+{
+    pub(crate) fn new(buf: BUF) -> Self {
+        // NOTE(unsafe) We now own this buffer and we won't call any &mut
+        // methods on it until the end of the DMA transfer
+        let (ptr, len) = unsafe { buf.static_read_buffer() };
+
+        Self {
+            buf,
+            address: ptr as u32,
+            len,
+        }
+    }
+
+    /// Number of elements in the source buffer
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if the source buffer holds no elements
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Return the underlying buffer
+    pub fn free(self) -> BUF {
+        self.buf
+    }
+}
+
+unsafe impl<BUF, W> TargetAddress<MemoryToMemory<W>> for MemorySource<BUF>
+where
+    BUF: StaticReadBuffer<Word = W>,
+    W: Into<u32>,
+{
+    fn address(&self) -> u32 {
+        self.address
+    }
+    type MemSize = W;
+}
+
 /// DMA from a memory location to a peripheral.
 #[derive(Debug, Clone, Copy)]
 pub struct MemoryToPeripheral;
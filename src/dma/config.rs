@@ -73,6 +73,13 @@ impl DmaConfig {
         self
     }
     /// Set the half_transfer_interrupt.
+    ///
+    /// The half transfer flag is set once the first half of the buffer has been
+    /// transferred. Together with `circular_buffer(true)` this allows processing
+    /// the first half of the buffer while the DMA fills the second half, and then
+    /// processing the second half on the transfer complete flag while the DMA wraps
+    /// around and fills the first half again. Both flags are set again on every lap
+    /// of a circular transfer, so they need to be cleared by the user.
     #[inline(always)]
     pub fn half_transfer_interrupt(mut self, half_transfer_interrupt: bool) -> Self {
         self.half_transfer_interrupt = half_transfer_interrupt;
@@ -1,4 +1,11 @@
+use core::marker::PhantomData;
+
+use crate::dma::traits::{Direction, TargetAddress};
+use crate::rcc::Rcc;
+use crate::stm32::DMAMUX;
+
 #[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum DmaMuxResources {
     DMAMUXReqG0 = 1,
     DMAMUXReqG1 = 2,
@@ -134,3 +141,200 @@ impl From<DmaMuxResources> for u8 {
         dmr as u8
     }
 }
+
+/// Trigger inputs for the DMAMUX request generators
+///
+/// See RM0440 "DMAMUX: assignment of trigger inputs to resources"
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RequestGeneratorTrigger {
+    Exti0 = 0,
+    Exti1 = 1,
+    Exti2 = 2,
+    Exti3 = 3,
+    Exti4 = 4,
+    Exti5 = 5,
+    Exti6 = 6,
+    Exti7 = 7,
+    Exti8 = 8,
+    Exti9 = 9,
+    Exti10 = 10,
+    Exti11 = 11,
+    Exti12 = 12,
+    Exti13 = 13,
+    Exti14 = 14,
+    Exti15 = 15,
+    DmaMuxEvt0 = 16,
+    DmaMuxEvt1 = 17,
+    DmaMuxEvt2 = 18,
+    DmaMuxEvt3 = 19,
+    Lptim1Out = 20,
+}
+
+/// Edge of the trigger input which makes the request generator issue its requests
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TriggerPolarity {
+    RisingEdge = 0b01,
+    FallingEdge = 0b10,
+    BothEdges = 0b11,
+}
+
+/// Marks a DMAMUX request generator channel
+pub trait RequestGenerator {
+    /// The DMAMUX request line of this generator
+    const REQUEST_LINE: DmaMuxResources;
+}
+
+/// A peripheral whose DMA requests are issued by the request generator `G`
+/// instead of by the peripheral itself
+///
+/// Created using `RequestGeneratorX::pace`. This can be passed to any of the
+/// `TransferExt` constructors in place of the peripheral.
+pub struct Paced<PERIPHERAL, G> {
+    peripheral: PERIPHERAL,
+    _generator: PhantomData<G>,
+}
+
+impl<PERIPHERAL, G> Paced<PERIPHERAL, G> {
+    /// Return the underlying peripheral
+    pub fn free(self) -> PERIPHERAL {
+        self.peripheral
+    }
+}
+
+unsafe impl<D, PERIPHERAL, G> TargetAddress<D> for Paced<PERIPHERAL, G>
+where
+    D: Direction,
+    PERIPHERAL: TargetAddress<D>,
+    G: RequestGenerator,
+{
+    type MemSize = PERIPHERAL::MemSize;
+
+    fn address(&self) -> u32 {
+        self.peripheral.address()
+    }
+
+    const REQUEST_LINE: Option<u8> = Some(G::REQUEST_LINE as u8);
+}
+
+/// All DMAMUX request generators
+pub struct RequestGenerators {
+    pub gen0: RequestGenerator0,
+    pub gen1: RequestGenerator1,
+    pub gen2: RequestGenerator2,
+    pub gen3: RequestGenerator3,
+}
+
+pub trait RequestGeneratorExt {
+    fn request_generators(self, rcc: &Rcc) -> RequestGenerators;
+}
+
+impl RequestGeneratorExt for DMAMUX {
+    fn request_generators(self, rcc: &Rcc) -> RequestGenerators {
+        // Enable DMAMux is not yet enabled
+        if !rcc.rb.ahb1enr.read().dmamuxen().bit_is_set() {
+            // Enable peripheral
+            rcc.rb.ahb1enr.modify(|_, w| w.dmamuxen().set_bit());
+        }
+
+        RequestGenerators {
+            gen0: RequestGenerator0 { _x: PhantomData },
+            gen1: RequestGenerator1 { _x: PhantomData },
+            gen2: RequestGenerator2 { _x: PhantomData },
+            gen3: RequestGenerator3 { _x: PhantomData },
+        }
+    }
+}
+
+macro_rules! request_generator {
+    ($($name:ident: $rgXcr:ident, $request_line:ident, $bit:literal,)+) => {$(
+        /// DMAMUX request generator channel
+        pub struct $name {
+            _x: PhantomData<()>,
+        }
+
+        impl RequestGenerator for $name {
+            const REQUEST_LINE: DmaMuxResources = DmaMuxResources::$request_line;
+        }
+
+        impl $name {
+            /// Issue `request_count` DMA requests every time the selected edge occurs on `trigger`
+            ///
+            /// The generator is disabled while being configured, call `enable` to start it.
+            ///
+            /// # Panics
+            ///
+            /// * When `request_count` is not in the range 1..=32
+            pub fn configure(
+                &mut self,
+                trigger: RequestGeneratorTrigger,
+                polarity: TriggerPolarity,
+                request_count: u8,
+            ) {
+                assert!(
+                    (1..=32).contains(&request_count),
+                    "The request generator can only issue 1 to 32 requests per trigger"
+                );
+
+                let dmamux = unsafe { &*DMAMUX::ptr() };
+
+                // GNBREQ may only be written while the generator is disabled
+                dmamux.$rgXcr.modify(|_, w| w.ge().clear_bit());
+
+                // SAFETY: All values are checked to be in range above
+                dmamux.$rgXcr.modify(|_, w| unsafe {
+                    w.sig_id()
+                        .bits(trigger as u8)
+                        .gpol()
+                        .bits(polarity as u8)
+                        .gnbreq()
+                        .bits(request_count - 1)
+                });
+            }
+
+            /// Start generating requests on trigger events
+            pub fn enable(&mut self) {
+                let dmamux = unsafe { &*DMAMUX::ptr() };
+                dmamux.$rgXcr.modify(|_, w| w.ge().set_bit());
+            }
+
+            /// Stop generating requests
+            pub fn disable(&mut self) {
+                let dmamux = unsafe { &*DMAMUX::ptr() };
+                dmamux.$rgXcr.modify(|_, w| w.ge().clear_bit());
+            }
+
+            /// Enable/disable the trigger overrun interrupt
+            pub fn set_overrun_interrupt_enable(&mut self, enable: bool) {
+                let dmamux = unsafe { &*DMAMUX::ptr() };
+                dmamux.$rgXcr.modify(|_, w| w.oie().bit(enable));
+            }
+
+            /// A new trigger event occurred before all requests from the previous one were served
+            pub fn get_overrun_flag(&self) -> bool {
+                let dmamux = unsafe { &*DMAMUX::ptr() };
+                dmamux.rgsr.read().of().bits() & (1 << $bit) != 0
+            }
+
+            pub fn clear_overrun_flag(&mut self) {
+                let dmamux = unsafe { &*DMAMUX::ptr() };
+                // SAFETY: Write 1 to clear, only touches the bit belonging to this generator
+                dmamux.rgcfr.write(|w| unsafe { w.cof().bits(1 << $bit) });
+            }
+
+            /// Let the DMA requests for `peripheral` be issued by this generator
+            pub fn pace<PERIPHERAL>(&self, peripheral: PERIPHERAL) -> Paced<PERIPHERAL, Self> {
+                Paced {
+                    peripheral,
+                    _generator: PhantomData,
+                }
+            }
+        }
+    )+};
+}
+
+request_generator! {
+    RequestGenerator0: rg0cr, DMAMUXReqG0, 0,
+    RequestGenerator1: rg1cr, DMAMUXReqG1, 1,
+    RequestGenerator2: rg2cr, DMAMUXReqG2, 2,
+    RequestGenerator3: rg3cr, DMAMUXReqG3, 3,
+}
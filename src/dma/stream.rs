@@ -4,9 +4,15 @@ use super::{
     config,
     traits::sealed::{Bits, Sealed},
     traits::*,
-    DmaDirection,
+    DmaDirection, Event,
 };
+#[cfg(feature = "async")]
+use core::cell::RefCell;
 use core::marker::PhantomData;
+#[cfg(feature = "async")]
+use core::task::Waker;
+#[cfg(feature = "async")]
+use cortex_m::interrupt::Mutex;
 
 use crate::rcc::Rcc;
 use crate::stm32;
@@ -30,9 +36,82 @@ pub trait Instance: Deref<Target = DMARegisterBlock> + Sealed {
 
     /// Gives a pointer to the DMAMUX used for this DMA.
     fn mux_ptr() -> *const DMAMUXRegisterBlock;
+
+    /// 0 for DMA1 and 1 for DMA2
+    const NUMBER: usize;
+}
+
+/// Waker of the task waiting for a stream, along with the interrupts it enabled
+#[cfg(feature = "async")]
+pub struct StreamWaker {
+    waker: Option<Waker>,
+    /// Interrupts enabled by the waiting task, which `on_interrupt` disables again
+    armed: DmaInterrupts,
+    /// Interrupts the user enabled themselves which `on_interrupt` disabled because their flag
+    /// was pending, enabled again by the waiting task
+    masked: DmaInterrupts,
 }
 
+#[cfg(feature = "async")]
+impl StreamWaker {
+    const fn new() -> Self {
+        StreamWaker {
+            waker: None,
+            armed: DmaInterrupts::NONE,
+            masked: DmaInterrupts::NONE,
+        }
+    }
+
+    /// Registers `waker` to be woken by the next interrupt of the stream
+    pub(crate) fn register(&mut self, waker: &Waker) {
+        match &self.waker {
+            Some(w) if w.will_wake(waker) => {}
+            _ => self.waker = Some(waker.clone()),
+        }
+    }
+
+    /// Enables the interrupt of `event` until the next interrupt of the stream, unless it is
+    /// already enabled
+    pub(crate) fn arm<S: Stream>(&mut self, stream: &mut S, event: Event) {
+        if stream.is_listening(event) {
+            return;
+        }
+        stream.listen(event);
+        match event {
+            Event::TransferComplete => self.armed.transfer_complete = true,
+            Event::HalfTransfer => self.armed.half_transfer = true,
+            Event::TransferError => self.armed.transfer_error = true,
+        }
+    }
+
+    /// Enables the interrupts of the user again which `on_interrupt` disabled, once their flag
+    /// has been cleared
+    pub(crate) fn restore<S: Stream>(&mut self, stream: &mut S) {
+        let masked = [
+            (&mut self.masked.transfer_complete, Event::TransferComplete),
+            (&mut self.masked.half_transfer, Event::HalfTransfer),
+            (&mut self.masked.transfer_error, Event::TransferError),
+        ];
+        for (masked, event) in masked {
+            if *masked && !stream.is_pending(event) {
+                stream.listen(event);
+                *masked = false;
+            }
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+#[allow(clippy::declare_interior_mutable_const)]
+const NO_WAKER: Mutex<RefCell<StreamWaker>> = Mutex::new(RefCell::new(StreamWaker::new()));
+
+/// Wakers of the tasks waiting for a stream, 8 per DMA
+#[cfg(feature = "async")]
+static WAKERS: [Mutex<RefCell<StreamWaker>>; 16] = [NO_WAKER; 16];
+
 impl Instance for DMA1 {
+    const NUMBER: usize = 0;
+
     #[inline(always)]
     fn ptr() -> *const DMARegisterBlock {
         DMA1::ptr()
@@ -45,6 +124,8 @@ impl Instance for DMA1 {
 }
 
 impl Instance for DMA2 {
+    const NUMBER: usize = 1;
+
     #[inline(always)]
     fn ptr() -> *const DMARegisterBlock {
         DMA2::ptr()
@@ -64,6 +145,15 @@ pub struct DmaInterrupts {
     half_transfer: bool,
 }
 
+#[cfg(feature = "async")]
+impl DmaInterrupts {
+    const NONE: DmaInterrupts = DmaInterrupts {
+        transfer_complete: false,
+        transfer_error: false,
+        half_transfer: false,
+    };
+}
+
 /// Stream 0 on DMA
 pub struct Stream0<DMA> {
     _dma: PhantomData<DMA>,
@@ -89,10 +179,12 @@ pub struct Stream5<DMA> {
     _dma: PhantomData<DMA>,
 }
 /// Stream 6 on DMA
+#[cfg(not(any(feature = "stm32g431", feature = "stm32g441")))]
 pub struct Stream6<DMA> {
     _dma: PhantomData<DMA>,
 }
 /// Stream 7 on DMA
+#[cfg(not(any(feature = "stm32g431", feature = "stm32g441")))]
 pub struct Stream7<DMA> {
     _dma: PhantomData<DMA>,
 }
@@ -103,7 +195,9 @@ impl<DMA> Sealed for Stream2<DMA> {}
 impl<DMA> Sealed for Stream3<DMA> {}
 impl<DMA> Sealed for Stream4<DMA> {}
 impl<DMA> Sealed for Stream5<DMA> {}
+#[cfg(not(any(feature = "stm32g431", feature = "stm32g441")))]
 impl<DMA> Sealed for Stream6<DMA> {}
+#[cfg(not(any(feature = "stm32g431", feature = "stm32g441")))]
 impl<DMA> Sealed for Stream7<DMA> {}
 
 /// Alias for a tuple with all DMA streams.
@@ -178,13 +272,19 @@ macro_rules! dma_stream {
     ($(($name:ident, $number:expr,
         regs => $chX:ident,
         fields => $tcif:ident, $htif:ident, $teif:ident, $gif:ident, $tcisr:ident, $htisr:ident, $teisr:ident, $gisr:ident,
-        dmamux => $cXcr:ident,)
+        dmamux => $cXcr:ident, $dma2_cXcr:ident,)
     ),+$(,)*) => {
         $(
             impl<I: Instance> Stream for $name<I> {
 
                 const NUMBER: usize = $number;
                 type Config = DmaConfig;
+
+                #[cfg(feature = "async")]
+                #[inline(always)]
+                fn waker() -> &'static Mutex<RefCell<StreamWaker>> {
+                    &WAKERS[I::NUMBER * 8 + $number]
+                }
                 type Interrupts = DmaInterrupts;
 
                 fn apply_config(&mut self, config: DmaConfig) {
@@ -291,11 +391,15 @@ macro_rules! dma_stream {
 
                 #[inline(always)]
                 fn set_request_line(&mut self, request_line: u8) {
-                    //NOTE(unsafe) We only access the registers that belongs to the StreamX
+                    //NOTE(unsafe) We only access the DMAMUX channel that belongs to the StreamX
                     let dmamux = unsafe { &*I::mux_ptr() };
-                    unsafe {
+                    // The first DMAMUX channels are routed to DMA1, the following ones to DMA2
+                    if I::NUMBER == 0 {
                         dmamux.$cXcr
-                            .modify(|_, w| w.dmareq_id().bits(request_line));
+                            .modify(|_, w| unsafe { w.dmareq_id().bits(request_line) });
+                    } else {
+                        dmamux.$dma2_cXcr
+                            .modify(|_, w| unsafe { w.dmareq_id().bits(request_line) });
                     }
                 }
 
@@ -343,6 +447,17 @@ macro_rules! dma_stream {
                 }
 
 
+                #[inline(always)]
+                fn is_listening(&self, event: Event) -> bool {
+                    //NOTE(unsafe) Atomic read with no side effects
+                    let cr = unsafe { &*I::ptr() }.$chX().cr.read();
+                    match event {
+                        Event::TransferComplete => cr.tcie().bit_is_set(),
+                        Event::HalfTransfer => cr.htie().bit_is_set(),
+                        Event::TransferError => cr.teie().bit_is_set(),
+                    }
+                }
+
                 #[inline(always)]
                 fn set_transfer_complete_interrupt_enable(&mut self, transfer_complete_interrupt: bool) {
                     //NOTE(unsafe) We only access the registers that belongs to the StreamX
@@ -474,6 +589,56 @@ macro_rules! dma_stream {
             }
 
             impl<I: Instance> $name<I> {
+                /// Handles the interrupt of the stream for the async functions of `Transfer` and
+                /// `CircTransfer`
+                ///
+                /// Call this from the interrupt handler of the DMA channel, which also has to be
+                /// unmasked. Interrupts enabled through `DmaConfig` or `listen` are disabled while
+                /// their flag is pending and enabled again by the waiting task once the flag is
+                /// cleared. Note that the channels are numbered from 1, so `Stream0` is `CH1`:
+                ///
+                /// ```ignore
+                /// unsafe { cortex_m::peripheral::NVIC::unmask(stm32::Interrupt::DMA1_CH1) };
+                ///
+                /// #[interrupt]
+                /// fn DMA1_CH1() {
+                ///     Stream0::<stm32::DMA1>::on_interrupt();
+                /// }
+                /// ```
+                #[cfg(feature = "async")]
+                pub fn on_interrupt() {
+                    //NOTE(unsafe) We only access the registers that belongs to the StreamX
+                    let dma = unsafe { &*I::ptr() };
+                    let isr = dma.isr.read();
+                    if isr.$gisr().bit_is_set() {
+                        cortex_m::interrupt::free(|cs| {
+                            let mut slot = <Self as Stream>::waker().borrow(cs).borrow_mut();
+                            // The flags are handled by the waiting task, so stop listening to
+                            // the interrupts it enabled and to those of the pending flags to not
+                            // retrigger the interrupt. The ones of the user are restored by the
+                            // waiting task.
+                            let armed = core::mem::replace(&mut slot.armed, DmaInterrupts::NONE);
+                            let cr = dma.$chX().cr.read();
+                            let pending = DmaInterrupts {
+                                transfer_complete: cr.tcie().bit_is_set() && isr.$tcisr().bit_is_set(),
+                                half_transfer: cr.htie().bit_is_set() && isr.$htisr().bit_is_set(),
+                                transfer_error: cr.teie().bit_is_set() && isr.$teisr().bit_is_set(),
+                            };
+                            slot.masked.transfer_complete |= pending.transfer_complete && !armed.transfer_complete;
+                            slot.masked.half_transfer |= pending.half_transfer && !armed.half_transfer;
+                            slot.masked.transfer_error |= pending.transfer_error && !armed.transfer_error;
+                            dma.$chX().cr.modify(|r, w| {
+                                w.tcie().bit(r.tcie().bit_is_set() && !armed.transfer_complete && !pending.transfer_complete)
+                                    .htie().bit(r.htie().bit_is_set() && !armed.half_transfer && !pending.half_transfer)
+                                    .teie().bit(r.teie().bit_is_set() && !armed.transfer_error && !pending.transfer_error)
+                            });
+                            if let Some(waker) = slot.waker.take() {
+                                waker.wake();
+                            }
+                        });
+                    }
+                }
+
                 #[inline(always)]
                 pub fn clear_half_transfer_interrupt(&mut self) {
                     //NOTE(unsafe) Atomic write with no side-effects and we only access the bits
@@ -504,6 +669,8 @@ macro_rules! dma_stream {
     };
 }
 
+// DMAMUX channels 0..=7 are routed to DMA1 and 8..=15 to DMA2
+#[cfg(not(any(feature = "stm32g431", feature = "stm32g441")))]
 dma_stream!(
     // Note: the field names start from one, unlike the RM where they start from
     // zero. May need updating if it gets fixed upstream.
@@ -511,48 +678,92 @@ dma_stream!(
         Stream0, 0,
         regs => ch1,
         fields => tcif1, htif1, teif1, gif1, tcif1, htif1, teif1, gif1,
-        dmamux => c0cr,
+        dmamux => c0cr, c8cr,
     ),
     (
         Stream1, 1,
         regs => ch2,
         fields => tcif2, htif2, teif2, gif2, tcif2, htif2, teif2, gif2,
-        dmamux => c1cr,
+        dmamux => c1cr, c9cr,
     ),
     (
         Stream2, 2,
         regs => ch3,
         fields => tcif3, htif3, teif3, gif3, tcif3, htif3, teif3, gif3,
-        dmamux => c2cr,
+        dmamux => c2cr, c10cr,
     ),
     (
         Stream3, 3,
         regs => ch4,
         fields => tcif4, htif4, teif4, gif4, tcif4, htif4, teif4, gif4,
-        dmamux => c3cr,
+        dmamux => c3cr, c11cr,
     ),
     (
         Stream4, 4,
         regs => ch5,
         fields => tcif5, htif5, teif5, gif5, tcif5, htif5, teif5, gif5,
-        dmamux => c4cr,
+        dmamux => c4cr, c12cr,
     ),
     (
         Stream5, 5,
         regs => ch6,
         fields => tcif6, htif6, teif6, gif6, tcif6, htif6, teif6, gif6,
-        dmamux => c5cr,
+        dmamux => c5cr, c13cr,
     ),
     (
         Stream6, 6,
         regs => ch7,
         fields => tcif7, htif7, teif7, gif7, tcif7, htif7, teif7, gif7,
-        dmamux => c6cr,
+        dmamux => c6cr, c14cr,
     ),
     (
         Stream7, 7,
         regs => ch8,
         fields => tcif8, htif8, teif8, gif8, tcif8, htif8, teif8, gif8,
-        dmamux => c7cr,
+        dmamux => c7cr, c15cr,
+    ),
+);
+
+// Category 2 devices have 6 channels per DMA, DMAMUX channels 0..=5 are routed to DMA1 and
+// 6..=11 to DMA2. Stream6 and Stream7 are not available on these devices.
+#[cfg(any(feature = "stm32g431", feature = "stm32g441"))]
+dma_stream!(
+    // Note: the field names start from one, unlike the RM where they start from
+    // zero. May need updating if it gets fixed upstream.
+    (
+        Stream0, 0,
+        regs => ch1,
+        fields => tcif1, htif1, teif1, gif1, tcif1, htif1, teif1, gif1,
+        dmamux => c0cr, c6cr,
+    ),
+    (
+        Stream1, 1,
+        regs => ch2,
+        fields => tcif2, htif2, teif2, gif2, tcif2, htif2, teif2, gif2,
+        dmamux => c1cr, c7cr,
+    ),
+    (
+        Stream2, 2,
+        regs => ch3,
+        fields => tcif3, htif3, teif3, gif3, tcif3, htif3, teif3, gif3,
+        dmamux => c2cr, c8cr,
+    ),
+    (
+        Stream3, 3,
+        regs => ch4,
+        fields => tcif4, htif4, teif4, gif4, tcif4, htif4, teif4, gif4,
+        dmamux => c3cr, c9cr,
+    ),
+    (
+        Stream4, 4,
+        regs => ch5,
+        fields => tcif5, htif5, teif5, gif5, tcif5, htif5, teif5, gif5,
+        dmamux => c4cr, c10cr,
+    ),
+    (
+        Stream5, 5,
+        regs => ch6,
+        fields => tcif6, htif6, teif6, gif6, tcif6, htif6, teif6, gif6,
+        dmamux => c5cr, c11cr,
     ),
 );
@@ -4,6 +4,12 @@
 //! https://github.com/stm32-rs/stm32f4xx-hal/blob/master/src/dma/traits.rs
 
 use super::*;
+#[cfg(feature = "async")]
+use core::cell::RefCell;
+#[cfg(feature = "async")]
+use cortex_m::interrupt::Mutex;
+#[cfg(feature = "async")]
+use stream::StreamWaker;
 
 pub(crate) mod sealed {
     /// Converts value to bits for setting a register value.
@@ -20,6 +26,11 @@ pub trait Stream: Sealed {
     /// Number of the stream register
     const NUMBER: usize;
 
+    /// Waker of the task waiting for the stream, woken by the `on_interrupt` function of the
+    /// stream
+    #[cfg(feature = "async")]
+    fn waker() -> &'static Mutex<RefCell<StreamWaker>>;
+
     /// Configuration structure for this stream.
     type Config;
 
@@ -112,6 +123,50 @@ pub trait Stream: Sealed {
     /// Get half transfer flag.
     fn get_half_transfer_flag() -> bool;
 
+    /// Enable the interrupt of `event`
+    fn listen(&mut self, event: Event) {
+        match event {
+            Event::TransferComplete => self.set_transfer_complete_interrupt_enable(true),
+            Event::HalfTransfer => self.set_half_transfer_interrupt_enable(true),
+            Event::TransferError => self.set_transfer_error_interrupt_enable(true),
+        }
+    }
+
+    /// Disable the interrupt of `event`
+    fn unlisten(&mut self, event: Event) {
+        match event {
+            Event::TransferComplete => self.set_transfer_complete_interrupt_enable(false),
+            Event::HalfTransfer => self.set_half_transfer_interrupt_enable(false),
+            Event::TransferError => self.set_transfer_error_interrupt_enable(false),
+        }
+    }
+
+    /// Returns `true` if the interrupt of `event` is enabled
+    fn is_listening(&self, event: Event) -> bool;
+
+    /// Returns `true` if the flag of `event` is set, whether or not its interrupt is enabled
+    fn is_pending(&self, event: Event) -> bool {
+        match event {
+            Event::TransferComplete => Self::get_transfer_complete_flag(),
+            Event::HalfTransfer => Self::get_half_transfer_flag(),
+            Event::TransferError => Self::get_transfer_error_flag(),
+        }
+    }
+
+    /// Clear the flag of `event`
+    fn clear_flag(&mut self, event: Event) {
+        match event {
+            Event::TransferComplete => self.clear_transfer_complete_interrupt(),
+            Event::HalfTransfer => self.clear_half_transfer_interrupt(),
+            Event::TransferError => self.clear_transfer_error_interrupt(),
+        }
+    }
+
+    /// Clear all flags of the stream, including the global interrupt flag (GIF)
+    fn clear_all_flags(&mut self) {
+        self.clear_interrupts();
+    }
+
     /// Get the memory address for the DMA stream.
     fn get_memory_address(&self) -> u32;
 
@@ -125,6 +180,9 @@ pub trait Stream: Sealed {
     fn set_number_of_transfers(&mut self, value: u16);
 
     /// Get the number of transfers (ndt) for the DMA stream.
+    ///
+    /// While the stream is running this is the number of items left in the current transfer,
+    /// or until the wrap around of a circular transfer. Reading it does not disturb the transfer.
     fn get_number_of_transfers() -> u16;
 
     /// Set the memory size (msize) for the DMA stream.
@@ -195,3 +253,41 @@ pub unsafe trait TargetAddress<D: Direction> {
     /// An optional associated request line
     const REQUEST_LINE: Option<u8> = None;
 }
+
+/// Element type the DMA can transfer
+///
+/// The peripheral size (PSIZE) of a transfer is taken from the `MemSize` of its target and the
+/// memory size (MSIZE) from the element type of its buffer, see [`BufferWord`].
+pub trait DmaWord: Sealed + Copy {
+    /// Value of the PSIZE/MSIZE field for this element type
+    const SIZE: u8;
+}
+
+macro_rules! dma_word {
+    ($($T:ty: $size:literal,)+) => {$(
+        impl Sealed for $T {}
+        impl DmaWord for $T {
+            const SIZE: u8 = $size;
+        }
+    )+};
+}
+
+dma_word!(
+    u8: 0,
+    i8: 0,
+    u16: 1,
+    i16: 1,
+    u32: 2,
+    i32: 2,
+);
+
+/// Element type of a buffer that can be transferred to or from a target with `MemSize` `P`
+///
+/// Every element type is compatible with itself, so by default the buffer has to match the
+/// target. Other pairs are only accepted where this is implemented explicitly, for example to
+/// only read the master sample of a [`DualSample`](crate::adc::DualSample). When the widths
+/// differ the DMA truncates each item to its lower bits or zero-extends it. There is no FIFO,
+/// so several items are never packed into one wider item.
+pub trait BufferWord<P: DmaWord>: DmaWord {}
+
+impl<W: DmaWord> BufferWord<W> for W {}
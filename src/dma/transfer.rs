@@ -1,7 +1,11 @@
 use crate::dma::{
-    traits, Direction, DmaDirection, MemoryToMemory, MemoryToPeripheral, PeripheralToMemory,
-    Stream, TargetAddress,
+    config::DmaConfig,
+    traits::{self, BufferWord, DmaWord},
+    DMAError, Direction, DmaDirection, Event, MemorySource, MemoryToMemory, MemoryToPeripheral,
+    PeripheralToMemory, Stream, TargetAddress,
 };
+#[cfg(feature = "async")]
+use core::{future::poll_fn, task::Poll};
 use core::{
     marker::PhantomData,
     mem,
@@ -28,6 +32,8 @@ where
     _direction: PhantomData<DIR>,
     _transfer_type: PhantomData<TXFRT>,
     buf: BUF,
+    /// Length of `buf` in elements, the number of transfers programmed into the stream
+    buf_len: u16,
 }
 
 macro_rules! transfer_def {
@@ -39,12 +45,17 @@ macro_rules! transfer_def {
             STREAM: Stream<Config = CONFIG>,
             DIR: Direction,
             PERIPHERAL: TargetAddress<DIR>,
-            BUF: $Buffer<Word = <PERIPHERAL as TargetAddress<DIR>>::MemSize>,
+            BUF: $Buffer,
+            <BUF as $Buffer>::Word: BufferWord<<PERIPHERAL as TargetAddress<DIR>>::MemSize>,
+            <PERIPHERAL as TargetAddress<DIR>>::MemSize: DmaWord,
         {
             /// Configures the DMA source and destination and applies supplied
             /// configuration. In a memory to memory transfer, the `peripheral` argument
             /// is the source of the data.
             ///
+            /// The memory and peripheral sizes are inferred from the element types, see
+            /// [`BufferWord`].
+            ///
             /// # Panics
             ///
             /// * When the transfer length is greater than (2^16 - 1)
@@ -95,6 +106,15 @@ macro_rules! transfer_def {
                 let buf_len = buf_len as u16;
                 stream.set_number_of_transfers(buf_len);
 
+                // NOTE(unsafe) The sizes match the element type of the buffer and the register
+                // width of the peripheral, which is an invariant of TargetAddress
+                unsafe {
+                    stream.set_memory_size(<<BUF as $Buffer>::Word as DmaWord>::SIZE);
+                    stream.set_peripheral_size(
+                        <<PERIPHERAL as TargetAddress<DIR>>::MemSize as DmaWord>::SIZE,
+                    );
+                }
+
                 // Set the DMAMUX request line if needed
                 if let Some(request_line) = PERIPHERAL::REQUEST_LINE {
                     stream.set_request_line(request_line);
@@ -106,6 +126,7 @@ macro_rules! transfer_def {
                     _direction: PhantomData,
                     _transfer_type: PhantomData,
                     buf: memory,
+                    buf_len,
                 };
                 transfer.apply_config(config);
 
@@ -122,8 +143,9 @@ macro_rules! transfer_def {
                 self.stream.disable();
                 fence(Ordering::SeqCst);
 
-                let (_, buf_len) = unsafe { self.buf.$rw_buffer() };
-                self.stream.set_number_of_transfers(buf_len as u16);
+                // Undo any address offset applied by `resume`
+                self.set_addresses(0);
+                self.stream.set_number_of_transfers(self.buf_len);
                 f(&mut self.peripheral);
 
                 // Preserve the instruction and bus ordering of preceding buffer access
@@ -133,6 +155,42 @@ macro_rules! transfer_def {
                     self.stream.enable();
                 }
             }
+
+            /// Resumes a transfer stopped by [`pause`](Transfer::pause) where it left off
+            ///
+            /// The DMA reloads its address pointers from the programmed base addresses whenever
+            /// the stream is enabled, while NDTR keeps the number of remaining items. Using `start`
+            /// after `pause` would therefore transfer the start of the buffer again. This moves
+            /// the base addresses past the items already transferred before enabling the stream.
+            /// The closure will be executed right after enabling the stream.
+            ///
+            /// This must not be used with a circular transfer, since the DMA reloads the moved
+            /// addresses at every wrap around. Restart those from the beginning instead.
+            pub fn resume<F>(&mut self, f: F)
+            where
+                F: FnOnce(&mut PERIPHERAL),
+            {
+                assert!(!STREAM::is_enabled(), "The transfer is not paused");
+
+                self.set_addresses(self.position());
+                self.start(f);
+            }
+
+            /// Sets the memory address, and the source address of a memory to memory transfer,
+            /// to the element `index` of the buffers
+            fn set_addresses(&mut self, index: usize) {
+                let (buf_ptr, _) = unsafe { self.buf.$rw_buffer() };
+                let offset = (index * mem::size_of::<<BUF as $Buffer>::Word>()) as u32;
+
+                // NOTE(unsafe) The addresses stay within the buffers owned by the transfer
+                unsafe {
+                    self.stream.set_memory_address(buf_ptr as u32 + offset);
+                    if DIR::direction() == DmaDirection::MemoryToMemory {
+                        let src_offset = (index * mem::size_of::<<PERIPHERAL as TargetAddress<DIR>>::MemSize>()) as u32;
+                        self.stream.set_peripheral_address(self.peripheral.address() + src_offset);
+                    }
+                }
+            }
         }
     };
 }
@@ -149,17 +207,7 @@ where
 {
     /// Applies all fields in DmaConfig.
     fn apply_config(&mut self, config: CONFIG) {
-        let msize = mem::size_of::<<PERIPHERAL as TargetAddress<DIR>>::MemSize>() / 2;
-
         self.stream.clear_interrupts();
-
-        // NOTE(unsafe) These values are correct because of the
-        // invariants of TargetAddress
-        unsafe {
-            self.stream.set_memory_size(msize as u8);
-            self.stream.set_peripheral_size(msize as u8);
-        }
-
         self.stream.apply_config(config);
     }
 
@@ -213,6 +261,9 @@ where
 
     /// Pauses the dma stream, the closure will be executed right before
     /// disabling the stream.
+    ///
+    /// The number of remaining items (NDTR) is preserved, use `resume` to continue where the
+    /// transfer left off.
     pub fn pause<F>(&mut self, f: F)
     where
         F: FnOnce(&mut PERIPHERAL),
@@ -223,6 +274,84 @@ where
         fence(Ordering::SeqCst);
     }
 
+    /// Returns `true` while the stream is enabled
+    ///
+    /// The hardware disables the stream once a non-circular transfer is complete or when a
+    /// transfer error occurs.
+    #[inline(always)]
+    pub fn is_running(&self) -> bool {
+        STREAM::is_enabled()
+    }
+
+    /// Returns the number of items left in the current transfer (NDTR)
+    ///
+    /// This only reads the stream's counter, so it is safe to call while the stream is running.
+    #[inline(always)]
+    pub fn remaining(&self) -> u16 {
+        STREAM::get_number_of_transfers()
+    }
+
+    /// Returns the index in the buffer of the next element to be transferred, which is the
+    /// number of elements transferred so far
+    ///
+    /// This is the buffer length minus [`remaining`](Self::remaining), which makes it
+    /// safe to call while the stream is running.
+    #[inline(always)]
+    pub fn position(&self) -> usize {
+        (self.buf_len - self.remaining()) as usize
+    }
+
+    /// Blocks until the transfer is complete
+    ///
+    /// Returns `DMAError::TransferError` if the stream stopped on a transfer error instead, see
+    /// [`has_errored`](Self::has_errored).
+    pub fn wait(&mut self) -> Result<(), DMAError> {
+        loop {
+            if self.has_errored() {
+                return Err(DMAError::TransferError);
+            }
+            if self.get_transfer_complete_flag() {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Returns `true` if the stream stopped on a transfer error (TEIF)
+    ///
+    /// The DMA disables the stream on a transfer error, usually caused by a bad address. The
+    /// flag stays set until the transfer is freed or restarted.
+    #[inline(always)]
+    pub fn has_errored(&self) -> bool {
+        self.get_transfer_error_flag()
+    }
+
+    /// Waits for the transfer to complete, see [`wait`](Self::wait)
+    ///
+    /// This uses the transfer complete and transfer error interrupts of the stream, so the
+    /// `on_interrupt` function of the stream, for example `Stream0::<DMA1>::on_interrupt`, has to
+    /// be called from its interrupt handler and the interrupt has to be unmasked in the NVIC.
+    #[cfg(feature = "async")]
+    pub async fn wait_async(&mut self) -> Result<(), DMAError> {
+        wait_for_flag(&mut self.stream, Event::TransferComplete).await
+    }
+
+    /// Waits for the transfer to complete and returns the underlying resources, see
+    /// [`wait_async`](Self::wait_async)
+    ///
+    /// The resources are returned along with the error on a transfer error.
+    #[cfg(feature = "async")]
+    #[allow(clippy::type_complexity)]
+    pub async fn complete(
+        mut self,
+    ) -> Result<(STREAM, PERIPHERAL, BUF), (DMAError, (STREAM, PERIPHERAL, BUF))> {
+        let result = self.wait_async().await;
+        let resources = self.free();
+        match result {
+            Ok(()) => Ok(resources),
+            Err(e) => Err((e, resources)),
+        }
+    }
+
     /// Stops the stream and returns the underlying resources.
     pub fn free(mut self) -> (STREAM, PERIPHERAL, BUF) {
         self.stream.disable();
@@ -242,6 +371,30 @@ where
         }
     }
 
+    /// Enable the interrupt of `event`
+    #[inline(always)]
+    pub fn listen(&mut self, event: Event) {
+        self.stream.listen(event);
+    }
+
+    /// Disable the interrupt of `event`
+    #[inline(always)]
+    pub fn unlisten(&mut self, event: Event) {
+        self.stream.unlisten(event);
+    }
+
+    /// Returns `true` if the flag of `event` is set
+    #[inline(always)]
+    pub fn is_pending(&self, event: Event) -> bool {
+        self.stream.is_pending(event)
+    }
+
+    /// Clear the flag of `event`
+    #[inline(always)]
+    pub fn clear_flag(&mut self, event: Event) {
+        self.stream.clear_flag(event);
+    }
+
     /// Clear all interrupts for the DMA stream.
     #[inline(always)]
     pub fn clear_interrupts(&mut self) {
@@ -276,6 +429,9 @@ where
         self.stream.clear_half_transfer_interrupt();
     }
 
+    /// Returns true once the first half of the buffer has been transferred (htif)
+    ///
+    /// See [`DmaConfig::half_transfer_interrupt`](super::config::DmaConfig::half_transfer_interrupt)
     #[inline(always)]
     pub fn get_half_transfer_flag(&self) -> bool {
         STREAM::get_half_transfer_flag()
@@ -305,48 +461,96 @@ where
 {
     transfer: Transfer<STREAM, PERIPHERAL, PeripheralToMemory, BUF, MutTransfer>,
     r_pos: usize,
+    /// Position the DMA was writing to at the last update of `unread`
+    w_pos: usize,
+    /// Number of elements written by the DMA but not yet read
+    unread: usize,
+    next_half: BufferHalf,
 }
 
-impl<STREAM, CONFIG, PERIPHERAL, BUF> CircTransfer<STREAM, PERIPHERAL, BUF>
+/// One of the two halves of the buffer of a [`CircTransfer`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BufferHalf {
+    First,
+    Second,
+}
+
+impl<STREAM, CONFIG, PERIPHERAL, BUF, W> CircTransfer<STREAM, PERIPHERAL, BUF>
 where
     STREAM: Stream<Config = CONFIG>,
-    BUF: StaticWriteBuffer + Deref,
-    <BUF as Deref>::Target:
-        Index<Range<usize>, Output = [<PERIPHERAL as TargetAddress<PeripheralToMemory>>::MemSize]>,
+    BUF: StaticWriteBuffer<Word = W> + Deref,
+    <BUF as Deref>::Target: Index<Range<usize>, Output = [W]>,
     PERIPHERAL: TargetAddress<PeripheralToMemory>,
-    <PERIPHERAL as TargetAddress<PeripheralToMemory>>::MemSize: Copy,
+    W: Copy,
 {
     /// Return the number of elements available to read
-    pub fn elements_available(&mut self) -> usize {
-        let blen = unsafe { self.transfer.buf.static_write_buffer().1 };
+    ///
+    /// Returns `DMAError::TransferError` if the stream stopped on a transfer error, see
+    /// [`has_errored`](Self::has_errored). Returns `DMAError::Overflow` if the DMA has written
+    /// a whole buffer length of data since the oldest unread element, meaning that unread data
+    /// was overwritten. All unread data is then dropped and reading continues with the data
+    /// the DMA writes next.
+    ///
+    /// The wrap arounds are counted using the transfer complete flag, so this should not be
+    /// mixed with [`next_buffer_half`](Self::next_buffer_half) or with clearing that flag
+    /// manually.
+    pub fn elements_available(&mut self) -> Result<usize, DMAError> {
+        if self.has_errored() {
+            return Err(DMAError::TransferError);
+        }
+
+        let blen = self.transfer.buf_len as usize;
+
+        // Read the flag before NDTR, so a wrap around in between shows up as a smaller write
+        // position instead of as an additional lap
+        let wrapped = self.transfer.get_transfer_complete_flag();
         let ndtr = STREAM::get_number_of_transfers() as usize;
-        let pos_at = self.r_pos;
 
         // the position the DMA would write to next
-        let pos_to = blen - ndtr;
+        let pos_to = (blen - ndtr) % blen;
 
-        if pos_at > pos_to {
-            // the buffer wraps around
-            blen + pos_to - pos_at
+        let written = if pos_to >= self.w_pos && !wrapped {
+            pos_to - self.w_pos
         } else {
-            // the buffer does not wrap around
-            pos_to - pos_at
+            // wrapped around once, possibly getting past the previous position again
+            self.transfer.clear_transfer_complete_interrupt();
+            blen + pos_to - self.w_pos
+        };
+        self.w_pos = pos_to;
+        self.unread += written;
+
+        // A full buffer can not be told apart from an empty one by the positions
+        if self.unread >= blen {
+            self.r_pos = pos_to;
+            self.unread = 0;
+            return Err(DMAError::Overflow);
         }
+
+        Ok(self.unread)
+    }
+
+    /// Returns `true` if the stream stopped on a transfer error (TEIF)
+    ///
+    /// The DMA disables the stream on a transfer error, usually caused by a bad address. The
+    /// flag stays set until the transfer is freed or restarted.
+    #[inline(always)]
+    pub fn has_errored(&self) -> bool {
+        self.transfer.has_errored()
     }
 
     /// Read the same number of elements as the provided buffer can hold.
     /// Blocks until the number of elements are available.
     ///
+    /// Returns an error on a transfer error or when unread data was overwritten, see
+    /// [`elements_available`](Self::elements_available).
+    ///
     /// # Panic
     ///
     /// This function panics if it tries to red more elements than the
     /// buffer of the transfer itself can hold.
     // TODO: fix the above limitation...
-    pub fn read_exact(
-        &mut self,
-        dat: &mut [<PERIPHERAL as TargetAddress<PeripheralToMemory>>::MemSize],
-    ) -> usize {
-        let blen = unsafe { self.transfer.buf.static_write_buffer().1 };
+    pub fn read_exact(&mut self, dat: &mut [W]) -> Result<usize, DMAError> {
+        let blen = self.transfer.buf_len as usize;
         let pos = self.r_pos;
         let read = dat.len();
 
@@ -355,7 +559,7 @@ where
             "Trying to read more than the DMA provided buffer can hold!"
         );
 
-        while self.elements_available() < read {}
+        while self.elements_available()? < read {}
 
         fence(Ordering::SeqCst);
 
@@ -376,24 +580,180 @@ where
             dat[left..read].copy_from_slice(&self.transfer.buf[0..read - left]);
             self.r_pos = read - left;
         }
+        self.unread -= read;
 
         fence(Ordering::SeqCst);
 
         // return the number of bytes read
-        read
+        Ok(read)
     }
 
-    pub fn read_available<'a>(
-        &mut self,
-        data: &'a mut [<PERIPHERAL as TargetAddress<PeripheralToMemory>>::MemSize],
-    ) -> &'a mut [<PERIPHERAL as TargetAddress<PeripheralToMemory>>::MemSize] {
-        let blen = unsafe { self.transfer.buf.static_write_buffer().1 };
-        let available = self.elements_available();
+    /// Read as many elements as are currently available, up to the length of `data`, and
+    /// return the part of `data` holding them
+    ///
+    /// Unlike [`read_exact`](Self::read_exact) this never blocks waiting for more data, the
+    /// returned slice is empty if nothing is available. See
+    /// [`elements_available`](Self::elements_available) for the errors.
+    pub fn read_available<'a>(&mut self, data: &'a mut [W]) -> Result<&'a mut [W], DMAError> {
+        let blen = self.transfer.buf_len as usize;
+        let available = self.elements_available()?;
         let len = data.len().min(available).min(blen - 1);
         let result = &mut data[0..len];
-        self.read_exact(result);
+        self.read_exact(result)?;
 
-        result
+        Ok(result)
+    }
+
+    /// Process the next completed half of the buffer (ping-pong buffering)
+    ///
+    /// The buffer is treated as two halves: `func` gets access to the half the DMA just
+    /// completed while the DMA keeps filling the other half. The halves are always handed
+    /// out alternately, starting with the first half.
+    ///
+    /// Returns `WouldBlock` until the next half is completed. Returns `DMAError::Overflow`
+    /// if the DMA has already completed the other half as well, meaning that it may have
+    /// started overwriting the data before or during `func`. The transfer then continues
+    /// with the half the DMA is currently filling.
+    ///
+    /// This uses the half transfer and transfer complete flags, so it should not be mixed
+    /// with `read_exact` and friends or with clearing those flags manually. Enable the
+    /// corresponding interrupts with [`Self::listen_buffer_halves`] to get an interrupt
+    /// every time a half completes.
+    pub fn next_buffer_half<F, T>(&mut self, func: F) -> nb::Result<T, DMAError>
+    where
+        F: FnOnce(&[W], BufferHalf) -> T,
+    {
+        let half = self.next_half;
+        let (done, other_done) = self.half_flags(half);
+        if !done {
+            return Err(nb::Error::WouldBlock);
+        }
+        if other_done {
+            self.recover_from_overflow();
+            return Err(nb::Error::Other(DMAError::Overflow));
+        }
+
+        match half {
+            BufferHalf::First => self.transfer.clear_half_transfer_interrupt(),
+            BufferHalf::Second => self.transfer.clear_transfer_complete_interrupt(),
+        }
+
+        // Protect the instruction sequence of the preceding flag check and the
+        // subsequent buffer access.
+        fence(Ordering::SeqCst);
+
+        let blen = self.transfer.buf_len as usize;
+        let range = match half {
+            BufferHalf::First => 0..blen / 2,
+            BufferHalf::Second => blen / 2..blen,
+        };
+        let result = func(&self.transfer.buf[range], half);
+
+        fence(Ordering::SeqCst);
+
+        // The DMA completing the other half while `func` was running means it has
+        // wrapped around into the half that was just processed.
+        if self.half_flags(half).1 {
+            self.recover_from_overflow();
+            return Err(nb::Error::Other(DMAError::Overflow));
+        }
+
+        self.next_half = match half {
+            BufferHalf::First => BufferHalf::Second,
+            BufferHalf::Second => BufferHalf::First,
+        };
+        Ok(result)
+    }
+
+    /// Enable the half transfer and transfer complete interrupts, which fire every time
+    /// a half of the buffer is completed, see [`Self::next_buffer_half`]
+    pub fn listen_buffer_halves(&mut self) {
+        self.transfer
+            .stream
+            .set_half_transfer_interrupt_enable(true);
+        self.transfer
+            .stream
+            .set_transfer_complete_interrupt_enable(true);
+    }
+
+    /// Disable the half transfer and transfer complete interrupts
+    pub fn unlisten_buffer_halves(&mut self) {
+        self.transfer
+            .stream
+            .set_half_transfer_interrupt_enable(false);
+        self.transfer
+            .stream
+            .set_transfer_complete_interrupt_enable(false);
+    }
+
+    /// Returns whether `half` and whether the other half have been completed
+    fn half_flags(&self, half: BufferHalf) -> (bool, bool) {
+        let ht = self.transfer.get_half_transfer_flag();
+        let tc = self.transfer.get_transfer_complete_flag();
+        match half {
+            BufferHalf::First => (ht, tc),
+            BufferHalf::Second => (tc, ht),
+        }
+    }
+
+    /// Waits for the next half of the buffer to be filled and passes it to `func`, see
+    /// [`next_buffer_half`](Self::next_buffer_half)
+    ///
+    /// This uses the half transfer, transfer complete and transfer error interrupts of the
+    /// stream, see [`Transfer::wait_async`] for the interrupt handler.
+    #[cfg(feature = "async")]
+    pub async fn next_half<F, T>(&mut self, func: F) -> Result<T, DMAError>
+    where
+        F: FnOnce(&[W], BufferHalf) -> T,
+    {
+        let event = match self.next_half {
+            BufferHalf::First => Event::HalfTransfer,
+            BufferHalf::Second => Event::TransferComplete,
+        };
+        wait_for_flag(&mut self.transfer.stream, event).await?;
+
+        self.next_buffer_half(func).map_err(|e| match e {
+            nb::Error::Other(e) => e,
+            nb::Error::WouldBlock => unreachable!(),
+        })
+    }
+
+    /// Drop the stale halves and continue with the half the DMA is currently filling
+    fn recover_from_overflow(&mut self) {
+        self.transfer.clear_half_transfer_interrupt();
+        self.transfer.clear_transfer_complete_interrupt();
+
+        self.next_half = self.current_half();
+    }
+
+    /// Returns the number of items left until the DMA wraps around to the start of the
+    /// buffer (NDTR)
+    ///
+    /// This only reads the stream's counter, so it is safe to call while the stream is running.
+    /// The DMA keeps going though, so the value is only a snapshot.
+    #[inline(always)]
+    pub fn remaining(&self) -> u16 {
+        self.transfer.remaining()
+    }
+
+    /// Returns the index in the buffer of the next element to be written by the DMA
+    ///
+    /// Like [`remaining`](Self::remaining) this is a snapshot of a running transfer.
+    #[inline(always)]
+    pub fn position(&self) -> usize {
+        self.transfer.position()
+    }
+
+    /// Returns the half of the buffer the DMA is currently writing to
+    ///
+    /// Like [`remaining`](Self::remaining) this is a snapshot of a running transfer.
+    pub fn current_half(&self) -> BufferHalf {
+        let blen = self.transfer.buf_len as usize;
+        if self.position() < blen / 2 {
+            BufferHalf::First
+        } else {
+            BufferHalf::Second
+        }
     }
 
     /// Starts the transfer, the closure will be executed right after enabling
@@ -414,11 +774,41 @@ where
         self.transfer.pause(f)
     }
 
+    /// Returns `true` while the stream is enabled
+    #[inline(always)]
+    pub fn is_running(&self) -> bool {
+        self.transfer.is_running()
+    }
+
     /// Stops the stream and returns the underlying resources.
     pub fn free(self) -> (STREAM, PERIPHERAL, BUF) {
         self.transfer.free()
     }
 
+    /// Enable the interrupt of `event`
+    #[inline(always)]
+    pub fn listen(&mut self, event: Event) {
+        self.transfer.stream.listen(event);
+    }
+
+    /// Disable the interrupt of `event`
+    #[inline(always)]
+    pub fn unlisten(&mut self, event: Event) {
+        self.transfer.stream.unlisten(event);
+    }
+
+    /// Returns `true` if the flag of `event` is set
+    #[inline(always)]
+    pub fn is_pending(&self, event: Event) -> bool {
+        self.transfer.stream.is_pending(event)
+    }
+
+    /// Clear the flag of `event`
+    #[inline(always)]
+    pub fn clear_flag(&mut self, event: Event) {
+        self.transfer.stream.clear_flag(event);
+    }
+
     /// Clear all interrupts for the DMA stream.
     #[inline(always)]
     pub fn clear_interrupts(&mut self) {
@@ -453,14 +843,299 @@ where
         self.transfer.clear_half_transfer_interrupt();
     }
 
+    /// Returns true once the first half of the buffer has been transferred (htif)
+    ///
+    /// See [`DmaConfig::half_transfer_interrupt`](super::config::DmaConfig::half_transfer_interrupt)
     #[inline(always)]
     pub fn get_half_transfer_flag(&self) -> bool {
         self.transfer.get_half_transfer_flag()
     }
 }
 
+/// Memory to peripheral transfer of several buffers, one after the other
+///
+/// The DMA has no support for linked lists, so the stream is reprogrammed with the next
+/// segment from its transfer complete interrupt by [`on_interrupt`](Self::on_interrupt). The
+/// peripheral stays enabled in between, it only has to wait for the short time it takes
+/// to run the interrupt handler.
+///
+/// ```ignore
+/// static HEADER: [u8; 2] = [0xAA, 0x55];
+/// let mut transfer = stream.into_chained_memory_to_peripheral_transfer(
+///     spi, [&HEADER, payload, crc], config,
+/// );
+/// transfer.start(|_spi| {});
+/// // From the interrupt handler of the stream
+/// if transfer.on_interrupt().unwrap() {
+///     // All segments have been handed to the peripheral
+/// }
+/// ```
+pub struct ChainedTransfer<STREAM, PERIPHERAL, W, const N: usize>
+where
+    STREAM: Stream,
+    PERIPHERAL: TargetAddress<MemoryToPeripheral>,
+    W: 'static,
+{
+    stream: STREAM,
+    peripheral: PERIPHERAL,
+    segments: [&'static [W]; N],
+    /// Index of the next segment to load into the stream
+    next: usize,
+    complete: bool,
+}
+
+impl<STREAM, PERIPHERAL, W, const N: usize> ChainedTransfer<STREAM, PERIPHERAL, W, N>
+where
+    STREAM: Stream<Config = DmaConfig>,
+    PERIPHERAL: TargetAddress<MemoryToPeripheral>,
+    <PERIPHERAL as TargetAddress<MemoryToPeripheral>>::MemSize: DmaWord,
+    W: BufferWord<<PERIPHERAL as TargetAddress<MemoryToPeripheral>>::MemSize> + 'static,
+{
+    /// Configures the stream for `peripheral`, the segments are loaded by `start`
+    ///
+    /// Memory increment and the transfer complete and transfer error interrupts are always
+    /// enabled while circular mode is always disabled.
+    ///
+    /// # Panics
+    ///
+    /// * When any of the segments is longer than (2^16 - 1)
+    pub(crate) fn init(
+        mut stream: STREAM,
+        peripheral: PERIPHERAL,
+        segments: [&'static [W]; N],
+        mut config: DmaConfig,
+    ) -> Self {
+        assert!(
+            segments.iter().all(|s| s.len() <= 65535),
+            "Hardware does not support more than 65535 transfers"
+        );
+
+        stream.disable();
+
+        fence(Ordering::SeqCst);
+
+        stream.set_direction(MemoryToPeripheral::direction());
+
+        // Set the peripheral address
+        //
+        // # Safety
+        //
+        // Must be a valid peripheral address
+        unsafe {
+            stream.set_peripheral_address(peripheral.address());
+        }
+
+        // NOTE(unsafe) The sizes match the element type of the segments and the register
+        // width of the peripheral, which is an invariant of TargetAddress
+        unsafe {
+            stream.set_memory_size(W::SIZE);
+            stream.set_peripheral_size(
+                <<PERIPHERAL as TargetAddress<MemoryToPeripheral>>::MemSize as DmaWord>::SIZE,
+            );
+        }
+
+        // Set the DMAMUX request line if needed
+        if let Some(request_line) = PERIPHERAL::REQUEST_LINE {
+            stream.set_request_line(request_line);
+        }
+
+        config.circular_buffer = false;
+        config.memory_increment = true;
+        config.transfer_complete_interrupt = true;
+        config.transfer_error_interrupt = true;
+        stream.clear_interrupts();
+        stream.apply_config(config);
+
+        Self {
+            stream,
+            peripheral,
+            segments,
+            next: 0,
+            complete: false,
+        }
+    }
+
+    /// Load the next non-empty segment into the disabled stream and enable it
+    ///
+    /// Returns `false` if there are no segments left
+    #[inline(always)]
+    fn load_next(&mut self) -> bool {
+        while let Some(segment) = self.segments.get(self.next) {
+            self.next += 1;
+            if segment.is_empty() {
+                continue;
+            }
+
+            // NOTE(unsafe) The segments are 'static and only ever read by the DMA
+            unsafe {
+                self.stream.set_memory_address(segment.as_ptr() as u32);
+            }
+            self.stream.set_number_of_transfers(segment.len() as u16);
+
+            // Preserve the instruction and bus ordering of preceding buffer access
+            // to the subsequent access by the DMA peripheral due to enabling it.
+            fence(Ordering::SeqCst);
+
+            unsafe {
+                self.stream.enable();
+            }
+            return true;
+        }
+        false
+    }
+
+    /// Starts the transfer from the first segment, the closure will be executed right
+    /// after enabling the stream.
+    pub fn start<F>(&mut self, f: F)
+    where
+        F: FnOnce(&mut PERIPHERAL),
+    {
+        self.stream.disable();
+        self.next = 0;
+        self.complete = !self.load_next();
+        f(&mut self.peripheral);
+    }
+
+    /// Continues with the next segment once the current one is done, has to be called from
+    /// the interrupt handler of the stream
+    ///
+    /// Returns `Ok(true)` once the last segment has been transferred. Note that this is
+    /// when the DMA has written the last element to the peripheral, which may still be
+    /// shifting it out.
+    ///
+    /// Do not call the `on_interrupt` function of the stream, for example
+    /// `Stream0::<DMA1>::on_interrupt`, for a chained transfer, that disables the interrupts
+    /// this relies on.
+    pub fn on_interrupt(&mut self) -> Result<bool, DMAError> {
+        // The hardware has disabled the stream
+        if self.stream.is_pending(Event::TransferError) {
+            self.stream.clear_flag(Event::TransferError);
+            return Err(DMAError::TransferError);
+        }
+
+        if self.stream.is_pending(Event::TransferComplete) {
+            // The stream has to be disabled to write the memory address and NDTR
+            self.stream.disable();
+            self.stream.clear_flag(Event::TransferComplete);
+            if !self.load_next() {
+                self.complete = true;
+            }
+        }
+
+        Ok(self.complete)
+    }
+
+    /// Returns `true` once the last segment has been transferred, see
+    /// [`on_interrupt`](Self::on_interrupt)
+    #[inline(always)]
+    pub fn is_complete(&self) -> bool {
+        self.complete
+    }
+
+    /// Returns `true` while the stream is enabled
+    #[inline(always)]
+    pub fn is_running(&self) -> bool {
+        STREAM::is_enabled()
+    }
+
+    /// Stops the stream and returns the underlying resources.
+    pub fn free(mut self) -> (STREAM, PERIPHERAL, [&'static [W]; N]) {
+        self.stream.disable();
+
+        // Protect the instruction and bus sequence of the preceding disable and
+        // the subsequent buffer access.
+        fence(Ordering::SeqCst);
+
+        self.stream.clear_interrupts();
+
+        unsafe {
+            let stream = ptr::read(&self.stream);
+            let peripheral = ptr::read(&self.peripheral);
+            let segments = self.segments;
+            mem::forget(self);
+            (stream, peripheral, segments)
+        }
+    }
+}
+
+impl<STREAM, PERIPHERAL, W, const N: usize> Drop for ChainedTransfer<STREAM, PERIPHERAL, W, N>
+where
+    STREAM: Stream,
+    PERIPHERAL: TargetAddress<MemoryToPeripheral>,
+    W: 'static,
+{
+    fn drop(&mut self) {
+        self.stream.disable();
+
+        // Protect the instruction and bus sequence of the preceding disable and
+        // the subsequent buffer access.
+        fence(Ordering::SeqCst);
+    }
+}
+
 macro_rules! impl_adc_overrun {
     ($($adc:ident, )*) => {$(
+        impl<STREAM, CONFIG, BUF> Transfer<STREAM, crate::adc::Adc<crate::stm32::$adc, crate::adc::DMA>, PeripheralToMemory, BUF, MutTransfer>
+        where
+            STREAM: Stream<Config = CONFIG>,
+        {
+            /// Stops the ADC and the stream and returns the underlying resources
+            ///
+            /// The ADC is stopped first and its DMA requests are disabled before the stream is
+            /// disabled, so no spurious request can touch the buffer after it is released.
+            pub fn abort(mut self) -> (STREAM, crate::adc::Adc<crate::stm32::$adc, crate::adc::Configured>, BUF) {
+                self.peripheral.stop_dma_requests();
+                // Disables the stream and clears its flags
+                let (stream, adc, buf) = self.free();
+                (stream, adc.disable_dma(), buf)
+            }
+
+            /// Returns `true` if the ADC lost at least one conversion result because the DMA did
+            /// not read the previous one in time (OVR)
+            ///
+            /// The flag stays set until [`clear_overrun_flag`](Self::clear_overrun_flag) is called.
+            /// The ADC does not issue any DMA request while it is set, so a circular transfer
+            /// stalls until then. What happens to the lost samples depends on
+            /// [`OverrunMode`](crate::adc::config::OverrunMode).
+            pub fn had_overrun(&self) -> bool {
+                self.peripheral.get_overrun_flag()
+            }
+
+            /// Clears the overrun flag, see [`had_overrun`](Self::had_overrun)
+            pub fn clear_overrun_flag(&mut self) {
+                self.peripheral.clear_overrun_flag();
+            }
+
+            /// Enable/disable the interrupt of the ADC on an overrun
+            pub fn set_overrun_interrupt(&mut self, enable: bool) {
+                self.peripheral.set_overrun_interrupt(enable);
+            }
+        }
+
+        impl<STREAM, CONFIG, BUF> CircTransfer<STREAM, crate::adc::Adc<crate::stm32::$adc, crate::adc::DMA>, BUF>
+        where
+            STREAM: Stream<Config = CONFIG>,
+        {
+            /// Stops the ADC and the stream and returns the underlying resources, see
+            /// [`Transfer::abort`]
+            pub fn abort(self) -> (STREAM, crate::adc::Adc<crate::stm32::$adc, crate::adc::Configured>, BUF) {
+                self.transfer.abort()
+            }
+
+            /// Returns `true` if the ADC lost at least one conversion result, see
+            /// [`Transfer::had_overrun`]
+            ///
+            /// The transfer stalls until the flag is cleared with `clear_overrun_flag`.
+            pub fn had_overrun(&self) -> bool {
+                self.transfer.had_overrun()
+            }
+
+            /// Enable/disable the interrupt of the ADC on an overrun
+            pub fn set_overrun_interrupt(&mut self, enable: bool) {
+                self.transfer.set_overrun_interrupt(enable);
+            }
+        }
+
         impl<STREAM, CONFIG, BUF> CircTransfer<STREAM, crate::adc::Adc<crate::stm32::$adc, crate::adc::DMA>, BUF>
         where
             STREAM: Stream<Config = CONFIG>,
@@ -531,20 +1206,53 @@ impl_serial_timeout!(USART1, USART2, USART3, UART4,);
 #[cfg(not(any(feature = "stm32g431", feature = "stm32g441")))]
 impl_serial_timeout!(UART5,);
 
+/// Waits for the flag of `event` or a transfer error using the interrupt of the stream
+#[cfg(feature = "async")]
+async fn wait_for_flag<STREAM: Stream>(stream: &mut STREAM, event: Event) -> Result<(), DMAError> {
+    poll_fn(|cx| {
+        cortex_m::interrupt::free(|cs| {
+            let mut slot = STREAM::waker().borrow(cs).borrow_mut();
+            slot.register(cx.waker());
+            slot.restore(stream);
+
+            if stream.is_pending(Event::TransferError) {
+                Poll::Ready(Err(DMAError::TransferError))
+            } else if stream.is_pending(event) {
+                Poll::Ready(Ok(()))
+            } else {
+                // Disabled again by `on_interrupt`
+                slot.arm(stream, event);
+                slot.arm(stream, Event::TransferError);
+                Poll::Pending
+            }
+        })
+    })
+    .await
+}
+
 pub trait TransferExt<STREAM>
 where
     STREAM: traits::Stream,
 {
-    fn into_memory_to_memory_transfer<PERIPHERAL, BUF, T>(
+    /// Copy the contents of `src` into `dst`
+    ///
+    /// Both memory and peripheral increment are enabled and the transfer size
+    /// is inferred from the element type of the buffers. The number of
+    /// elements copied is the length of `dst`.
+    ///
+    /// # Panics
+    ///
+    /// * When `src` holds fewer elements than `dst`
+    fn into_memory_to_memory_transfer<SRC, DST, W>(
         self,
-        per: PERIPHERAL,
-        buf: BUF,
+        src: SRC,
+        dst: DST,
         config: <STREAM as traits::Stream>::Config,
-    ) -> Transfer<STREAM, PERIPHERAL, MemoryToMemory<T>, BUF, MutTransfer>
+    ) -> Transfer<STREAM, MemorySource<SRC>, MemoryToMemory<W>, DST, MutTransfer>
     where
-        T: Into<u32>,
-        PERIPHERAL: TargetAddress<MemoryToMemory<T>>,
-        BUF: StaticWriteBuffer<Word = <PERIPHERAL as TargetAddress<MemoryToMemory<T>>>::MemSize>;
+        W: Into<u32> + DmaWord,
+        SRC: StaticReadBuffer<Word = W>,
+        DST: StaticWriteBuffer<Word = W>;
     fn into_peripheral_to_memory_transfer<PERIPHERAL, BUF>(
         self,
         per: PERIPHERAL,
@@ -553,7 +1261,10 @@ where
     ) -> Transfer<STREAM, PERIPHERAL, PeripheralToMemory, BUF, MutTransfer>
     where
         PERIPHERAL: TargetAddress<PeripheralToMemory>,
-        BUF: StaticWriteBuffer<Word = <PERIPHERAL as TargetAddress<PeripheralToMemory>>::MemSize>;
+        <PERIPHERAL as TargetAddress<PeripheralToMemory>>::MemSize: DmaWord,
+        BUF: StaticWriteBuffer,
+        <BUF as StaticWriteBuffer>::Word:
+            BufferWord<<PERIPHERAL as TargetAddress<PeripheralToMemory>>::MemSize>;
     fn into_memory_to_peripheral_transfer<PERIPHERAL, BUF>(
         self,
         per: PERIPHERAL,
@@ -562,7 +1273,10 @@ where
     ) -> Transfer<STREAM, PERIPHERAL, MemoryToPeripheral, BUF, ConstTransfer>
     where
         PERIPHERAL: TargetAddress<MemoryToPeripheral>,
-        BUF: StaticReadBuffer<Word = <PERIPHERAL as TargetAddress<MemoryToPeripheral>>::MemSize>;
+        <PERIPHERAL as TargetAddress<MemoryToPeripheral>>::MemSize: DmaWord,
+        BUF: StaticReadBuffer,
+        <BUF as StaticReadBuffer>::Word:
+            BufferWord<<PERIPHERAL as TargetAddress<MemoryToPeripheral>>::MemSize>;
     fn into_peripheral_to_peripheral_transfer<PSRC, PDST>(
         self,
         src: PSRC,
@@ -572,9 +1286,11 @@ where
     where
         PSRC: TargetAddress<PeripheralToMemory>,
         PDST: TargetAddress<MemoryToPeripheral>,
-        [<PDST as TargetAddress<MemoryToPeripheral>>::MemSize; 1]: embedded_dma::WriteTarget,
-        &'static mut [<PDST as TargetAddress<MemoryToPeripheral>>::MemSize; 1]:
-            StaticWriteBuffer<Word = <PSRC as TargetAddress<PeripheralToMemory>>::MemSize>;
+        <PSRC as TargetAddress<PeripheralToMemory>>::MemSize: DmaWord,
+        <PDST as TargetAddress<MemoryToPeripheral>>::MemSize:
+            BufferWord<<PSRC as TargetAddress<PeripheralToMemory>>::MemSize>,
+        [<PDST as TargetAddress<MemoryToPeripheral>>::MemSize; 1]:
+            embedded_dma::WriteTarget<Word = <PDST as TargetAddress<MemoryToPeripheral>>::MemSize>;
     fn into_circ_peripheral_to_memory_transfer<PERIPHERAL, BUF>(
         self,
         per: PERIPHERAL,
@@ -583,13 +1299,23 @@ where
     ) -> CircTransfer<STREAM, PERIPHERAL, BUF>
     where
         PERIPHERAL: TargetAddress<PeripheralToMemory>,
-        <PERIPHERAL as TargetAddress<PeripheralToMemory>>::MemSize: Copy,
-        BUF: StaticWriteBuffer<Word = <PERIPHERAL as TargetAddress<PeripheralToMemory>>::MemSize>,
-        BUF: Deref,
-        <BUF as Deref>::Target: Index<
-            Range<usize>,
-            Output = [<PERIPHERAL as TargetAddress<PeripheralToMemory>>::MemSize],
-        >;
+        <PERIPHERAL as TargetAddress<PeripheralToMemory>>::MemSize: DmaWord,
+        BUF: StaticWriteBuffer + Deref,
+        <BUF as StaticWriteBuffer>::Word:
+            BufferWord<<PERIPHERAL as TargetAddress<PeripheralToMemory>>::MemSize>,
+        <BUF as Deref>::Target: Index<Range<usize>, Output = [<BUF as StaticWriteBuffer>::Word]>;
+
+    /// Send `segments` to `per` one after the other, see [`ChainedTransfer`]
+    fn into_chained_memory_to_peripheral_transfer<PERIPHERAL, W, const N: usize>(
+        self,
+        per: PERIPHERAL,
+        segments: [&'static [W]; N],
+        config: <STREAM as traits::Stream>::Config,
+    ) -> ChainedTransfer<STREAM, PERIPHERAL, W, N>
+    where
+        PERIPHERAL: TargetAddress<MemoryToPeripheral>,
+        <PERIPHERAL as TargetAddress<MemoryToPeripheral>>::MemSize: DmaWord,
+        W: BufferWord<<PERIPHERAL as TargetAddress<MemoryToPeripheral>>::MemSize> + 'static;
 }
 
 macro_rules! transfer_constructor {
@@ -600,21 +1326,28 @@ macro_rules! transfer_constructor {
             crate::stm32::$DMA: crate::dma::stream::Instance,
             Self: traits::Stream,
         {
-            fn into_memory_to_memory_transfer<PERIPHERAL, BUF, T>(
+            fn into_memory_to_memory_transfer<SRC, DST, W>(
                 self,
-                per: PERIPHERAL,
-                buf: BUF,
+                src: SRC,
+                mut dst: DST,
                 mut config: <Self as traits::Stream>::Config,
-            ) -> Transfer<Self, PERIPHERAL, MemoryToMemory<T>, BUF, MutTransfer>
+            ) -> Transfer<Self, MemorySource<SRC>, MemoryToMemory<W>, DST, MutTransfer>
             where
-                T: Into<u32>,
-                PERIPHERAL: TargetAddress<MemoryToMemory<T>>,
-                BUF: StaticWriteBuffer<
-                    Word = <PERIPHERAL as TargetAddress<MemoryToMemory<T>>>::MemSize,
-                >,
+                W: Into<u32> + DmaWord,
+                SRC: StaticReadBuffer<Word = W>,
+                DST: StaticWriteBuffer<Word = W>,
             {
+                let src = MemorySource::new(src);
+                let (_, dst_len) = unsafe { dst.static_write_buffer() };
+                assert!(
+                    src.len() >= dst_len,
+                    "Source buffer is smaller than the destination buffer"
+                );
+
                 config.circular_buffer = false;
-                Transfer::init(self, per, buf, config)
+                config.memory_increment = true;
+                config.peripheral_increment = true;
+                Transfer::init(self, src, dst, config)
             }
             fn into_peripheral_to_memory_transfer<PERIPHERAL, BUF>(
                 self,
@@ -624,9 +1357,10 @@ macro_rules! transfer_constructor {
             ) -> Transfer<Self, PERIPHERAL, PeripheralToMemory, BUF, MutTransfer>
             where
                 PERIPHERAL: TargetAddress<PeripheralToMemory>,
-                BUF: StaticWriteBuffer<
-                    Word = <PERIPHERAL as TargetAddress<PeripheralToMemory>>::MemSize,
-                >,
+                <PERIPHERAL as TargetAddress<PeripheralToMemory>>::MemSize: DmaWord,
+                BUF: StaticWriteBuffer,
+                <BUF as StaticWriteBuffer>::Word:
+            BufferWord<<PERIPHERAL as TargetAddress<PeripheralToMemory>>::MemSize>,
             {
                 Transfer::init(self, per, buf, config)
             }
@@ -638,9 +1372,10 @@ macro_rules! transfer_constructor {
             ) -> Transfer<Self, PERIPHERAL, MemoryToPeripheral, BUF, ConstTransfer>
             where
                 PERIPHERAL: TargetAddress<MemoryToPeripheral>,
-                BUF: StaticReadBuffer<
-                    Word = <PERIPHERAL as TargetAddress<MemoryToPeripheral>>::MemSize,
-                >,
+                <PERIPHERAL as TargetAddress<MemoryToPeripheral>>::MemSize: DmaWord,
+                BUF: StaticReadBuffer,
+                <BUF as StaticReadBuffer>::Word:
+            BufferWord<<PERIPHERAL as TargetAddress<MemoryToPeripheral>>::MemSize>,
             {
                 Transfer::init_const(self, per, buf, config)
             }
@@ -653,9 +1388,12 @@ macro_rules! transfer_constructor {
             where
                 PSRC: TargetAddress<PeripheralToMemory>,
                 PDST: TargetAddress<MemoryToPeripheral>,
-                [<PDST as TargetAddress<MemoryToPeripheral>>::MemSize; 1]: embedded_dma::WriteTarget,
-                &'static mut [<PDST as TargetAddress<MemoryToPeripheral>>::MemSize; 1]:
-                StaticWriteBuffer<Word = <PSRC as TargetAddress<PeripheralToMemory>>::MemSize> {
+                <PSRC as TargetAddress<PeripheralToMemory>>::MemSize: DmaWord,
+                <PDST as TargetAddress<MemoryToPeripheral>>::MemSize:
+            BufferWord<<PSRC as TargetAddress<PeripheralToMemory>>::MemSize>,
+                [<PDST as TargetAddress<MemoryToPeripheral>>::MemSize; 1]: embedded_dma::WriteTarget<
+                    Word = <PDST as TargetAddress<MemoryToPeripheral>>::MemSize,
+                > {
                 let data_addr: u32 = dst.address();
                 let ptr: &mut PDST::MemSize = unsafe { &mut *(data_addr as *mut _) };
                  //TODO: check that this is correct; nightly has core::array::from_mut...
@@ -669,16 +1407,32 @@ macro_rules! transfer_constructor {
                 config: <Self as traits::Stream>::Config,
             ) -> CircTransfer<Self, PERIPHERAL, BUF>
             where PERIPHERAL: TargetAddress<PeripheralToMemory>,
-                <PERIPHERAL as TargetAddress<PeripheralToMemory>>::MemSize: Copy,
-                BUF: StaticWriteBuffer<Word = <PERIPHERAL as TargetAddress<PeripheralToMemory>>::MemSize>,
-                BUF: Deref,
-                <BUF as Deref>::Target: Index< Range<usize>,
-                    Output = [<PERIPHERAL as TargetAddress<PeripheralToMemory>>::MemSize], > {
+                <PERIPHERAL as TargetAddress<PeripheralToMemory>>::MemSize: DmaWord,
+                BUF: StaticWriteBuffer + Deref,
+                <BUF as StaticWriteBuffer>::Word:
+            BufferWord<<PERIPHERAL as TargetAddress<PeripheralToMemory>>::MemSize>,
+                <BUF as Deref>::Target: Index<Range<usize>, Output = [<BUF as StaticWriteBuffer>::Word]> {
                 CircTransfer {
                     transfer: Transfer::init(self, per, buf, config),
                     r_pos: 0,
+                    w_pos: 0,
+                    unread: 0,
+                    next_half: BufferHalf::First,
                 }
             }
+            fn into_chained_memory_to_peripheral_transfer<PERIPHERAL, W, const N: usize>(
+                self,
+                per: PERIPHERAL,
+                segments: [&'static [W]; N],
+                config: <Self as traits::Stream>::Config,
+            ) -> ChainedTransfer<Self, PERIPHERAL, W, N>
+            where
+                PERIPHERAL: TargetAddress<MemoryToPeripheral>,
+                <PERIPHERAL as TargetAddress<MemoryToPeripheral>>::MemSize: DmaWord,
+                W: BufferWord<<PERIPHERAL as TargetAddress<MemoryToPeripheral>>::MemSize> + 'static,
+            {
+                ChainedTransfer::init(self, per, segments, config)
+            }
         }
         )+
     };
@@ -691,14 +1445,18 @@ transfer_constructor!(
     (DMA1, Stream3),
     (DMA1, Stream4),
     (DMA1, Stream5),
-    (DMA1, Stream6),
-    (DMA1, Stream7),
     (DMA2, Stream0),
     (DMA2, Stream1),
     (DMA2, Stream2),
     (DMA2, Stream3),
     (DMA2, Stream4),
     (DMA2, Stream5),
+);
+
+#[cfg(not(any(feature = "stm32g431", feature = "stm32g441")))]
+transfer_constructor!(
+    (DMA1, Stream6),
+    (DMA1, Stream7),
     (DMA2, Stream6),
     (DMA2, Stream7),
 );
@@ -45,7 +45,14 @@ pub struct Output<MODE> {
 /// Push pull output (type state)
 pub struct PushPull;
 
-/// GPIO Pin speed selection
+/// GPIO Pin speed selection (OSPEEDR)
+///
+/// Sets the slew rate of outputs and alternate functions, pins default to `Low`. Higher speeds
+/// give sharper edges at the cost of more noise, see the datasheet for the max frequency of
+/// each setting. Typically `High` or `VeryHigh` is needed for fast SPI clocks.
+///
+/// Fast-mode Plus (1MHz) I2C does not depend on this setting but on the Fm+ drive of the pin,
+/// enabled with the `I2Cx_FMP` bits in `SYSCFG_CFGR1`.
 pub enum Speed {
     Low = 0,
     Medium = 1,
@@ -82,12 +89,29 @@ pub const AF14: u8 = 14;
 pub const AF15: u8 = 15;
 
 /// External Interrupt Pin
+///
+/// Implemented for all pins, see the `button` example:
+///
+/// ```ignore
+/// let mut button = gpioc.pc13.into_pull_down_input();
+/// button.make_interrupt_source(&mut syscfg);
+/// button.trigger_on_edge(&mut exti, SignalEdge::Rising);
+/// button.enable_interrupt(&mut exti);
+/// // ...and in the EXTI15_10 handler
+/// button.clear_interrupt_pending_bit();
+/// ```
 pub trait ExtiPin {
+    /// Connect the EXTI line of this pin number to this port (SYSCFG_EXTICRx)
     fn make_interrupt_source(&mut self, syscfg: &mut SysCfg);
+    /// Select the edge(s) triggering the EXTI line (EXTI_RTSR1/FTSR1)
     fn trigger_on_edge(&mut self, exti: &mut EXTI, level: SignalEdge);
+    /// Unmask the interrupt (EXTI_IMR1)
     fn enable_interrupt(&mut self, exti: &mut EXTI);
+    /// Mask the interrupt (EXTI_IMR1)
     fn disable_interrupt(&mut self, exti: &mut EXTI);
+    /// Clear the pending flag (EXTI_PR1)
     fn clear_interrupt_pending_bit(&mut self);
+    /// Returns `true` if the pending flag is set (EXTI_PR1)
     fn check_interrupt(&self) -> bool;
 }
 
@@ -179,7 +203,7 @@ macro_rules! exti {
                 let offset = 4 * ($i % 4);
                 syscfg.$exticri.modify(|r, w| unsafe {
                     let mut exticr = r.bits();
-                    exticr = (exticr & !(0xf << offset)) | ($extigpionr << offset); //FIXME: clears other pins
+                    exticr = (exticr & !(0xf << offset)) | ($extigpionr << offset);
                     w.bits(exticr)
                 });
             }
@@ -233,6 +257,96 @@ macro_rules! exti {
     };
 }
 
+/// Fully erased pin, both the port and the pin number are stored at runtime
+///
+/// This allows collecting pins of different ports into an array, see `erase`.
+pub struct ErasedPin<MODE> {
+    port: u8,
+    i: u8,
+    _mode: PhantomData<MODE>,
+}
+
+impl<MODE> ErasedPin<MODE> {
+    /// Port of the pin, 0 for GPIOA, 1 for GPIOB and so on
+    pub fn port_id(&self) -> u8 {
+        self.port
+    }
+
+    /// Number of the pin within its port
+    pub fn pin_id(&self) -> u8 {
+        self.i
+    }
+
+    fn block(&self) -> &crate::stm32::gpioa::RegisterBlock {
+        // NOTE(unsafe) The GPIO ports are 0x400 apart and the IDR, ODR and BSRR registers used by
+        // the erased pin are at the same offsets in all of them
+        unsafe {
+            &*(crate::stm32::GPIOA::ptr() as *const u8)
+                .add(self.port as usize * 0x400)
+                .cast::<crate::stm32::gpioa::RegisterBlock>()
+        }
+    }
+
+    fn idr_is_low(&self) -> bool {
+        self.block().idr.read().bits() & (1 << self.i) == 0
+    }
+}
+
+impl<MODE> hal::digital::v2::OutputPin for ErasedPin<Output<MODE>> {
+    type Error = ();
+
+    fn set_high(&mut self) -> Result<(), ()> {
+        // NOTE(unsafe) atomic write to a stateless register
+        self.block().bsrr.write(|w| unsafe { w.bits(1 << self.i) });
+        Ok(())
+    }
+
+    fn set_low(&mut self) -> Result<(), ()> {
+        // NOTE(unsafe) atomic write to a stateless register
+        self.block()
+            .bsrr
+            .write(|w| unsafe { w.bits(1 << (self.i + 16)) });
+        Ok(())
+    }
+}
+
+impl<MODE> hal::digital::v2::StatefulOutputPin for ErasedPin<Output<MODE>> {
+    fn is_set_high(&self) -> Result<bool, ()> {
+        let is_set_high = !self.is_set_low()?;
+        Ok(is_set_high)
+    }
+
+    fn is_set_low(&self) -> Result<bool, ()> {
+        Ok(self.block().odr.read().bits() & (1 << self.i) == 0)
+    }
+}
+
+impl<MODE> hal::digital::v2::toggleable::Default for ErasedPin<Output<MODE>> {}
+
+impl<MODE> hal::digital::v2::InputPin for ErasedPin<Output<MODE>> {
+    type Error = ();
+
+    fn is_high(&self) -> Result<bool, ()> {
+        Ok(!self.idr_is_low())
+    }
+
+    fn is_low(&self) -> Result<bool, ()> {
+        Ok(self.idr_is_low())
+    }
+}
+
+impl<MODE> hal::digital::v2::InputPin for ErasedPin<Input<MODE>> {
+    type Error = ();
+
+    fn is_high(&self) -> Result<bool, ()> {
+        Ok(!self.idr_is_low())
+    }
+
+    fn is_low(&self) -> Result<bool, ()> {
+        Ok(self.idr_is_low())
+    }
+}
+
 macro_rules! gpio {
     ($GPIOX:ident, $gpiox:ident, $iopxenr:ident, $PXx:ident, $Pxn:expr, [
         $($PXi:ident: ($pxi:ident, $i:expr, $exticri:ident),)+
@@ -272,6 +386,18 @@ macro_rules! gpio {
                 _mode: PhantomData<MODE>,
             }
 
+            impl<MODE> $PXx<MODE> {
+                /// Set pin speed
+                pub fn set_speed(&mut self, speed: Speed) {
+                    let offset = 2 * self.i;
+                    unsafe {
+                        (*$GPIOX::ptr()).ospeedr.modify(|r, w| {
+                            w.bits((r.bits() & !(0b11 << offset)) | ((speed as u32) << offset))
+                        })
+                    }
+                }
+            }
+
             impl<MODE> OutputPin for $PXx<Output<MODE>> {
                 type Error = ();
 
@@ -337,6 +463,50 @@ macro_rules! gpio {
             exti_erased!($PXx<Output<MODE>>, $Pxn);
             exti_erased!($PXx<Input<MODE>>, $Pxn);
 
+            /// Output pins of this port which are written together with a single BSRR store
+            ///
+            /// All pins change at the same time, without the read-modify-write or the skew of
+            /// setting the pins one by one.
+            pub struct OutputGroup<MODE, const N: usize> {
+                pins: [$PXx<Output<MODE>>; N],
+                mask: u16,
+            }
+
+            impl<MODE, const N: usize> OutputGroup<MODE, N> {
+                /// Group the given pins, see `downgrade` for getting the pins as `$PXx`
+                pub fn new(pins: [$PXx<Output<MODE>>; N]) -> Self {
+                    let mask = pins.iter().fold(0, |mask, pin| mask | (1 << pin.i));
+                    OutputGroup { pins, mask }
+                }
+
+                /// Mask of the pins in this group, bit n is set if pin n is in the group
+                pub fn mask(&self) -> u16 {
+                    self.mask
+                }
+
+                /// Set the pins in `mask` to the corresponding bits of `value`, other pins are
+                /// left untouched
+                ///
+                /// Panics if `mask` contains pins not in this group.
+                pub fn write_pins(&mut self, mask: u16, value: u16) {
+                    assert!(mask & !self.mask == 0, "pin not in this group");
+                    let set = (mask & value) as u32;
+                    let reset = (mask & !value) as u32;
+                    // NOTE(unsafe) atomic write to a stateless register, only touching owned pins
+                    unsafe { (*$GPIOX::ptr()).bsrr.write(|w| w.bits(set | (reset << 16))) };
+                }
+
+                /// Set all pins in the group to the corresponding bits of `value`
+                pub fn write(&mut self, value: u16) {
+                    self.write_pins(self.mask, value);
+                }
+
+                /// Release the pins
+                pub fn release(self) -> [$PXx<Output<MODE>>; N] {
+                    self.pins
+                }
+            }
+
             $(
                 pub struct $PXi<MODE> {
                     _mode: PhantomData<MODE>,
@@ -424,6 +594,10 @@ macro_rules! gpio {
                     }
 
                     /// Configures the pin to operate as an analog pin
+                    ///
+                    /// This is all that is needed to connect the pin to the ADC, comparators,
+                    /// opamps and DAC outputs. Unlike the STM32L47x/L48x the G4 has no analog
+                    /// switch register (GPIOx_ASCR) to close.
                     pub fn into_analog(self) -> $PXi<Analog> {
                         let offset = 2 * $i;
                         unsafe {
@@ -562,6 +736,14 @@ macro_rules! gpio {
                     pub fn downgrade(self) -> $PXx<Output<MODE>> {
                         $PXx { i: $i, _mode: self._mode }
                     }
+
+                    /// Erases the port and pin number from the type
+                    ///
+                    /// Unlike `downgrade` this also allows collecting pins of different ports
+                    /// into an array
+                    pub fn erase(self) -> ErasedPin<Output<MODE>> {
+                        ErasedPin { port: $Pxn, i: $i, _mode: PhantomData }
+                    }
                 }
 
                 impl<MODE> OutputPin for $PXi<Output<MODE>> {
@@ -619,6 +801,14 @@ macro_rules! gpio {
                     pub fn downgrade(self) -> $PXx<Input<MODE>> {
                         $PXx { i: $i, _mode: self._mode }
                     }
+
+                    /// Erases the port and pin number from the type
+                    ///
+                    /// Unlike `downgrade` this also allows collecting pins of different ports
+                    /// into an array
+                    pub fn erase(self) -> ErasedPin<Input<MODE>> {
+                        ErasedPin { port: $Pxn, i: $i, _mode: PhantomData }
+                    }
                 }
 
                 impl<MODE> InputPin for $PXi<Input<MODE>> {
@@ -644,6 +834,11 @@ macro_rules! gpio {
                 pub fn get_id (&self) -> u8 {
                     self.i
                 }
+
+                /// Erases the port from the type, see `ErasedPin`
+                pub fn erase(self) -> ErasedPin<TYPE> {
+                    ErasedPin { port: $Pxn, i: self.i, _mode: PhantomData }
+                }
             }
         }
     }
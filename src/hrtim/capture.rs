@@ -1,6 +1,6 @@
 use core::marker::PhantomData;
 
-use stm32g4::stm32g474::{HRTIM_TIMA, HRTIM_TIMB, HRTIM_TIMC, HRTIM_TIMD, HRTIM_TIME, HRTIM_TIMF};
+use crate::stm32::{HRTIM_TIMA, HRTIM_TIMB, HRTIM_TIMC, HRTIM_TIMD, HRTIM_TIME, HRTIM_TIMF};
 
 pub struct Ch1;
 pub struct Ch2;
@@ -151,3 +151,96 @@ impl_capture! {
     HRTIM_TIMF: Ch1, cpt1fr, cpt1fcr, cpt1x, timfdier, timficr, timfisr, cpt1ie, cpt1c, cpt1,
     HRTIM_TIMF: Ch2, cpt2fr, cpt2fcr, cpt2x, timfdier, timficr, timfisr, cpt2ie, cpt2c, cpt2
 }
+
+/// Result of [`ExternalMeasurement::poll`]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Copy, Clone, Debug)]
+pub struct FrequencyDutyMeasurement {
+    /// Number of timer ticks between the last two rising edges
+    pub period_ticks: u32,
+
+    /// Number of timer ticks the signal was high during the measured period
+    pub high_ticks: u32,
+
+    /// The falling edge capture was missing or did not lie within the measured period,
+    /// `high_ticks` should not be trusted.
+    ///
+    /// This typically means a capture was overwritten since the last call to `poll`.
+    pub stale: bool,
+}
+
+impl FrequencyDutyMeasurement {
+    /// The fraction of the period the signal was high, in the range 0.0 to 1.0
+    pub fn duty(&self) -> f32 {
+        self.high_ticks as f32 / self.period_ticks as f32
+    }
+}
+
+/// Frequency and duty measurement of an external signal using both capture units of a timer
+///
+/// Created using `HrTim::measure_external`. Capture 1 is used for the rising edges and
+/// capture 2 for the falling edges.
+///
+/// NOTE: This assumes the timer is counting up and that the period of the measured signal is
+/// shorter than the period of the timer, so that the counter wraps at most once between two
+/// rising edges.
+pub struct ExternalMeasurement {
+    last_rise: Option<u16>,
+}
+
+impl ExternalMeasurement {
+    pub(super) fn new() -> Self {
+        ExternalMeasurement { last_rise: None }
+    }
+
+    /// Check for a new measurement
+    ///
+    /// Returns `None` if no new rising edge has been captured since the last call, for the very
+    /// first rising edge and if a rising edge was captured while reading the measurement, as
+    /// the captures can not be matched up then. The next measurement starts from that edge.
+    ///
+    /// The hardware only keeps the latest capture, so call this at least once per period of the
+    /// measured signal, for example from the capture 1 interrupt. If more than one rising edge
+    /// happens between two calls `period_ticks` spans several periods, this can not be
+    /// detected.
+    pub fn poll<T: super::timer::HrSlaveTimer>(
+        &mut self,
+        timer: &mut T,
+    ) -> Option<FrequencyDutyMeasurement> {
+        if !timer.capture_ch1().is_pending() {
+            return None;
+        }
+
+        // The counter runs from 0 to period, both inclusive
+        let wrap = u32::from(timer.get_period()) + 1;
+        let ticks_since = |from: u16, to: u16| (u32::from(to) + wrap - u32::from(from)) % wrap;
+
+        let (rise, _) = timer.capture_ch1().get();
+        timer.capture_ch1().clear_interrupt();
+
+        let fall_pending = timer.capture_ch2().is_pending();
+        let (fall, _) = timer.capture_ch2().get();
+        timer.capture_ch2().clear_interrupt();
+
+        // A rising edge captured after reading `rise` means `fall` might belong to the next
+        // period, or that the edge was lost when clearing the flag
+        let (latest_rise, _) = timer.capture_ch1().get();
+        if latest_rise != rise || timer.capture_ch1().is_pending() {
+            timer.capture_ch1().clear_interrupt();
+            self.last_rise = Some(latest_rise);
+            return None;
+        }
+
+        let last_rise = self.last_rise.replace(rise)?;
+
+        let period_ticks = ticks_since(last_rise, rise);
+        let high_ticks = ticks_since(last_rise, fall);
+        let stale = !fall_pending || high_ticks >= period_ticks;
+
+        Some(FrequencyDutyMeasurement {
+            period_ticks,
+            high_ticks: if stale { 0 } else { high_ticks },
+            stale,
+        })
+    }
+}
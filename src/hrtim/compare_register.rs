@@ -1,12 +1,79 @@
 use core::marker::PhantomData;
 
+use super::{timer::HrTimer, HrtimPrescaler};
+use crate::rcc::Clocks;
 use crate::stm32::{
     HRTIM_MASTER, HRTIM_TIMA, HRTIM_TIMB, HRTIM_TIMC, HRTIM_TIMD, HRTIM_TIME, HRTIM_TIMF,
 };
+use crate::time::NanoSecond;
 
 pub trait HrCompareRegister {
     fn get_duty(&self) -> u16;
     fn set_duty(&mut self, duty: u16);
+
+    /// Set the compare value to `fraction` (`0.0..=1.0`) of the period of `timer`
+    ///
+    /// The value is rounded to the nearest tick and clamped to the period as well as the
+    /// minimum and maximum compare values allowed with the prescaler of `timer`.
+    fn set_duty_fraction<T>(&mut self, timer: &T, fraction: f32)
+    where
+        T: HrTimer,
+        T::Prescaler: HrtimPrescaler,
+    {
+        let period = timer.get_period();
+        let fraction = fraction.clamp(0.0, 1.0);
+        let duty = (fraction * f32::from(period) + 0.5) as u32;
+        self.set_duty(clamp_duty::<T::Prescaler>(duty, period));
+    }
+
+    /// Set the compare value to `time` after the start of the period of `timer`
+    ///
+    /// The value is rounded to the nearest tick and clamped to the period as well as the
+    /// minimum and maximum compare values allowed with the prescaler of `timer`.
+    fn set_time<T>(&mut self, timer: &T, clocks: &Clocks, time: NanoSecond)
+    where
+        T: HrTimer,
+        T::Prescaler: HrtimPrescaler,
+    {
+        let tick_freq = clocks.hrtim_clk.raw() / u64::from(T::Prescaler::VALUE);
+        // Saturate instead of overflowing for long times, these are clamped to the period anyway
+        let ticks = u64::from(time.ticks())
+            .saturating_mul(tick_freq)
+            .saturating_add(500_000_000)
+            / 1_000_000_000;
+        let duty = ticks.min(u64::from(u32::MAX)) as u32;
+        self.set_duty(clamp_duty::<T::Prescaler>(duty, timer.get_period()));
+    }
+}
+
+/// Clamp `duty` to `period` and the compare values allowed with the prescaler
+fn clamp_duty<PSCL: HrtimPrescaler>(duty: u32, period: u16) -> u16 {
+    let max = period.min(PSCL::MAX_CR);
+    duty.clamp(u32::from(PSCL::MIN_CR), u32::from(max)) as u16
+}
+
+/// Compare register with its timer and prescaler types erased
+///
+/// Created using `erase` on any of the compare registers. This is useful for
+/// storing compare registers from different timers in the same struct or array.
+pub struct ErasedCompareRegister {
+    /// Address of the CMPxyR register
+    ptr: *mut u32,
+}
+
+// SAFETY: The erased register is the sole owner of its compare register
+unsafe impl Send for ErasedCompareRegister {}
+
+impl HrCompareRegister for ErasedCompareRegister {
+    fn get_duty(&self) -> u16 {
+        // SAFETY: ptr points to a valid CMPxyR register which is owned by us
+        unsafe { core::ptr::read_volatile(self.ptr) as u16 }
+    }
+    fn set_duty(&mut self, duty: u16) {
+        // SAFETY: ptr points to a valid CMPxyR register which is owned by us.
+        // Only the lower 16 bits of the register are used, the rest are reserved.
+        unsafe { core::ptr::write_volatile(self.ptr, u32::from(duty)) }
+    }
 }
 
 pub struct HrCr1<TIM, PSCL>(PhantomData<(TIM, PSCL)>);
@@ -49,6 +116,15 @@ macro_rules! hrtim_cr_helper {
             }
         }
 
+        impl<PSCL> $cr_type<$TIMX, PSCL> {
+            /// Erase the timer and prescaler types of this compare register
+            pub fn erase(self) -> ErasedCompareRegister {
+                let tim = unsafe { &*$TIMX::ptr() };
+
+                ErasedCompareRegister { ptr: tim.$cmpXYr.as_ptr() }
+            }
+        }
+
         $(
             /// Compare match event
             impl<PSCL> super::event::EventSource<$TIMX, PSCL> for $cr_type<$TIMX, PSCL> {
@@ -139,11 +215,16 @@ hrtim_cr! {
 
 macro_rules! hrtim_master_cr {
     ($($cr_type:ident: $cr_index:expr),*) => {$(
-        /// Compare match event for neighbor timer
+        /// Master timer compare match event, usable by the outputs of all timers
+        ///
+        /// This allows the timers to share the master timer as a common time base, for example
+        /// to phase shift interleaved converters using one master compare per timer.
+        /// Note that the master timer compares are not capture sources.
         impl<DST, PSCL> super::event::EventSource<DST, PSCL> for $cr_type<HRTIM_MASTER, PSCL> {
             const BITS: u32 = 1 << ($cr_index + 7); // MSTCMP1 is at bit 8 etc
         }
 
+        /// Master timer compare match event, resets the counter of any timer
         impl<DST, PSCL> super::event::TimerResetEventSource<DST, PSCL> for $cr_type<HRTIM_MASTER, PSCL> {
             const BITS: u32 = 1 << ($cr_index + 4); // MSTCMP1 is at bit 5
         }
@@ -2,28 +2,30 @@ use core::marker::PhantomData;
 
 use crate::{
     hrtim::fault::{
-        FltMonitor1, FltMonitor2, FltMonitor3, FltMonitor4, FltMonitor5, FltMonitor6, FltMonitorSys,
+        FltMonitor1, FltMonitor2, FltMonitor3, FltMonitor4, FltMonitor5, FltMonitor6,
+        FltMonitorSys, SoftwareFault, SoftwareFaultError,
+    },
+    rcc::Rcc,
+    stm32::{
+        HRTIM_COMMON, HRTIM_MASTER, HRTIM_TIMA, HRTIM_TIMB, HRTIM_TIMC, HRTIM_TIMD, HRTIM_TIME,
+        HRTIM_TIMF,
     },
-    rcc::{Enable, Rcc, Reset},
-    stm32::{HRTIM_COMMON, RCC},
 };
 
 use super::{external_event::EevInputs, fault::FaultInputs};
 
 pub trait HrControltExt {
-    fn hr_control(self, _rcc: &mut Rcc) -> HrTimOngoingCalibration;
+    /// Enable the HRTIM and start the DLL calibration without waiting for it to finish
+    ///
+    /// Use `poll` or `wait_for_calibration` on the result to get hold of the peripheral.
+    fn hr_control(self, rcc: &mut Rcc) -> HrTimOngoingCalibration;
 }
 
 impl HrControltExt for HRTIM_COMMON {
-    fn hr_control(self, _rcc: &mut Rcc) -> HrTimOngoingCalibration {
+    fn hr_control(self, rcc: &mut Rcc) -> HrTimOngoingCalibration {
         let common = unsafe { &*HRTIM_COMMON::ptr() };
 
-        unsafe {
-            let rcc_ptr = &*RCC::ptr();
-
-            HRTIM_COMMON::enable(rcc_ptr);
-            HRTIM_COMMON::reset(rcc_ptr);
-        }
+        rcc.enable_and_reset::<HRTIM_COMMON>();
 
         // Start calibration procedure
         common
@@ -45,6 +47,8 @@ impl HrControltExt for HRTIM_COMMON {
 
             flt_divider: SamplingClkDiv::None,
             eev_divider: SamplingClkDiv::None,
+
+            periodic_calibration: None,
         }
     }
 }
@@ -64,6 +68,8 @@ pub struct HrTimOngoingCalibration {
 
     flt_divider: SamplingClkDiv,
     eev_divider: SamplingClkDiv,
+
+    periodic_calibration: Option<CalibrationRate>,
 }
 
 impl HrTimOngoingCalibration {
@@ -86,15 +92,18 @@ impl HrTimOngoingCalibration {
 
             flt_divider,
             eev_divider,
+            periodic_calibration,
         } = self;
 
         unsafe {
-            // Enable periodic calibration
-            // with f_hrtim at 170MHz, these settings leads to
-            // a period of about 6.2ms
+            // Configure periodic calibration, if requested
+            let (rate, enable) = match periodic_calibration {
+                Some(rate) => (rate as u8, true),
+                None => (CalibrationRate::Cycles1048576 as u8, false),
+            };
             common
                 .dllcr
-                .modify(|_r, w| w.calrte().bits(0b00).cal().set_bit().calen().clear_bit());
+                .modify(|_r, w| w.calrte().bits(rate).cal().set_bit().calen().bit(enable));
             common.fltinr2.write(|w| w.fltsd().bits(flt_divider as u8));
             common.eecr3.write(|w| w.eevsd().bits(eev_divider as u8));
 
@@ -128,20 +137,50 @@ impl HrTimOngoingCalibration {
         }
     }
 
-    pub fn wait_for_calibration(self) -> (HrTimCalibrated, FaultInputs, EevInputs) {
+    /// Returns `true` once the initial calibration is done
+    pub fn is_done(&self) -> bool {
         let common = unsafe { &*HRTIM_COMMON::ptr() };
-        while common.isr.read().dllrdy().bit_is_clear() {
-            // Wait until ready
+        common.isr.read().dllrdy().bit_is_set()
+    }
+
+    /// Non blocking version of `wait_for_calibration`
+    ///
+    /// Returns `self` back as the error while the calibration is still ongoing, so that other
+    /// setup may be done in the meantime.
+    pub fn poll(self) -> Result<(HrTimCalibrated, FaultInputs, EevInputs), Self> {
+        if !self.is_done() {
+            return Err(self);
         }
 
         // Calibration is now done, it is safe to continue
         unsafe { self.init() };
 
-        (
+        Ok((
             HrTimCalibrated { _x: PhantomData },
             unsafe { FaultInputs::new() },
             unsafe { EevInputs::new() },
-        )
+        ))
+    }
+
+    /// Block until the calibration is done, this may take more than 1ms
+    ///
+    /// See `poll` for a non blocking alternative
+    pub fn wait_for_calibration(mut self) -> (HrTimCalibrated, FaultInputs, EevInputs) {
+        loop {
+            match self.poll() {
+                Ok(res) => return res,
+                Err(this) => self = this,
+            }
+        }
+    }
+
+    /// Enable periodic recalibration at the specified rate (CALEN, CALRTE)
+    ///
+    /// Periodic calibration compensates for temperature and voltage drift of the DLL.
+    /// It is disabled by default.
+    pub fn enable_periodic_calibration(mut self, rate: CalibrationRate) -> Self {
+        self.periodic_calibration = Some(rate);
+        self
     }
 
     pub fn set_adc1_trigger_psc(mut self, post_scaler: AdcTriggerPostscaler) -> Self {
@@ -208,6 +247,115 @@ impl HrTimCalibrated {
     }
 }
 
+impl HrTimCalibrated {
+    /// Same as `constrain` but also hands out one control handle per timer
+    ///
+    /// The handles in [`HrTimControls`] only touch the bits belonging to their own timer,
+    /// so they may be moved to different tasks without having to share the `HrPwmControl`.
+    pub fn split(self) -> (HrPwmControl, HrTimControls) {
+        let timer_controls = HrTimControls {
+            master: HrTimControl { _x: PhantomData },
+            tima: HrTimControl { _x: PhantomData },
+            timb: HrTimControl { _x: PhantomData },
+            timc: HrTimControl { _x: PhantomData },
+            timd: HrTimControl { _x: PhantomData },
+            time: HrTimControl { _x: PhantomData },
+            timf: HrTimControl { _x: PhantomData },
+        };
+
+        (self.constrain(), timer_controls)
+    }
+}
+
+/// Per timer control handles, see [`HrTimCalibrated::split`]
+pub struct HrTimControls {
+    pub master: HrTimControl<HRTIM_MASTER>,
+    pub tima: HrTimControl<HRTIM_TIMA>,
+    pub timb: HrTimControl<HRTIM_TIMB>,
+    pub timc: HrTimControl<HRTIM_TIMC>,
+    pub timd: HrTimControl<HRTIM_TIMD>,
+    pub time: HrTimControl<HRTIM_TIME>,
+    pub timf: HrTimControl<HRTIM_TIMF>,
+}
+
+/// Handle to the parts of the common HRTIM registers that belong to one timer
+pub struct HrTimControl<TIM> {
+    _x: PhantomData<TIM>,
+}
+
+/// Bits in CR2 that are not trigger bits and thus have to be preserved on write
+const CR2_SWAP_MASK: u32 = 0b11_1111 << 16;
+
+macro_rules! impl_tim_control {
+    ($($TIMX:ident: $tXswu:ident, $tXrst:ident, $tXudis:ident $(, $out_bits:expr)*;)+) => {$(
+        impl HrTimControl<$TIMX> {
+            /// Force an update of the preloaded registers of this timer
+            ///
+            /// The bit is cleared by hardware once the update has taken place
+            pub fn software_update(&mut self) {
+                let common = unsafe { &*HRTIM_COMMON::ptr() };
+                cortex_m::interrupt::free(|_| {
+                    // SAFETY: Only the swap bits are kept, writing 0 to the other
+                    // trigger bits has no effect
+                    common.cr2.modify(|r, w| unsafe {
+                        w.bits(r.bits() & CR2_SWAP_MASK).$tXswu().set_bit()
+                    });
+                });
+            }
+
+            /// Reset the counter of this timer
+            ///
+            /// The bit is cleared by hardware once the reset has taken place
+            pub fn software_reset(&mut self) {
+                let common = unsafe { &*HRTIM_COMMON::ptr() };
+                cortex_m::interrupt::free(|_| {
+                    // SAFETY: See `software_update`
+                    common.cr2.modify(|r, w| unsafe {
+                        w.bits(r.bits() & CR2_SWAP_MASK).$tXrst().set_bit()
+                    });
+                });
+            }
+
+            /// Disable/enable the transfer of preloaded registers into the active registers
+            ///
+            /// This is useful for updating multiple registers without risking an update
+            /// event occuring in between.
+            pub fn set_update_disabled(&mut self, disabled: bool) {
+                let common = unsafe { &*HRTIM_COMMON::ptr() };
+                cortex_m::interrupt::free(|_| {
+                    common.cr1.modify(|_r, w| w.$tXudis().bit(disabled));
+                });
+            }
+
+            $(
+                /// Enable both outputs of this timer
+                pub fn enable_outputs(&mut self) {
+                    let common = unsafe { &*HRTIM_COMMON::ptr() };
+                    // SAFETY: OENR is a set-only register, writing 0 has no effect
+                    common.oenr.write(|w| unsafe { w.bits($out_bits) });
+                }
+
+                /// Disable both outputs of this timer
+                pub fn disable_outputs(&mut self) {
+                    let common = unsafe { &*HRTIM_COMMON::ptr() };
+                    // SAFETY: ODISR is a set-only register, writing 0 has no effect
+                    common.odisr.write(|w| unsafe { w.bits($out_bits) });
+                }
+            )*
+        }
+    )+};
+}
+
+impl_tim_control! {
+    HRTIM_MASTER: mswu, mrst, mudis;
+    HRTIM_TIMA: taswu, tarst, taudis, 0b11;
+    HRTIM_TIMB: tbswu, tbrst, tbudis, 0b11 << 2;
+    HRTIM_TIMC: tcswu, tcrst, tcudis, 0b11 << 4;
+    HRTIM_TIMD: tdswu, tdrst, tdudis, 0b11 << 6;
+    HRTIM_TIME: teswu, terst, teudis, 0b11 << 8;
+    HRTIM_TIMF: tfswu, tfrst, tfudis, 0b11 << 10;
+}
+
 pub struct HrPwmControl {
     _x: PhantomData<()>,
 
@@ -232,6 +380,35 @@ pub struct HrPwmControl {
     pub adc_trigger10: Adc10Trigger,
 }
 
+impl HrPwmControl {
+    /// Assert `fault` from software
+    ///
+    /// All outputs with this fault enabled, see `with_fault_source`, are put into the state
+    /// given by their `FaultAction`. This lasts until the fault is cleared through the
+    /// corresponding `FaultMonitor`, the same way as for a fault triggered by hardware.
+    ///
+    /// Returns `Err(SoftwareFaultError::Locked)` unless the fault input was finalized with
+    /// `SourceBuilder::allow_software_fault` and has not been locked with `lock_fault` since.
+    pub fn force_fault<FS: SoftwareFault>(&mut self, fault: &FS) -> Result<(), SoftwareFaultError> {
+        fault.force()
+    }
+
+    /// Lock the configuration of a fault input finalized with
+    /// `SourceBuilder::allow_software_fault`
+    ///
+    /// The fault can no longer be asserted from software afterwards. The lock is only released
+    /// by a reset of the HRTIM.
+    pub fn lock_fault<FS: SoftwareFault>(&mut self, fault: &FS) {
+        fault.lock();
+    }
+
+    /// The system fault, triggered by clock failure and the sources connected with
+    /// `FltMonitorSys::connect_sources`
+    pub fn system_fault(&mut self) -> &mut FltMonitorSys {
+        &mut self.fault_sys
+    }
+}
+
 macro_rules! impl_adc1234_trigger {
     ($($t:ident: [$trait_:ident, $adcXr:ident, $variant345:ident $(, $variant12:ident)*]),*) => {$(
         pub struct $t {
@@ -344,6 +521,21 @@ pub enum AdcTriggerPostscaler {
     Div32 = 31,
 }
 
+/// Period of the periodic DLL calibration (CALRTE), in number of f_hrtim cycles
+pub enum CalibrationRate {
+    /// About 6.2ms with f_hrtim at 170MHz
+    Cycles1048576 = 0b00,
+
+    /// About 0.77ms with f_hrtim at 170MHz
+    Cycles131072 = 0b01,
+
+    /// About 96µs with f_hrtim at 170MHz
+    Cycles16384 = 0b10,
+
+    /// About 12µs with f_hrtim at 170MHz
+    Cycles2048 = 0b11,
+}
+
 /// The divsion ratio between f_hrtim and the fault signal sampling clock for digital filters
 pub enum SamplingClkDiv {
     /// No division
@@ -0,0 +1,115 @@
+use core::marker::PhantomData;
+
+use super::timer::HrTim;
+use crate::dma::{mux::DmaMuxResources, traits::TargetAddress, PeripheralToMemory};
+use crate::stm32::{
+    HRTIM_MASTER, HRTIM_TIMA, HRTIM_TIMB, HRTIM_TIMC, HRTIM_TIMD, HRTIM_TIME, HRTIM_TIMF,
+};
+
+/// Event generating a DMA request of a HRTIM timer, see `HrTim::enable_dma_request`
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum DmaEvent {
+    Compare1,
+    Compare2,
+    Compare3,
+    Compare4,
+    Repetition,
+    Update,
+
+    /// Not available for the master timer
+    Capture1,
+
+    /// Not available for the master timer
+    Capture2,
+
+    /// Not available for the master timer
+    Reset,
+}
+
+impl DmaEvent {
+    /// Bit in TIMxDIER
+    pub(super) fn mask(self) -> u32 {
+        match self {
+            DmaEvent::Compare1 => 1 << 16,
+            DmaEvent::Compare2 => 1 << 17,
+            DmaEvent::Compare3 => 1 << 18,
+            DmaEvent::Compare4 => 1 << 19,
+            DmaEvent::Repetition => 1 << 20,
+            DmaEvent::Update => 1 << 22,
+            DmaEvent::Capture1 => 1 << 23,
+            DmaEvent::Capture2 => 1 << 24,
+            DmaEvent::Reset => 1 << 29,
+        }
+    }
+
+    pub(super) fn is_available_on_master(self) -> bool {
+        !matches!(
+            self,
+            DmaEvent::Capture1 | DmaEvent::Capture2 | DmaEvent::Reset
+        )
+    }
+}
+
+/// Register read by the DMA on every DMA request of the HRTIM timer `TIM`
+///
+/// Used as the source of a peripheral to memory transfer this builds up a log of, for
+/// example, the counter value or the level of some pins without any CPU involvement.
+///
+/// ```ignore
+/// timer.enable_dma_request(DmaEvent::Compare1);
+/// let source = HrDmaSource::counter(&timer);
+/// let transfer = stream.into_peripheral_to_memory_transfer(source, buffer, config);
+/// ```
+pub struct HrDmaSource<TIM> {
+    address: u32,
+    _timer: PhantomData<TIM>,
+}
+
+impl<TIM> HrDmaSource<TIM> {
+    /// Read the register at `address` on every DMA request of the timer
+    ///
+    /// # Safety
+    ///
+    /// `address` has to be the address of a 32 bit register which is safe to read at any time,
+    /// for example a GPIO IDR.
+    pub unsafe fn new(address: u32) -> Self {
+        HrDmaSource {
+            address,
+            _timer: PhantomData,
+        }
+    }
+}
+
+macro_rules! hrtim_dma {
+    ($($TIMX:ident: $cntXr:ident,)+) => {$(
+        impl HrDmaSource<$TIMX> {
+            /// Read the counter of the timer on every DMA request of the timer
+            pub fn counter<PSCL>(_timer: &HrTim<$TIMX, PSCL>) -> Self {
+                let tim = unsafe { &*$TIMX::ptr() };
+
+                // SAFETY: The counter register is always safe to read
+                unsafe { Self::new(tim.$cntXr.as_ptr() as u32) }
+            }
+        }
+
+        unsafe impl TargetAddress<PeripheralToMemory> for HrDmaSource<$TIMX> {
+            type MemSize = u32;
+
+            const REQUEST_LINE: Option<u8> = Some(DmaMuxResources::$TIMX as u8);
+
+            fn address(&self) -> u32 {
+                self.address
+            }
+        }
+    )+};
+}
+
+hrtim_dma! {
+    HRTIM_MASTER: mcntr,
+    HRTIM_TIMA: cntar,
+    HRTIM_TIMB: cntr,
+    HRTIM_TIMC: cntcr,
+    HRTIM_TIMD: cntdr,
+    HRTIM_TIME: cnter,
+    HRTIM_TIMF: cntfr,
+}
@@ -5,7 +5,9 @@ use crate::gpio::gpiob::{PB3, PB4, PB5, PB6, PB7, PB8, PB9};
 use crate::gpio::gpioc::{PC11, PC12, PC5, PC6};
 use crate::gpio::{self, AF13, AF3};
 use crate::pwm::Polarity;
-use crate::stm32::HRTIM_COMMON;
+use crate::stm32::{
+    ADC1, ADC2, ADC3, ADC4, ADC5, HRTIM_COMMON, TIM1, TIM2, TIM3, TIM6, TIM7, TIM8,
+};
 
 use super::control::HrTimCalibrated;
 
@@ -90,6 +92,53 @@ macro_rules! impl_eev_input {
     };
 }
 
+/// Analog watchdog `AWD` of `ADC` used as source for an external event
+///
+/// NOTE: The analog watchdog itself has to be configured on the ADC. The event is
+/// active for as long as the watchdog flag of the ADC is set, so the flag needs to be
+/// cleared in software for the event to go inactive again.
+pub struct AdcAwdSource<ADC, const AWD: u8> {
+    _x: PhantomData<ADC>,
+}
+
+impl<ADC: crate::adc::TriggerType, const AWD: u8> AdcAwdSource<ADC, AWD> {
+    pub fn new<STATUS>(_adc: &crate::adc::Adc<ADC, STATUS>) -> Self {
+        AdcAwdSource { _x: PhantomData }
+    }
+}
+
+// Sources not connected to any pin or comparator
+//
+// See RM0440 'External events mapping and associated features'
+macro_rules! impl_eev_internal_src {
+    ($($N:literal: $src:ty => $src_bits:literal,)+) => {$(
+        unsafe impl EevSrcBits<$N> for $src {
+            const SRC_BITS: u8 = $src_bits;
+        }
+    )+};
+}
+
+impl_eev_internal_src! {
+    // Trigger output of a timer, see `Timer::set_trigger_source`
+    1: &crate::timer::Timer<TIM1> => 0b10,
+    2: &crate::timer::Timer<TIM2> => 0b10,
+    3: &crate::timer::Timer<TIM3> => 0b10,
+    7: &crate::timer::Timer<TIM7> => 0b10,
+    9: &crate::timer::Timer<TIM6> => 0b10,
+    10: &crate::timer::Timer<TIM8> => 0b10,
+
+    // Analog watchdog outputs
+    1: AdcAwdSource<ADC1, 1> => 0b11,
+    2: AdcAwdSource<ADC1, 2> => 0b11,
+    3: AdcAwdSource<ADC1, 3> => 0b11,
+    4: AdcAwdSource<ADC2, 1> => 0b11,
+    5: AdcAwdSource<ADC2, 2> => 0b11,
+    6: AdcAwdSource<ADC2, 3> => 0b11,
+    7: AdcAwdSource<ADC3, 1> => 0b11,
+    8: AdcAwdSource<ADC4, 1> => 0b11,
+    10: AdcAwdSource<ADC5, 1> => 0b11,
+}
+
 impl_eev_input!(1: COMP = [COMP2], PINS = [(PC12, AF3)]);
 impl_eev_input!(2: COMP = [COMP4], PINS = [(PC11, AF3)]);
 impl_eev_input!(3: COMP = [COMP6], PINS = [(PB7, AF13)]);
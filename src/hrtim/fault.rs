@@ -8,6 +8,7 @@ use crate::gpio::{self, AF13, AF3};
 use crate::hrtim::control::HrPwmControl;
 use crate::pwm::FaultMonitor;
 use crate::stm32::HRTIM_COMMON;
+use crate::syscfg::SysCfg;
 
 pub enum FaultAction {
     /// Output never enters fault mode
@@ -29,6 +30,32 @@ pub unsafe trait FaultSource: Copy {
     const ENABLE_BITS: u8;
 }
 
+/// Fault sources which may be asserted from software, see [`HrPwmControl::force_fault`]
+pub trait SoftwareFault: FaultSource {
+    #[doc(hidden)]
+    fn force(&self) -> Result<(), SoftwareFaultError>;
+
+    #[doc(hidden)]
+    fn lock(&self);
+}
+
+/// Error of [`HrPwmControl::force_fault`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SoftwareFaultError {
+    /// The configuration of the fault input is locked, see
+    /// [`SourceBuilder::allow_software_fault`]
+    Locked,
+
+    /// The fault was not latched in time, for example because of a very long filter
+    Timeout,
+}
+
+/// Number of polls of the fault flag before `SoftwareFault::force` gives up
+///
+/// The slowest filter needs 256 periods of f_flts, which is at most f_hrtim / 8, so this
+/// leaves a wide margin with one poll per HRTIM clock cycle.
+const FORCE_FAULT_TIMEOUT: u32 = 10_000;
+
 pub struct SourceBuilder<I> {
     _input: I,
     src_bits: u8,
@@ -38,6 +65,12 @@ pub struct SourceBuilder<I> {
 
     /// FLTxF[3:0]
     filter_bits: u8,
+
+    /// FLTxLCK
+    lock: bool,
+
+    /// FLTxBLKE and FLTxBLKS
+    blanking: Option<FaultBlanking>,
 }
 
 impl<I> SourceBuilder<I> {
@@ -47,6 +80,8 @@ impl<I> SourceBuilder<I> {
             src_bits,
             is_active_high: false,
             filter_bits: 0b0000,
+            lock: true,
+            blanking: None,
         }
     }
 }
@@ -56,7 +91,8 @@ macro_rules! impl_faults {
         $input:ident => $source:ident:
             PINS=[($pin:ident, $af:ident) $(,($pin_b:ident, $af_b:ident))*],
             COMP=$compX:ident, $enable_bits:literal,
-            $fltinrZ:ident, $fltWsrc_0:ident, $fltWsrc_1:ident, $fltWp:ident, $fltWf:ident, $fltWe:ident, $fltWlck:ident,
+            $fltinrZ:ident, $fltWsrc_0:ident, $fltWsrc_1:ident, $fltWp:ident, $fltWf:ident, $fltWe:ident, $fltWlck:ident, $fltW:ident,
+            $fltinrB:ident, $fltWblke:ident, $fltWblks:ident,
     )+) => {$(
 
         // This should NOT be Copy/Clone
@@ -94,12 +130,16 @@ macro_rules! impl_faults {
 
         impl SourceBuilder<$input> {
             pub fn finalize(self, _control: &mut HrPwmControl) -> $source {
-                let SourceBuilder{ _input, src_bits, is_active_high, filter_bits } = self;
+                let SourceBuilder{ _input, src_bits, is_active_high, filter_bits, lock, blanking } = self;
 
                 // Setup fault source
                 unsafe {
                     let common = &*HRTIM_COMMON::ptr();
 
+                    common.$fltinrB.modify(|_r, w| w
+                        .$fltWblke().bit(blanking.is_some())
+                        .$fltWblks().bit(blanking == Some(FaultBlanking::MovingWindow))
+                    );
                     common.fltinr2.modify(|_r, w| w.$fltWsrc_1().bit(src_bits & 0b10 != 0));
                     common.$fltinrZ.modify(|_r, w| w
                         .$fltWsrc_0().bit(src_bits & 0b01 != 0)
@@ -109,7 +149,9 @@ macro_rules! impl_faults {
                     );
 
                     // ... and lock configuration
-                    common.$fltinrZ.modify(|_r, w| w.$fltWlck().set_bit());
+                    if lock {
+                        common.$fltinrZ.modify(|_r, w| w.$fltWlck().set_bit());
+                    }
                 }
 
                 $source {
@@ -122,28 +164,98 @@ macro_rules! impl_faults {
                 self
             }
 
-            // TODO: add more settings
-            /* pub fn blanking(?) -> Self */
+            /// Ignore the fault input during a blanking window of the associated timer
+            ///
+            /// This is useful to mask out switching noise, for example a current spike right
+            /// after the output turns on. Fault input 1 is blanked by timer A, input 2 by timer B
+            /// and so on up to fault input 6 which is blanked by timer F.
+            pub fn blanking(mut self, blanking: FaultBlanking) -> Self {
+                self.blanking = Some(blanking);
+                self
+            }
 
+            /// Set the digital filter of the fault input
+            ///
+            /// A fault is only detected once the input has been active for the number of
+            /// consecutive samples given by `filter`, which rejects short glitches.
             pub fn filter(mut self, filter: FaultSamplingFilter) -> Self {
                 self.filter_bits = filter as u8;
                 self
             }
+
+            /// Do not lock the configuration of this fault input
+            ///
+            /// This is required for the fault to be asserted from software using
+            /// [`HrPwmControl::force_fault`]. The polarity, filter and enable bits of the
+            /// fault input stay writable until they are locked with
+            /// [`HrPwmControl::lock_fault`].
+            pub fn allow_software_fault(mut self) -> Self {
+                self.lock = false;
+                self
+            }
         }
 
         unsafe impl FaultSource for $source {
             const ENABLE_BITS: u8 = $enable_bits;
         }
+
+        impl SoftwareFault for $source {
+            fn force(&self) -> Result<(), SoftwareFaultError> {
+                let common = unsafe { &*HRTIM_COMMON::ptr() };
+
+                if common.$fltinrZ.read().$fltWlck().bit_is_set() {
+                    return Err(SoftwareFaultError::Locked);
+                }
+
+                // FLTxP may only be written while the fault input is disabled. The register is
+                // shared with other fault inputs, so this must not be interrupted.
+                let set_polarity = |is_active_high: bool| {
+                    cortex_m::interrupt::free(|_| {
+                        common.$fltinrZ.modify(|_r, w| w.$fltWe().clear_bit());
+                        common.$fltinrZ.modify(|_r, w| w.$fltWp().bit(is_active_high));
+                        common.$fltinrZ.modify(|_r, w| w.$fltWe().set_bit());
+                    })
+                };
+
+                // Flip the polarity so that the current, inactive, level of the input
+                // is seen as a fault...
+                let is_active_high = common.$fltinrZ.read().$fltWp().bit();
+                set_polarity(!is_active_high);
+
+                // ...wait for it to pass the filter and be latched...
+                let is_latched = (0..FORCE_FAULT_TIMEOUT)
+                    .any(|_| common.isr.read().$fltW().bit_is_set());
+
+                // ...then restore the polarity. The outputs stay in their fault state
+                // until the fault is cleared.
+                set_polarity(is_active_high);
+
+                if is_latched {
+                    Ok(())
+                } else {
+                    Err(SoftwareFaultError::Timeout)
+                }
+            }
+
+            fn lock(&self) {
+                let common = unsafe { &*HRTIM_COMMON::ptr() };
+
+                // The register is shared with other fault inputs
+                cortex_m::interrupt::free(|_| {
+                    common.$fltinrZ.modify(|_r, w| w.$fltWlck().set_bit())
+                });
+            }
+        }
     )+}
 }
 
 impl_faults!(
-    FaultInput1 => FaultSource1: PINS=[(PA12, AF13)], COMP=COMP2, 0b000001, fltinr1, flt1src, flt1src_1, flt1p, flt1f, flt1e, flt1lck,
-    FaultInput2 => FaultSource2: PINS=[(PA15, AF13)], COMP=COMP4, 0b000010, fltinr1, flt2src, flt2src_1, flt2p, flt2f, flt2e, flt2lck,
-    FaultInput3 => FaultSource3: PINS=[(PB10, AF13)], COMP=COMP6, 0b000100, fltinr1, flt3src, flt3src_1, flt3p, flt3f, flt3e, flt3lck,
-    FaultInput4 => FaultSource4: PINS=[(PB11, AF13)], COMP=COMP1, 0b001000, fltinr1, flt4src, flt4src_1, flt4p, flt4f, flt4e, flt4lck,
-    FaultInput5 => FaultSource5: PINS=[(PB0, AF13), (PC7, AF3)], COMP=COMP3, 0b010000, fltinr2, flt5src, flt5src_1, flt5p, flt5f, flt5e, flt5lck,
-    FaultInput6 => FaultSource6: PINS=[(PC10, AF13)], COMP=COMP5, 0b100000, fltinr2, flt6src_0, flt6src_1, flt6p, flt6f, flt6e, flt6lck,
+    FaultInput1 => FaultSource1: PINS=[(PA12, AF13)], COMP=COMP2, 0b000001, fltinr1, flt1src, flt1src_1, flt1p, flt1f, flt1e, flt1lck, flt1, fltinr3, flt1blke, flt1blks,
+    FaultInput2 => FaultSource2: PINS=[(PA15, AF13)], COMP=COMP4, 0b000010, fltinr1, flt2src, flt2src_1, flt2p, flt2f, flt2e, flt2lck, flt2, fltinr3, flt2blke, flt2blks,
+    FaultInput3 => FaultSource3: PINS=[(PB10, AF13)], COMP=COMP6, 0b000100, fltinr1, flt3src, flt3src_1, flt3p, flt3f, flt3e, flt3lck, flt3, fltinr3, flt3blke, flt3blks,
+    FaultInput4 => FaultSource4: PINS=[(PB11, AF13)], COMP=COMP1, 0b001000, fltinr1, flt4src, flt4src_1, flt4p, flt4f, flt4e, flt4lck, flt4, fltinr3, flt4blke, flt4blks,
+    FaultInput5 => FaultSource5: PINS=[(PB0, AF13), (PC7, AF3)], COMP=COMP3, 0b010000, fltinr2, flt5src, flt5src_1, flt5p, flt5f, flt5e, flt5lck, flt5, fltinr4, flt5blke, flt5blks,
+    FaultInput6 => FaultSource6: PINS=[(PC10, AF13)], COMP=COMP5, 0b100000, fltinr2, flt6src_0, flt6src_1, flt6p, flt6f, flt6e, flt6lck, flt6, fltinr4, flt6blke, flt6blks,
 );
 
 pub struct FaultInputs {
@@ -168,6 +280,18 @@ impl FaultInputs {
     }
 }
 
+/// Blanking window of a fault input, see [`SourceBuilder::blanking`]
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum FaultBlanking {
+    /// The fault input is ignored from the counter reset or roll-over until the compare 3
+    /// event of the associated timer
+    ResetAligned,
+
+    /// The fault input is ignored during a moving window set by the compare 3 event of
+    /// the associated timer, see the reference manual for details
+    MovingWindow,
+}
+
 pub enum FaultSamplingFilter {
     /// No filtering, fault acts asynchronously
     ///
@@ -227,11 +351,31 @@ pub enum FaultSamplingFilter {
 }
 
 macro_rules! impl_flt_monitor {
-    ($($t:ident: ($fltx:ident, $fltxc:ident),)+) => {$(
+    ($($t:ident: ($fltx:ident, $fltxc:ident, $fltxie:ident),)+) => {$(
         pub struct $t {
             pub(crate) _x: PhantomData<()>
         }
 
+        impl $t {
+            /// Enable the fault interrupt, `HRTIM_TIM_FLT`, for this fault
+            ///
+            /// Use `is_fault_active` to check for and `clear_fault` to clear the pending fault
+            pub fn listen(&mut self) {
+                let common = unsafe { &*HRTIM_COMMON::ptr() };
+
+                // IER is shared with the other fault monitors
+                cortex_m::interrupt::free(|_| common.ier.modify(|_r, w| w.$fltxie().set_bit()));
+            }
+
+            /// Disable the fault interrupt for this fault
+            pub fn unlisten(&mut self) {
+                let common = unsafe { &*HRTIM_COMMON::ptr() };
+
+                // IER is shared with the other fault monitors
+                cortex_m::interrupt::free(|_| common.ier.modify(|_r, w| w.$fltxie().clear_bit()));
+            }
+        }
+
         impl FaultMonitor for $t {
             fn is_fault_active(&self) -> bool {
                 let common = unsafe { &*HRTIM_COMMON::ptr() };
@@ -252,11 +396,55 @@ macro_rules! impl_flt_monitor {
 }
 
 impl_flt_monitor!(
-    FltMonitorSys: (sysflt, sysfltc),
-    FltMonitor1: (flt1, flt1c),
-    FltMonitor2: (flt2, flt2c),
-    FltMonitor3: (flt3, flt3c),
-    FltMonitor4: (flt4, flt4c),
-    FltMonitor5: (flt5, flt5c),
-    FltMonitor6: (flt6, flt6c),
+    FltMonitorSys: (sysflt, sysfltc, sysflte),
+    FltMonitor1: (flt1, flt1c, flt1ie),
+    FltMonitor2: (flt2, flt2c, flt2ie),
+    FltMonitor3: (flt3, flt3c, flt3ie),
+    FltMonitor4: (flt4, flt4c, flt4ie),
+    FltMonitor5: (flt5, flt5c, flt5ie),
+    FltMonitor6: (flt6, flt6c, flt6ie),
 );
+
+/// Sources routed to the system fault, see [`FltMonitorSys::connect_sources`]
+///
+/// The clock security system is always connected once enabled in the RCC.
+#[derive(Default)]
+pub struct SystemFaultSources {
+    /// Cortex-M4 LOCKUP (hardfault) output
+    pub lockup: bool,
+
+    /// SRAM parity error
+    pub sram_parity: bool,
+
+    /// PVD output
+    pub pvd: bool,
+
+    /// Flash ECC double error
+    pub flash_ecc: bool,
+}
+
+impl FltMonitorSys {
+    /// Connect additional sources to the system fault
+    ///
+    /// The system fault acts on every output with a `FaultAction` other than `FaultAction::None`,
+    /// no `with_fault_source` is needed for it. Use the `FaultMonitor` implementation to check
+    /// and clear it.
+    ///
+    /// NOTE: These connections are shared with the break inputs of TIM1/8/15/16/17/20 and can
+    /// not be undone until the next system reset.
+    pub fn connect_sources(&mut self, syscfg: &mut SysCfg, sources: SystemFaultSources) {
+        syscfg.cfgr2.modify(|r, w| {
+            w.cll()
+                .bit(r.cll().bit() || sources.lockup)
+                .spl()
+                .bit(r.spl().bit() || sources.sram_parity)
+                .pvdl()
+                .bit(r.pvdl().bit() || sources.pvd)
+                .eccl()
+                .bit(r.eccl().bit() || sources.flash_ecc)
+                // Writing 0 leaves the SRAM parity error flag untouched
+                .spf()
+                .clear_bit()
+        });
+    }
+}
@@ -3,6 +3,7 @@ pub mod capture;
 pub mod compare_register;
 pub mod control;
 pub mod deadtime;
+pub mod dma;
 pub mod event;
 pub mod external_event;
 pub mod fault;
@@ -31,7 +32,7 @@ use self::timer_eev_cfg::EevCfgs;
 use crate::pwm::{
     self, Alignment, ComplementaryImpossible, Pins, Polarity, Pwm, PwmPinEnable, TimerType,
 };
-use crate::rcc::{GetBusFreq, Rcc};
+use crate::rcc::Rcc;
 use crate::time::Hertz;
 
 /// Internal enum that keeps track of the count settings before PWM is finalized
@@ -202,24 +203,170 @@ pub struct HrPwmBuilder<TIM, PSCL, PS, OUT> {
     eev_cfg: EevCfgs<TIM>,
     out1_polarity: Polarity,
     out2_polarity: Polarity,
+    update_gating_bits: u8,
+    delcmp2_bits: u8,
+    delcmp4_bits: u8,
+    delayed_protection_bits: Option<u8>,
+    roll_over: RollOverModes,
 }
 
+/// The event on which preloaded registers are transferred to the active registers
+///
+/// If `preload` is never called on the builder, preload is left disabled (PREEN = 0,
+/// the reset value). In that case writes to the period and compare registers take
+/// effect immediately which may result in one distorted cycle if written at the wrong time.
 pub enum PreloadSource {
-    /// Preloaded registers are updated on counter roll over or counter reset
+    /// Preloaded registers are updated on counter roll over or counter reset (TxRSTU)
     OnCounterReset,
 
     /// Preloaded registers are updated by master timer update
     OnMasterTimerUpdate,
 
-    /// Prealoaded registers are updaten when the counter rolls over and the repetition counter is 0
+    /// Prealoaded registers are updaten when the counter rolls over and the repetition counter is 0 (TxREPU)
     OnRepetitionUpdate,
 }
 
 pub enum MasterPreloadSource {
-    /// Prealoaded registers are updaten when the master counter rolls over and the master repetition counter is 0
+    /// Prealoaded registers are updaten when the master counter rolls over and the master repetition counter is 0 (MREPU)
     OnMasterRepetitionUpdate,
 }
 
+/// Which roll-over(s) generate the roll-over event in up-down counting mode
+///
+/// Defaults to `Both` which is also the reset value of the registers. In up counting mode
+/// the roll-over always happens at the period and this setting has no effect.
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+pub enum RollOverMode {
+    /// Roll-over event both when the counter reaches 0 (valley) and the period (crest)
+    #[default]
+    Both = 0b00,
+
+    /// Roll-over event only when the counter reaches 0 (valley)
+    Valley = 0b01,
+
+    /// Roll-over event only when the counter reaches the period (crest)
+    Crest = 0b10,
+}
+
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+struct RollOverModes {
+    /// ROM, used by the counter reset, update and repetition events
+    counter: RollOverMode,
+
+    /// OUTROM, used by the output set/reset events
+    output: RollOverMode,
+
+    /// ADROM, used by the ADC triggers
+    adc: RollOverMode,
+
+    /// BMROM, used by the burst mode controller
+    burst_mode: RollOverMode,
+
+    /// FEROM, used by the fault and external event sampling
+    fault_and_event: RollOverMode,
+}
+
+/// Auto-delayed mode of compare register 2 and 4 (DELCMP2/DELCMP4)
+///
+/// In auto-delayed mode the compare value is relative to the last capture event
+/// rather than the start of the period. The compare event is thus only armed once the capture
+/// has happened, which is useful for things like valley switching.
+///
+/// Only compare register 2 and 4 of timer A-F support this. Cr2 is linked to capture 1 and
+/// Cr4 to capture 2, so the capture unit has to be set up using `HrCapture::add_event` on
+/// `capture_ch1` and `capture_ch2` respectively.
+///
+/// NOTE: The compare register must not be written to 0 while in auto-delayed mode.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum AutoDelayedMode {
+    /// The compare event is only armed after a capture event
+    AfterCapture = 0b01,
+
+    /// The compare event is armed after a capture event, or on a Cr1 match if no capture
+    /// has occured by then (timeout)
+    AfterCaptureOrCr1 = 0b10,
+
+    /// The compare event is armed after a capture event, or on a Cr3 match if no capture
+    /// has occured by then (timeout)
+    AfterCaptureOrCr3 = 0b11,
+}
+
+/// Outputs affected by the delayed protection, see `HrPwmBuilder::delayed_protection`
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum DelayedProtectionMode {
+    /// Output 1 goes to its idle state once its current active pulse is completed
+    Out1DelayedIdle = 0b00,
+
+    /// Output 2 goes to its idle state once its current active pulse is completed
+    Out2DelayedIdle = 0b01,
+
+    /// Both outputs go to their idle state once their current active pulses are completed
+    BothDelayedIdle = 0b10,
+
+    /// The ongoing active pulse is terminated and the other output gets a pulse of the same
+    /// length before both outputs go to their idle state. This keeps the volt-seconds of a
+    /// transformer balanced.
+    BalancedIdle = 0b11,
+}
+
+/// External event triggering the delayed protection, see `HrPwmBuilder::delayed_protection`
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum DelayedProtectionEvent {
+    /// EEV6 for timer A, B and C, or EEV8 for timer D, E and F
+    First = 0b0,
+
+    /// EEV7 for timer A, B and C, or EEV9 for timer D, E and F
+    Second = 0b1,
+}
+
+/// Gating of the update event of timer A-F (UPDGAT)
+///
+/// Defaults to `Independent` which is also the reset value of the register.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum UpdateGating {
+    /// The update occurs independently of any DMA burst transfer
+    Independent = 0b0000,
+
+    /// The update occurs when a DMA burst transfer is completed
+    DmaBurstComplete = 0b0001,
+
+    /// The update occurs on the update event following a DMA burst transfer completion
+    UpdateAfterDmaBurst = 0b0010,
+
+    /// The update occurs on a rising edge of hrtim_upd_en1
+    UpdEn1 = 0b0011,
+
+    /// The update occurs on a rising edge of hrtim_upd_en2
+    UpdEn2 = 0b0100,
+
+    /// The update occurs on a rising edge of hrtim_upd_en3
+    UpdEn3 = 0b0101,
+
+    /// The update occurs on the update event following a rising edge of hrtim_upd_en1
+    UpdateAfterUpdEn1 = 0b0110,
+
+    /// The update occurs on the update event following a rising edge of hrtim_upd_en2
+    UpdateAfterUpdEn2 = 0b0111,
+
+    /// The update occurs on the update event following a rising edge of hrtim_upd_en3
+    UpdateAfterUpdEn3 = 0b1000,
+}
+
+/// Gating of the update event of the master timer (BRSTDMA)
+///
+/// Defaults to `Independent` which is also the reset value of the register.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum MasterUpdateGating {
+    /// The update occurs independently of any DMA burst transfer
+    Independent = 0b00,
+
+    /// The update occurs when a DMA burst transfer is completed
+    DmaBurstComplete = 0b01,
+
+    /// The update occurs on the master timer roll-over following a DMA burst transfer completion
+    RollOverAfterDmaBurst = 0b10,
+}
+
 macro_rules! hrtim_finalize_body {
     ($this:expr, $PreloadSource:ident, $TIMX:ident: (
         $timXcr:ident, $ck_psc:ident, $perXr:ident, $perx:ident, $tXcen:ident, $rep:ident, $repx:ident, $dier:ident, $repie:ident
@@ -260,10 +407,29 @@ macro_rules! hrtim_finalize_body {
         });
 
         $(
-            tim.$timXcr2.modify(|_r, w|
-                // Set counting direction
-                w.udm().bit($this.counting_direction == HrCountingDirection::UpDown)
-            );
+            // SAFETY: The roll-over bits are always set from a valid `RollOverMode`
+            tim.$timXcr2.modify(|_r, w| unsafe {
+                let roll_over = $this.roll_over;
+                w
+                    // Set counting direction
+                    .udm().bit($this.counting_direction == HrCountingDirection::UpDown)
+
+                    // Roll-over modes, only relevant in up-down mode
+                    .rom().bits(roll_over.counter as u8)
+                    .outrom().bits(roll_over.output as u8)
+                    .adrom().bits(roll_over.adc as u8)
+                    .bmrom().bits(roll_over.burst_mode as u8)
+                    .ferom().bits(roll_over.fault_and_event as u8)
+            });
+
+            // SAFETY: update_gating_bits is always set from a valid `UpdateGating`
+            tim.$timXcr.modify(|_r, w| unsafe { w.updgat().bits($this.update_gating_bits) });
+
+            // SAFETY: delcmpX_bits are always set from a valid `AutoDelayedMode` or 0b00
+            tim.$timXcr.modify(|_r, w| unsafe { w
+                .delcmp2().bits($this.delcmp2_bits)
+                .delcmp4().bits($this.delcmp4_bits)
+            });
 
             // Only available for timers with outputs(not HRTIM_MASTER)
             let _ = tim.$outXr;
@@ -300,6 +466,10 @@ macro_rules! hrtim_finalize_body {
                 // Set output polarity for both outputs
                 .pol1().bit($this.out1_polarity == Polarity::ActiveLow)
                 .pol2().bit($this.out2_polarity == Polarity::ActiveLow)
+
+                // Delayed protection
+                .dlyprten().bit($this.delayed_protection_bits.is_some())
+                .dlyprt().bits($this.delayed_protection_bits.unwrap_or(0b000))
             );
             if let Some(deadtime) = $this.deadtime {
                 let DeadtimeConfig {
@@ -397,6 +567,10 @@ macro_rules! hrtim_finalize_body {
     }};
 
     (MasterPreloadSource, $this:expr, $tim:expr, $timXcr:ident) => {{
+        // The master timer has its own update gating field
+        // SAFETY: update_gating_bits is always set from a valid `MasterUpdateGating`
+        $tim.$timXcr.modify(|_r, w| unsafe { w.brstdma().bits($this.update_gating_bits) });
+
         match $this.preload_source {
             Some(MasterPreloadSource::OnMasterRepetitionUpdate) => {
                 $tim.$timXcr.modify(|_r, w| w
@@ -447,6 +621,11 @@ macro_rules! hrtim_common_methods {
                 eev_cfg,
                 out1_polarity,
                 out2_polarity,
+                update_gating_bits,
+                delcmp2_bits,
+                delcmp4_bits,
+                delayed_protection_bits,
+                roll_over,
             } = self;
 
             let period = match count {
@@ -476,6 +655,11 @@ macro_rules! hrtim_common_methods {
                 eev_cfg,
                 out1_polarity,
                 out2_polarity,
+                update_gating_bits,
+                delcmp2_bits,
+                delcmp4_bits,
+                delayed_protection_bits,
+                roll_over,
             }
         }
 
@@ -484,6 +668,9 @@ macro_rules! hrtim_common_methods {
             self
         }
 
+        /// Enable preload of the period and compare registers
+        ///
+        /// See [`PreloadSource`] for what happens if this is never called
         // TODO: Allow setting multiple?
         pub fn preload(mut self, preload_source: $PS) -> Self {
             self.preload_source = Some(preload_source);
@@ -553,10 +740,7 @@ macro_rules! hrtim_hal {
                     PINS: Pins<Self, CHANNEL, COMP> + ToHrOut,
                     CHANNEL: HrtimChannel<Pscl128>
                 {
-                    // TODO: That 32x factor... Is that included below, or should we
-                    // do that? Also that will likely risk overflowing u32 since
-                    // 170MHz * 32 = 5.44GHz > u32::MAX.Hz()
-                    let clk = HertzU64::from(HRTIM_COMMON::get_timer_frequency(&rcc.clocks)) * 32;
+                    let clk = rcc.clocks.hrtim_clk;
 
                     HrPwmBuilder {
                         _tim: PhantomData,
@@ -578,6 +762,11 @@ macro_rules! hrtim_hal {
                         eev_cfg: EevCfgs::default(),
                         out1_polarity: Polarity::ActiveHigh,
                         out2_polarity: Polarity::ActiveHigh,
+                        update_gating_bits: UpdateGating::Independent as u8,
+                        delcmp2_bits: 0b00,
+                        delcmp4_bits: 0b00,
+                        delayed_protection_bits: None,
+                        roll_over: RollOverModes::default(),
                     }
                 }
             }
@@ -617,6 +806,19 @@ macro_rules! hrtim_hal {
                     self
                 }
 
+                /// Enable delayed protection, putting the outputs in their idle state
+                /// according to `mode` when `event` occurs
+                ///
+                /// Unlike a fault, which acts immediately, this lets the ongoing pulse finish
+                /// (or be balanced) first. The delay is thus given by the pulse itself and is
+                /// not programmable. The external event has to be set up separately, see
+                /// the `external_event` module. The outputs stay idle until they are enabled again.
+                pub fn delayed_protection(mut self, mode: DelayedProtectionMode, event: DelayedProtectionEvent) -> Self {
+                    self.delayed_protection_bits = Some(((event as u8) << 2) | mode as u8);
+
+                    self
+                }
+
                 pub fn out1_polarity(mut self, polarity: Polarity) -> Self {
                     self.out1_polarity = polarity;
 
@@ -662,6 +864,16 @@ macro_rules! hrtim_hal {
                     self
                 }
 
+                /// Center aligned PWM, short for `counting_direction(HrCountingDirection::UpDown)`
+                ///
+                /// One period spans counting up to the period and back down to 0, so for the same
+                /// period the frequency is halved. This is taken into account by `frequency` and
+                /// by `HrTimer::frequency`. A compare value then matches twice per period, once
+                /// counting up and once counting down, see [`HrCountingDirection::UpDown`].
+                pub fn center_aligned(self) -> Self {
+                    self.counting_direction(HrCountingDirection::UpDown)
+                }
+
                 /// Set interleaved or half modes
                 ///
                 /// NOTE: Check [`InterleavedMode`] for more info about special cases
@@ -671,12 +883,101 @@ macro_rules! hrtim_hal {
                     self
                 }
 
+                /// Set which roll-over(s) the counter roll-over event is generated at (ROM)
+                ///
+                /// This affects the update, repetition and counter reset events.
+                /// See [`RollOverMode`]
+                pub fn roll_over_mode(mut self, mode: RollOverMode) -> Self {
+                    self.roll_over.counter = mode;
+
+                    self
+                }
+
+                /// Set which roll-over(s) the timer output set/reset on roll-over happens at (OUTROM)
+                ///
+                /// See [`RollOverMode`]
+                pub fn output_roll_over_mode(mut self, mode: RollOverMode) -> Self {
+                    self.roll_over.output = mode;
+
+                    self
+                }
+
+                /// Set which roll-over(s) the ADC triggers on roll-over happen at (ADROM)
+                ///
+                /// For example `RollOverMode::Valley` to only sample in the middle of the
+                /// low side on-time.
+                /// See [`RollOverMode`]
+                pub fn adc_roll_over_mode(mut self, mode: RollOverMode) -> Self {
+                    self.roll_over.adc = mode;
+
+                    self
+                }
+
+                /// Set which roll-over(s) the burst mode controller sees (BMROM)
+                ///
+                /// See [`RollOverMode`]
+                pub fn burst_mode_roll_over_mode(mut self, mode: RollOverMode) -> Self {
+                    self.roll_over.burst_mode = mode;
+
+                    self
+                }
+
+                /// Set which roll-over(s) are used for fault and external event sampling (FEROM)
+                ///
+                /// See [`RollOverMode`]
+                pub fn fault_and_event_roll_over_mode(mut self, mode: RollOverMode) -> Self {
+                    self.roll_over.fault_and_event = mode;
+
+                    self
+                }
+
+                /// Enable or disable half mode
+                ///
+                /// This keeps Cr1 at PERIOD / 2, see [`InterleavedMode::Dual`]. Disabling only
+                /// affects half mode, triple and quad interleaved modes are left as is.
+                pub fn half_mode(mut self, enable: bool) -> Self {
+                    self.interleaved_mode = match (enable, self.interleaved_mode) {
+                        (true, _) => InterleavedMode::Dual,
+                        (false, InterleavedMode::Dual) => InterleavedMode::Disabled,
+                        (false, mode) => mode,
+                    };
+
+                    self
+                }
+
+                /// Put compare register 2 in auto-delayed mode, relative to capture 1
+                ///
+                /// See [`AutoDelayedMode`]
+                pub fn cr2_auto_delayed(mut self, mode: AutoDelayedMode) -> Self {
+                    self.delcmp2_bits = mode as _;
+
+                    self
+                }
+
+                /// Put compare register 4 in auto-delayed mode, relative to capture 2
+                ///
+                /// See [`AutoDelayedMode`]
+                pub fn cr4_auto_delayed(mut self, mode: AutoDelayedMode) -> Self {
+                    self.delcmp4_bits = mode as _;
+
+                    self
+                }
+
                 pub fn deadtime(mut self, deadtime: DeadtimeConfig) -> Self {
                     self.deadtime = Some(deadtime);
 
                     self
                 }
 
+                /// Set when the preloaded registers are allowed to be updated
+                ///
+                /// See [`UpdateGating`]
+                pub fn update_gating(mut self, update_gating: UpdateGating) -> Self {
+                    self.update_gating_bits = update_gating as _;
+
+                    self
+                }
+
                 //pub fn swap_mode(mut self, enable: bool) -> Self
             }
         )+
@@ -697,10 +998,7 @@ macro_rules! hrtim_hal_master {
                 PINS: Pins<Self, CHANNEL, COMP> + ToHrOut, // TODO: figure out
                 CHANNEL: HrtimChannel<Pscl128>
             {
-                // TODO: That 32x factor... Is that included below, or should we
-                // do that? Also that will likely risk overflowing u32 since
-                // 170MHz * 32 = 5.44GHz > u32::MAX.Hz()
-                let clk = HertzU64::from(HRTIM_COMMON::get_timer_frequency(&rcc.clocks)) * 32;
+                let clk = rcc.clocks.hrtim_clk;
 
                 HrPwmBuilder {
                     _tim: PhantomData,
@@ -722,6 +1020,11 @@ macro_rules! hrtim_hal_master {
                     eev_cfg: EevCfgs::default(),
                     out1_polarity: Polarity::ActiveHigh,
                     out2_polarity: Polarity::ActiveHigh,
+                    update_gating_bits: MasterUpdateGating::Independent as u8,
+                    delcmp2_bits: 0b00,
+                    delcmp4_bits: 0b00,
+                    delayed_protection_bits: None,
+                    roll_over: RollOverModes::default(),
                 }
             }
         }
@@ -737,6 +1040,15 @@ macro_rules! hrtim_hal_master {
             }
 
             hrtim_common_methods!($TIMX, MasterPreloadSource);
+
+            /// Set when the preloaded registers are allowed to be updated
+            ///
+            /// See [`MasterUpdateGating`]
+            pub fn update_gating(mut self, update_gating: MasterUpdateGating) -> Self {
+                self.update_gating_bits = update_gating as _;
+
+                self
+            }
         }
     )*}
 }
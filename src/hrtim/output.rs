@@ -14,7 +14,22 @@ use crate::{
 };
 
 macro_rules! hrtim_out {
-    ($($TIMX:ident: $out_type:ident: $tXYoen:ident, $tXYodis:ident, $tXYods:ident, $setXYr:ident, $rstXYr:ident,)+) => {$(
+    ($($TIMX:ident: $out_type:ident: $tXYoen:ident, $tXYodis:ident, $tXYods:ident, $setXYr:ident, $rstXYr:ident, $bit_index:literal, $isr:ident, $oYcpy:ident,)+) => {$(
+        impl<PSCL> $out_type<$TIMX, PSCL> {
+            /// Erase the timer and prescaler types of this output
+            ///
+            /// NOTE: Set and reset events can not be changed through the erased output,
+            /// so make sure to configure them before erasing.
+            pub fn erase(self) -> ErasedHrOut {
+                let tim = unsafe { &*$TIMX::ptr() };
+                ErasedHrOut {
+                    mask: 1 << $bit_index,
+                    isr: tim.$isr.as_ptr(),
+                    cpy_mask: out_cpy::$oYcpy,
+                }
+            }
+        }
+
         impl<PSCL> HrOutput<PSCL, $TIMX> for $out_type<$TIMX, PSCL> {
             fn enable(&mut self) {
                 let common = unsafe { &*HRTIM_COMMON::ptr() };
@@ -47,41 +62,40 @@ macro_rules! hrtim_out {
             fn get_state(&self) -> State {
                 let ods;
                 let oen;
+                let active;
 
                 unsafe {
                     let common = &*HRTIM_COMMON::ptr();
+                    let tim = &*$TIMX::ptr();
                     ods = common.odsr.read().$tXYods().bit_is_set();
                     oen = common.oenr.read().$tXYoen().bit_is_set();
+                    active = tim.$isr.read().$oYcpy().bit_is_set();
                 }
 
-                match (oen, ods) {
-                    (true, _) => State::Running,
-                    (false, false) => State::Idle,
-                    (false, true) => State::Fault
-                }
+                State::from_bits(oen, ods, active)
             }
         }
     )+};
 }
 
 hrtim_out! {
-    HRTIM_TIMA: HrOut1: ta1oen, ta1odis, ta1ods, seta1r, rsta1r,
-    HRTIM_TIMA: HrOut2: ta2oen, ta2odis, ta2ods, seta2r, rsta2r,
+    HRTIM_TIMA: HrOut1: ta1oen, ta1odis, ta1ods, seta1r, rsta1r, 0, timaisr, o1cpy,
+    HRTIM_TIMA: HrOut2: ta2oen, ta2odis, ta2ods, seta2r, rsta2r, 1, timaisr, o2cpy,
 
-    HRTIM_TIMB: HrOut1: tb1oen, tb1odis, tb1ods, setb1r, rstb1r,
-    HRTIM_TIMB: HrOut2: tb2oen, tb2odis, tb2ods, setb2r, rstb2r,
+    HRTIM_TIMB: HrOut1: tb1oen, tb1odis, tb1ods, setb1r, rstb1r, 2, timbisr, o1cpy,
+    HRTIM_TIMB: HrOut2: tb2oen, tb2odis, tb2ods, setb2r, rstb2r, 3, timbisr, o2cpy,
 
-    HRTIM_TIMC: HrOut1: tc1oen, tc1odis, tc1ods, setc1r, rstc1r,
-    HRTIM_TIMC: HrOut2: tc2oen, tc2odis, tc2ods, setc2r, rstc2r,
+    HRTIM_TIMC: HrOut1: tc1oen, tc1odis, tc1ods, setc1r, rstc1r, 4, timcisr, o1cpy,
+    HRTIM_TIMC: HrOut2: tc2oen, tc2odis, tc2ods, setc2r, rstc2r, 5, timcisr, o2cpy,
 
-    HRTIM_TIMD: HrOut1: td1oen, td1odis, td1ods, setd1r, rstd1r,
-    HRTIM_TIMD: HrOut2: td2oen, td2odis, td2ods, setd2r, rstd2r,
+    HRTIM_TIMD: HrOut1: td1oen, td1odis, td1ods, setd1r, rstd1r, 6, timdisr, o1cpy,
+    HRTIM_TIMD: HrOut2: td2oen, td2odis, td2ods, setd2r, rstd2r, 7, timdisr, o2cpy,
 
-    HRTIM_TIME: HrOut1: te1oen, te1odis, te1ods, sete1r, rste1r,
-    HRTIM_TIME: HrOut2: te2oen, te2odis, te2ods, sete2r, rste2r,
+    HRTIM_TIME: HrOut1: te1oen, te1odis, te1ods, sete1r, rste1r, 8, timeisr, o1cpy,
+    HRTIM_TIME: HrOut2: te2oen, te2odis, te2ods, sete2r, rste2r, 9, timeisr, o2cpy,
 
-    HRTIM_TIMF: HrOut1: tf1oen, tf1odis, tf1ods, setf1r, rstf1r,
-    HRTIM_TIMF: HrOut2: tf2oen, tf2odis, tf2ods, setf2r, rstf2r,
+    HRTIM_TIMF: HrOut1: tf1oen, tf1odis, tf1ods, setf1r, rstf1r, 10, timfisr, o1cpy,
+    HRTIM_TIMF: HrOut2: tf2oen, tf2odis, tf2ods, setf2r, rstf2r, 11, timfisr, o2cpy,
 }
 
 pub trait HrOutput<PSCL, TIM> {
@@ -89,8 +103,35 @@ pub trait HrOutput<PSCL, TIM> {
     fn enable(&mut self);
 
     /// Disable this output
+    ///
+    /// The output goes to its idle state (ODISR). There is no way to disable a single output
+    /// to its fault state, only a fault input can do that for all outputs it is enabled for,
+    /// see `HrPwmControl::force_fault`.
     fn disable(&mut self);
 
+    /// Returns `true` if the output is enabled, i.e. not in idle or fault state
+    fn is_enabled(&self) -> bool {
+        self.get_state().is_running()
+    }
+
+    /// Returns `true` if this output has been put in its fault state by a fault
+    ///
+    /// This tells which outputs were affected by a fault, as opposed to `FaultMonitor::is_fault_active`
+    /// which tells which fault source triggered.
+    fn is_faulted(&self) -> bool {
+        self.get_state() == State::Fault
+    }
+
+    /// Bring this output out of its fault state by enabling it again
+    ///
+    /// The fault source has to be cleared first, see `FaultMonitor::clear_fault`, otherwise
+    /// the output stays in its fault state. Outputs which are not in fault state are left as is.
+    fn clear_fault(&mut self) {
+        if self.is_faulted() {
+            self.enable();
+        }
+    }
+
     /// Set this output to active every time the specified event occurs
     ///
     /// NOTE: Enabling the same event for both SET and RESET
@@ -113,14 +154,111 @@ pub trait HrOutput<PSCL, TIM> {
     fn get_state(&self) -> State;
 }
 
+/// OxCPY bits in TIMxISR
+#[allow(non_upper_case_globals)]
+mod out_cpy {
+    pub const o1cpy: u32 = 1 << 20;
+    pub const o2cpy: u32 = 1 << 21;
+}
+
+/// Timer and prescaler type of an [`ErasedHrOut`] in `HrOutput<Erased, Erased>`
+///
+/// No event source exists for it, so the set and reset events of an erased output can not be
+/// changed.
+pub struct Erased;
+
+/// Output with its timer and prescaler types erased
+///
+/// Created using `HrOut1::erase` or `HrOut2::erase`. This is useful for
+/// storing outputs from different timers in the same struct or array.
+/// Enabling, disabling and reading the state costs the same as
+/// for the non erased output.
+pub struct ErasedHrOut {
+    /// Bit in OENR, ODISR and ODSR corresponding to this output
+    mask: u32,
+
+    /// TIMxISR of the timer this output belongs to
+    isr: *const u32,
+
+    /// Bit in TIMxISR holding the current level of this output
+    cpy_mask: u32,
+}
+
+// SAFETY: The erased output is the sole owner of its bits in OENR, ODISR and ODSR, and only
+// reads TIMxISR
+unsafe impl Send for ErasedHrOut {}
+
+impl HrOutput<Erased, Erased> for ErasedHrOut {
+    fn enable(&mut self) {
+        let common = unsafe { &*HRTIM_COMMON::ptr() };
+        // SAFETY: OENR is a set-only register and mask only ever has one valid bit set
+        common.oenr.write(|w| unsafe { w.bits(self.mask) });
+    }
+
+    fn disable(&mut self) {
+        let common = unsafe { &*HRTIM_COMMON::ptr() };
+        // SAFETY: ODISR is a set-only register and mask only ever has one valid bit set
+        common.odisr.write(|w| unsafe { w.bits(self.mask) });
+    }
+
+    fn enable_set_event<ES: EventSource<Erased, Erased>>(&mut self, _set_event: &ES) {
+        unreachable!("there are no event sources for erased outputs")
+    }
+    fn disable_set_event<ES: EventSource<Erased, Erased>>(&mut self, _set_event: &ES) {
+        unreachable!("there are no event sources for erased outputs")
+    }
+
+    fn enable_rst_event<ES: EventSource<Erased, Erased>>(&mut self, _reset_event: &ES) {
+        unreachable!("there are no event sources for erased outputs")
+    }
+    fn disable_rst_event<ES: EventSource<Erased, Erased>>(&mut self, _reset_event: &ES) {
+        unreachable!("there are no event sources for erased outputs")
+    }
+
+    fn get_state(&self) -> State {
+        let common = unsafe { &*HRTIM_COMMON::ptr() };
+        let ods = common.odsr.read().bits() & self.mask != 0;
+        let oen = common.oenr.read().bits() & self.mask != 0;
+
+        // SAFETY: isr points to the TIMxISR register of the owning timer
+        let active = unsafe { self.isr.read_volatile() } & self.cpy_mask != 0;
+
+        State::from_bits(oen, ods, active)
+    }
+}
+
 #[derive(Debug, PartialEq, Copy, Clone)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum State {
+    /// Disabled by software (ODISR), the output is at its idle level
     Idle,
-    Running,
+
+    /// Enabled and currently active (OxCPY set)
+    RunActive,
+
+    /// Enabled and currently inactive (OxCPY cleared)
+    RunInactive,
+
+    /// Disabled by a fault, the output is at the level given by its `FaultAction`
     Fault,
 }
 
+impl State {
+    fn from_bits(oen: bool, ods: bool, active: bool) -> Self {
+        match (oen, ods, active) {
+            (true, _, true) => State::RunActive,
+            (true, _, false) => State::RunInactive,
+            (false, false, _) => State::Idle,
+            (false, true, _) => State::Fault,
+        }
+    }
+
+    /// Returns `true` for `RunActive` and `RunInactive`
+    pub fn is_running(&self) -> bool {
+        matches!(self, State::RunActive | State::RunInactive)
+    }
+}
+
 pub unsafe trait ToHrOut {
     type Out<PSCL>: ToHrOut;
 }
@@ -6,7 +6,59 @@ use core::marker::PhantomData;
 use super::{
     capture::{self, HrCapt},
     control::HrPwmControl,
+    dma::DmaEvent,
+    HrCountingDirection, HrtimPrescaler,
 };
+use crate::rcc::Clocks;
+use crate::time::Hertz;
+
+/// Interrupt of a HRTIM timer, see `HrTimer::listen`
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum HrInterrupt {
+    Compare1,
+    Compare2,
+    Compare3,
+    Compare4,
+
+    /// Repetition counter underflow
+    ///
+    /// With a repetition counter of 0, the default, this occurs once every period
+    Repetition,
+    Update,
+
+    /// Not available for the master timer
+    Capture1,
+
+    /// Not available for the master timer
+    Capture2,
+
+    /// Not available for the master timer
+    Reset,
+}
+
+impl HrInterrupt {
+    /// Bit in TIMxDIER, TIMxISR and TIMxICR
+    fn mask(self) -> u32 {
+        match self {
+            HrInterrupt::Compare1 => 1 << 0,
+            HrInterrupt::Compare2 => 1 << 1,
+            HrInterrupt::Compare3 => 1 << 2,
+            HrInterrupt::Compare4 => 1 << 3,
+            HrInterrupt::Repetition => 1 << 4,
+            HrInterrupt::Update => 1 << 6,
+            HrInterrupt::Capture1 => 1 << 7,
+            HrInterrupt::Capture2 => 1 << 8,
+            HrInterrupt::Reset => 1 << 13,
+        }
+    }
+
+    fn is_available_on_master(self) -> bool {
+        !matches!(
+            self,
+            HrInterrupt::Capture1 | HrInterrupt::Capture2 | HrInterrupt::Reset
+        )
+    }
+}
 
 pub struct HrTim<TIM, PSCL> {
     _timer: PhantomData<TIM>,
@@ -29,6 +81,25 @@ pub trait HrTimer {
     /// NOTE: This will affect the maximum duty usable for `HrCompareRegister::set_duty`
     fn set_period(&mut self, period: u16);
 
+    /// Frequency of the timer period with the current period and prescaler
+    ///
+    /// Takes the x32 HRTIM clock, the prescaler and up-down counting into account
+    fn frequency(&self, clocks: &Clocks) -> Hertz
+    where
+        Self::Prescaler: HrtimPrescaler;
+
+    /// Duration of one timer tick in nanoseconds, which is the resolution of the
+    /// period and compare registers
+    fn resolution_ns(&self, clocks: &Clocks) -> f32
+    where
+        Self::Prescaler: HrtimPrescaler;
+
+    /// Counting mode of the timer
+    ///
+    /// The hardware does not expose the current direction of an up-down counting timer, it is
+    /// however latched with every capture, see `HrCapture::get`.
+    fn counting_direction(&self) -> HrCountingDirection;
+
     /// Start timer
     fn start(&mut self, _hr_control: &mut HrPwmControl);
 
@@ -40,6 +111,22 @@ pub trait HrTimer {
 
     fn clear_repetition_interrupt(&mut self);
 
+    /// Enable the interrupt for `interrupt`
+    ///
+    /// Panics if `interrupt` is not available for this timer
+    fn listen(&mut self, interrupt: HrInterrupt);
+
+    /// Disable the interrupt for `interrupt`
+    fn unlisten(&mut self, interrupt: HrInterrupt);
+
+    /// Returns true if `interrupt` has occurred since its flag was last cleared
+    ///
+    /// The flag is set regardless of whether the interrupt is enabled
+    fn is_pending(&self, interrupt: HrInterrupt) -> bool;
+
+    /// Clear the flag of `interrupt`
+    fn clear_pending(&mut self, interrupt: HrInterrupt);
+
     /// Make a handle to this timers reset event to use as adc trigger
     fn as_reset_adc_trigger(&self) -> super::adc_trigger::TimerReset<Self::Timer>;
 
@@ -81,8 +168,9 @@ macro_rules! hrtim_timer {
         $dier:ident,
         $repie:ident,
         $icr:ident,
+        $isr:ident,
         $repc:ident,
-        $(($rstXr:ident))*,
+        $(($rstXr:ident, $timXcr2:ident))*,
     )+) => {$(
         impl<PSCL> HrTimer for HrTim<$TIMX, PSCL> {
             type Prescaler = PSCL;
@@ -99,6 +187,34 @@ macro_rules! hrtim_timer {
                 tim.$perXr.write(|w| unsafe { w.$perx().bits(period as u16) });
             }
 
+            fn frequency(&self, clocks: &Clocks) -> Hertz
+            where
+                PSCL: HrtimPrescaler,
+            {
+                let tick_freq = clocks.hrtim_clk.raw() / u64::from(PSCL::VALUE);
+                let mut ticks = u64::from(self.get_period()) + 1;
+                if self.is_up_down() {
+                    ticks *= 2;
+                }
+
+                Hertz::from_raw((tick_freq / ticks) as u32)
+            }
+
+            fn resolution_ns(&self, clocks: &Clocks) -> f32
+            where
+                PSCL: HrtimPrescaler,
+            {
+                1e9 * f32::from(PSCL::VALUE) / clocks.hrtim_clk.raw() as f32
+            }
+
+            fn counting_direction(&self) -> HrCountingDirection {
+                if self.is_up_down() {
+                    HrCountingDirection::UpDown
+                } else {
+                    HrCountingDirection::Up
+                }
+            }
+
             /// Start timer
             fn start(&mut self, _hr_control: &mut HrPwmControl) {
                 // Start timer
@@ -140,6 +256,34 @@ macro_rules! hrtim_timer {
 
                 tim.$icr.write(|w| w.$repc().set_bit());
             }
+
+            fn listen(&mut self, interrupt: HrInterrupt) {
+                assert!(hrtim_timer!(@is_slave $($rstXr)*) || interrupt.is_available_on_master());
+                let tim = unsafe { &*$TIMX::ptr() };
+
+                // SAFETY: the mask is a single valid interrupt enable bit
+                tim.$dier.modify(|r, w| unsafe { w.bits(r.bits() | interrupt.mask()) });
+            }
+
+            fn unlisten(&mut self, interrupt: HrInterrupt) {
+                let tim = unsafe { &*$TIMX::ptr() };
+
+                // SAFETY: the mask only clears an interrupt enable bit
+                tim.$dier.modify(|r, w| unsafe { w.bits(r.bits() & !interrupt.mask()) });
+            }
+
+            fn is_pending(&self, interrupt: HrInterrupt) -> bool {
+                let tim = unsafe { &*$TIMX::ptr() };
+
+                tim.$isr.read().bits() & interrupt.mask() != 0
+            }
+
+            fn clear_pending(&mut self, interrupt: HrInterrupt) {
+                let tim = unsafe { &*$TIMX::ptr() };
+
+                // SAFETY: the flag clear bits are at the same positions as the flags
+                tim.$icr.write(|w| unsafe { w.bits(interrupt.mask()) });
+            }
         }
 
         impl<PSCL> HrTim<$TIMX, PSCL> {
@@ -154,9 +298,36 @@ macro_rules! hrtim_timer {
 
                 tim.$dier.modify(|_r, w| w.$repie().bit(enable));
             }
+
+            /// Generate a DMA request every time `event` occurs, see [`HrDmaSource`](super::dma::HrDmaSource)
+            ///
+            /// Panics if `event` is not available for this timer
+            pub fn enable_dma_request(&mut self, event: DmaEvent) {
+                assert!(hrtim_timer!(@is_slave $($rstXr)*) || event.is_available_on_master());
+                let tim = unsafe { &*$TIMX::ptr() };
+
+                // SAFETY: the mask is a single valid DMA request enable bit
+                tim.$dier.modify(|r, w| unsafe { w.bits(r.bits() | event.mask()) });
+            }
+
+            /// Stop generating DMA requests when `event` occurs
+            pub fn disable_dma_request(&mut self, event: DmaEvent) {
+                let tim = unsafe { &*$TIMX::ptr() };
+
+                // SAFETY: the mask only clears a DMA request enable bit
+                tim.$dier.modify(|r, w| unsafe { w.bits(r.bits() & !event.mask()) });
+            }
         }
 
         $(
+            impl<PSCL> HrTim<$TIMX, PSCL> {
+                fn is_up_down(&self) -> bool {
+                    let tim = unsafe { &*$TIMX::ptr() };
+
+                    tim.$timXcr2.read().udm().bit_is_set()
+                }
+            }
+
             impl<PSCL> HrSlaveTimer for HrTim<$TIMX, PSCL> {
                 type CaptureCh1 = HrCapt<Self::Timer, Self::Prescaler, capture::Ch1>;
                 type CaptureCh2 = HrCapt<Self::Timer, Self::Prescaler, capture::Ch2>;
@@ -201,6 +372,30 @@ macro_rules! hrtim_timer {
             }
 
 
+            impl<PSCL> HrTim<$TIMX, PSCL> {
+                /// Set up both capture units to measure the frequency and duty of an external signal
+                ///
+                /// `rising` is added to capture 1 and `falling` to capture 2. Since the edge
+                /// sensitivity is set per external event input, these are typically two
+                /// external events fed by the same signal, one configured as `Edge::Rising`
+                /// and the other as `Edge::Falling`.
+                ///
+                /// See [`ExternalMeasurement`](super::capture::ExternalMeasurement)
+                pub fn measure_external<R, F>(&mut self, rising: &R, falling: &F) -> super::capture::ExternalMeasurement
+                    where R: super::capture::CaptureEvent<$TIMX, PSCL>,
+                          F: super::capture::CaptureEvent<$TIMX, PSCL>,
+                {
+                    use super::capture::HrCapture;
+
+                    HrCapt::<$TIMX, PSCL, capture::Ch1>::add_event(&mut self.capture_ch1, rising);
+                    HrCapt::<$TIMX, PSCL, capture::Ch2>::add_event(&mut self.capture_ch2, falling);
+                    self.capture_ch1.clear_interrupt();
+                    self.capture_ch2.clear_interrupt();
+
+                    super::capture::ExternalMeasurement::new()
+                }
+            }
+
             /// Timer Period event
             impl<DST, PSCL> super::event::EventSource<DST, PSCL> for HrTim<$TIMX, PSCL> {
                 // $rstXr
@@ -214,7 +409,10 @@ macro_rules! hrtim_timer {
                 const BITS: u32 = 1 << 1;
             }
         )*
-    )+}
+    )+};
+
+    (@is_slave $rstXr:ident) => { true };
+    (@is_slave) => { false };
 }
 
 macro_rules! hrtim_timer_adc_trigger {
@@ -242,14 +440,14 @@ use super::adc_trigger::Adc579Trigger as Adc579;
 use super::adc_trigger::Adc6810Trigger as Adc6810;
 
 hrtim_timer! {
-    HRTIM_MASTER: mcntr, mcnt, mper, mcen, mper, mrep, mrep, mdier, mrepie, micr, mrepc,,
-
-    HRTIM_TIMA: cntar, cntx, perar, tacen, perx, repar, repx, timadier, repie, timaicr, repc, (rstar),
-    HRTIM_TIMB: cntr, cntx, perbr, tbcen, perx, repbr, repx, timbdier, repie, timbicr, repc, (rstbr),
-    HRTIM_TIMC: cntcr, cntx, percr, tccen, perx, repcr, repx, timcdier, repie, timcicr, repc, (rstcr),
-    HRTIM_TIMD: cntdr, cntx, perdr, tdcen, perx, repdr, repx, timddier, repie, timdicr, repc, (rstdr),
-    HRTIM_TIME: cnter, cntx, perer, tecen, perx, reper, repx, timedier, repie, timeicr, repc, (rster),
-    HRTIM_TIMF: cntfr, cntx, perfr, tfcen, perx, repfr, repx, timfdier, repie, timficr, repc, (rstfr),
+    HRTIM_MASTER: mcntr, mcnt, mper, mcen, mper, mrep, mrep, mdier, mrepie, micr, misr, mrepc,,
+
+    HRTIM_TIMA: cntar, cntx, perar, tacen, perx, repar, repx, timadier, repie, timaicr, timaisr, repc, (rstar, timacr2),
+    HRTIM_TIMB: cntr, cntx, perbr, tbcen, perx, repbr, repx, timbdier, repie, timbicr, timbisr, repc, (rstbr, timbcr2),
+    HRTIM_TIMC: cntcr, cntx, percr, tccen, perx, repcr, repx, timcdier, repie, timcicr, timcisr, repc, (rstcr, timccr2),
+    HRTIM_TIMD: cntdr, cntx, perdr, tdcen, perx, repdr, repx, timddier, repie, timdicr, timdisr, repc, (rstdr, timdcr2),
+    HRTIM_TIME: cnter, cntx, perer, tecen, perx, reper, repx, timedier, repie, timeicr, timeisr, repc, (rster, timecr2),
+    HRTIM_TIMF: cntfr, cntx, perfr, tfcen, perx, repfr, repx, timfdier, repie, timficr, timfisr, repc, (rstfr, timfcr2),
 }
 
 hrtim_timer_adc_trigger! {
@@ -263,6 +461,13 @@ hrtim_timer_adc_trigger! {
     HRTIM_TIMF: [(Adc13: [(PER: 1 << 24), (RST: 1 << 28)]), (Adc24: [(PER: 1 << 24),               ]), (Adc579: [(PER: 30), (RST: 31)]), (Adc6810: [(PER: 31),          ])]
 }
 
+impl<PSCL> HrTim<HRTIM_MASTER, PSCL> {
+    /// The master timer always counts up
+    fn is_up_down(&self) -> bool {
+        false
+    }
+}
+
 /// Master Timer Period event
 impl<DST, PSCL> super::event::TimerResetEventSource<DST, PSCL> for HrTim<HRTIM_MASTER, PSCL> {
     const BITS: u32 = 1 << 4; // MSTPER
@@ -1,4 +1,14 @@
 //! I2C
+//!
+//! Besides the embedded-hal 0.2 blocking traits, `I2c` implements the embedded-hal 1.0
+//! `I2c` trait. This makes it usable with the bus sharing wrappers of `embedded-hal-bus`,
+//! for example to share one bus between two drivers:
+//!
+//! ```ignore
+//! let bus = core::cell::RefCell::new(dp.I2C1.i2c(sda, scl, Config::new(400.kHz()), &mut rcc));
+//! let display = Display::new(embedded_hal_bus::i2c::RefCellDevice::new(&bus));
+//! let imu = Imu::new(embedded_hal_bus::i2c::RefCellDevice::new(&bus));
+//! ```
 use hal::blocking::i2c::{Read, Write, WriteRead};
 
 use crate::gpio::{gpioa::*, gpiob::*, gpioc::*, gpiof::*};
@@ -11,7 +21,7 @@ use crate::gpio::{gpioa::*, gpiob::*, gpioc::*, gpiof::*};
 ))]
 use crate::gpio::{gpiog::*, AF3};
 use crate::gpio::{AlternateOD, AF2, AF4, AF8};
-use crate::rcc::{Enable, GetBusFreq, Rcc, RccBus, Reset};
+use crate::rcc::{Enable, KernelClock, Rcc, Reset};
 #[cfg(any(
     feature = "stm32g471",
     feature = "stm32g473",
@@ -23,6 +33,8 @@ use crate::stm32::I2C4;
 use crate::stm32::{I2C1, I2C2, I2C3, RCC};
 use crate::time::Hertz;
 use core::cmp;
+use core::ops::Range;
+use embedded_hal_one::i2c::Operation;
 
 /// I2C bus configuration.
 pub struct Config {
@@ -115,6 +127,89 @@ pub trait SDAPin<I2C> {}
 /// I2C SCL pin
 pub trait SCLPin<I2C> {}
 
+/// 7-bit I2C slave address, without the R/W bit
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Address7(u8);
+
+impl Address7 {
+    /// Returns `None` if `addr` does not fit in 7 bits, for example because it already
+    /// includes the R/W bit
+    pub const fn new(addr: u8) -> Option<Self> {
+        if addr <= 0x7F {
+            Some(Address7(addr))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the address, without the R/W bit
+    pub const fn get(self) -> u8 {
+        self.0
+    }
+}
+
+/// 10-bit I2C slave address
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Address10(u16);
+
+impl Address10 {
+    /// Returns `None` if `addr` does not fit in 10 bits
+    pub const fn new(addr: u16) -> Option<Self> {
+        if addr <= 0x3FF {
+            Some(Address10(addr))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the address, in the range `0..=0x3FF`
+    pub const fn get(self) -> u16 {
+        self.0
+    }
+}
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// I2C slave address, either [`Address7`] or [`Address10`]
+pub trait Address: Copy + sealed::Sealed {
+    /// Value of the SADD field in CR2
+    fn sadd(self) -> u16;
+
+    /// Value of the ADD10 bit in CR2
+    fn is_ten_bit(self) -> bool;
+}
+
+impl sealed::Sealed for Address7 {}
+impl Address for Address7 {
+    fn sadd(self) -> u16 {
+        u16::from(self.0) << 1
+    }
+
+    fn is_ten_bit(self) -> bool {
+        false
+    }
+}
+
+impl sealed::Sealed for Address10 {}
+impl Address for Address10 {
+    fn sadd(self) -> u16 {
+        self.0
+    }
+
+    fn is_ten_bit(self) -> bool {
+        true
+    }
+}
+
+/// Validates the 7-bit address passed to the embedded-hal traits
+fn seven_bit(addr: u8) -> Result<Address7, Error> {
+    Address7::new(addr).ok_or(Error::InvalidAddress)
+}
+
 /// I2C error
 #[derive(Debug)]
 pub enum Error {
@@ -123,6 +218,23 @@ pub enum Error {
     PECError,
     BusError,
     ArbitrationLost,
+    /// Address passed to the embedded-hal traits does not fit in 7 or 10 bits
+    InvalidAddress,
+}
+
+impl embedded_hal_one::i2c::Error for Error {
+    fn kind(&self) -> embedded_hal_one::i2c::ErrorKind {
+        use embedded_hal_one::i2c::{ErrorKind, NoAcknowledgeSource};
+
+        match self {
+            Error::Overrun => ErrorKind::Overrun,
+            Error::Nack => ErrorKind::NoAcknowledge(NoAcknowledgeSource::Unknown),
+            Error::PECError => ErrorKind::Other,
+            Error::BusError => ErrorKind::Bus,
+            Error::ArbitrationLost => ErrorKind::ArbitrationLoss,
+            Error::InvalidAddress => ErrorKind::Other,
+        }
+    }
 }
 
 pub trait I2cExt<I2C> {
@@ -171,6 +283,74 @@ macro_rules! busy_wait {
     };
 }
 
+/// Adjacent operations in the same direction, which are sent as one transfer without a
+/// repeated START in between
+#[derive(Debug, PartialEq, Eq)]
+struct OperationGroup {
+    read: bool,
+    /// Indices of the operations in the group
+    operations: Range<usize>,
+    /// Total number of bytes of the operations
+    len: usize,
+}
+
+impl OperationGroup {
+    /// Reads into an empty buffer do nothing, but still separate the operations around them
+    fn is_skipped(&self) -> bool {
+        self.read && self.len == 0
+    }
+}
+
+/// The group of operations starting at `start`
+fn operation_group(operations: &[Operation<'_>], start: usize) -> Option<OperationGroup> {
+    let read = matches!(operations.get(start)?, Operation::Read(_));
+
+    let mut end = start;
+    let mut len = 0;
+    for op in &operations[start..] {
+        match op {
+            Operation::Read(buffer) if read => len += buffer.len(),
+            Operation::Write(bytes) if !read => len += bytes.len(),
+            _ => break,
+        }
+        end += 1;
+    }
+
+    Some(OperationGroup {
+        read,
+        operations: start..end,
+        len,
+    })
+}
+
+/// NBYTES and RELOAD for the next chunk of a transfer with `remaining` bytes left
+///
+/// NBYTES is only 8 bits wide, longer transfers are split into chunks of 255 bytes using
+/// RELOAD.
+fn chunk(remaining: usize) -> (u8, bool) {
+    if remaining > 255 {
+        (255, true)
+    } else {
+        (remaining as u8, false)
+    }
+}
+
+/// Load the next chunk once the current one is done, see `chunk`
+macro_rules! reload_chunk {
+    ($i2c:expr, $in_chunk:ident, $remaining:ident) => {
+        if $in_chunk == 0 {
+            // Wait for the current chunk to complete its NBYTES
+            busy_wait!($i2c, tcr, bit_is_set);
+            let (nbytes, reload) = chunk($remaining);
+            $i2c.cr2
+                .modify(|_, w| w.nbytes().bits(nbytes).reload().bit(reload));
+            $in_chunk = nbytes;
+        }
+        $in_chunk -= 1;
+        $remaining -= 1;
+    };
+}
+
 macro_rules! i2c {
     ($I2CX:ident, $i2cx:ident,
         sda: [ $($( #[ $pmetasda:meta ] )* $PSDA:ty,)+ ],
@@ -213,17 +393,13 @@ macro_rules! i2c {
                 SCL: SCLPin<$I2CX>,
             {
                 // Enable and reset I2C
-                unsafe {
-                    let rcc_ptr = &(*RCC::ptr());
-                    $I2CX::enable(rcc_ptr);
-                    $I2CX::reset(rcc_ptr);
-                }
+                rcc.enable_and_reset::<$I2CX>();
 
                 // Make sure the I2C unit is disabled so we can configure it
                 i2c.cr1.modify(|_, w| w.pe().clear_bit());
 
-                // Setup protocol timings
-                let timing_bits = config.timing_bits(<$I2CX as RccBus>::Bus::get_frequency(&rcc.clocks));
+                // Setup protocol timings, based on the kernel clock selected by `Rcc::set_kernel_clock`
+                let timing_bits = config.timing_bits(<$I2CX as KernelClock>::kernel_frequency(rcc));
                 i2c.timingr.write(|w| unsafe { w.bits(timing_bits) });
 
                 // Enable the I2C processing
@@ -252,81 +428,41 @@ macro_rules! i2c {
             }
         }
 
+        impl<SDA, SCL> I2c<$I2CX, SDA, SCL> {
+            /// Returns `true` if there is no communication on the bus (`ISR.BUSY` is clear)
+            pub fn is_bus_idle(&self) -> bool {
+                self.i2c.isr.read().busy().bit_is_clear()
+            }
+
+            /// Returns `true` if the last transfer completed with a STOP condition,
+            /// `false` if it is still ongoing or failed
+            pub fn last_stop_generated(&self) -> bool {
+                self.i2c.isr.read().stopf().bit_is_set()
+            }
+
+            /// Clear the STOP flag from a previous transfer
+            fn clear_stop_flag(&mut self) {
+                self.i2c.icr.write(|w| w.stopcf().set_bit());
+            }
+        }
+
         impl<SDA, SCL> WriteRead for I2c<$I2CX, SDA, SCL> {
             type Error = Error;
 
+            /// Write `bytes` and then read into `buffer` using a repeated START, the bus is not
+            /// released by a STOP between the write and the read
+            ///
+            /// An empty `buffer` only does the write, see `transaction_to`
             fn write_read(
                 &mut self,
                 addr: u8,
                 bytes: &[u8],
                 buffer: &mut [u8],
             ) -> Result<(), Self::Error> {
-                // TODO support transfers of more than 255 bytes
-                assert!(bytes.len() < 256 && bytes.len() > 0);
-                assert!(buffer.len() < 256 && buffer.len() > 0);
-
-                // Wait for any previous address sequence to end automatically.
-                // This could be up to 50% of a bus cycle (ie. up to 0.5/freq)
-                while self.i2c.cr2.read().start().bit_is_set() {};
-
-                // Set START and prepare to send `bytes`.
-                // The START bit can be set even if the bus is BUSY or
-                // I2C is in slave mode.
-                self.i2c.cr2.write(|w| {
-                    w
-                        // Start transfer
-                        .start().set_bit()
-                        // Set number of bytes to transfer
-                        .nbytes().bits(bytes.len() as u8)
-                        // Set address to transfer to/from
-                        .sadd().bits((addr << 1) as u16)
-                        // 7-bit addressing mode
-                        .add10().clear_bit()
-                        // Set transfer direction to write
-                        .rd_wrn().clear_bit()
-                        // Software end mode
-                        .autoend().clear_bit()
-                });
-
-                for byte in bytes {
-                    // Wait until we are allowed to send data
-                    // (START has been ACKed or last byte went through)
-                    busy_wait!(self.i2c, txis, bit_is_set);
-
-                    // Put byte on the wire
-                    self.i2c.txdr.write(|w| { w.txdata().bits(*byte) });
-                }
-
-                // Wait until the write finishes before beginning to read.
-                busy_wait!(self.i2c, tc, bit_is_set);
-
-                // reSTART and prepare to receive bytes into `buffer`
-                self.i2c.cr2.write(|w| {
-                    w
-                        // Start transfer
-                        .start().set_bit()
-                        // Set number of bytes to transfer
-                        .nbytes().bits(buffer.len() as u8)
-                        // Set address to transfer to/from
-                        .sadd().bits((addr << 1) as u16)
-                        // 7-bit addressing mode
-                        .add10().clear_bit()
-                        // Set transfer direction to read
-                        .rd_wrn().set_bit()
-                        // Automatic end mode
-                        .autoend().set_bit()
-                });
-
-                for byte in buffer {
-                    // Wait until we have received something
-                    busy_wait!(self.i2c, rxne, bit_is_set);
-
-                    *byte = self.i2c.rxdr.read().rxdata().bits();
-                }
-
-                // automatic STOP
-
-                Ok(())
+                self.transaction_to(
+                    seven_bit(addr)?,
+                    &mut [Operation::Write(bytes), Operation::Read(buffer)],
+                )
             }
         }
 
@@ -335,6 +471,8 @@ macro_rules! i2c {
 
             fn write(&mut self, addr: u8, bytes: &[u8]) -> Result<(), Self::Error> {
                 assert!(bytes.len() < 256 && bytes.len() > 0);
+                let addr = seven_bit(addr)?;
+                self.clear_stop_flag();
 
                 self.i2c.cr2.modify(|_, w| {
                     w
@@ -343,7 +481,9 @@ macro_rules! i2c {
                         // Set number of bytes to transfer
                         .nbytes().bits(bytes.len() as u8)
                         // Set address to transfer to/from
-                        .sadd().bits((addr << 1) as u16)
+                        .sadd().bits(addr.sadd())
+                        // 7-bit addressing mode
+                        .add10().clear_bit()
                         // Set transfer direction to write
                         .rd_wrn().clear_bit()
                         // Automatic end mode
@@ -365,12 +505,125 @@ macro_rules! i2c {
             }
         }
 
+        impl<SDA, SCL> embedded_hal_one::i2c::ErrorType for I2c<$I2CX, SDA, SCL> {
+            type Error = Error;
+        }
+
+        impl<SDA, SCL> I2c<$I2CX, SDA, SCL> {
+            /// Execute the provided operations on the I2C bus, like
+            /// `embedded_hal_one::i2c::I2c::transaction` but with an already validated 7 or
+            /// 10-bit address
+            ///
+            /// Reads into an empty buffer are skipped. The peripheral can not end a read of zero
+            /// bytes, since the byte the slave starts sending after the address can not be
+            /// NACKed. They still end a run of writes, so the write after them starts with a
+            /// repeated START. Empty writes are sent as just the address, which is a quick
+            /// command.
+            pub fn transaction_to<A: Address>(
+                &mut self,
+                address: A,
+                operations: &mut [Operation<'_>],
+            ) -> Result<(), Error> {
+                self.clear_stop_flag();
+
+                let mut next = 0;
+                let mut started = false;
+                while let Some(group) = operation_group(operations, next) {
+                    next = group.operations.end;
+                    if group.is_skipped() {
+                        continue;
+                    }
+
+                    let mut remaining = group.len;
+                    let (nbytes, reload) = chunk(remaining);
+                    let mut in_chunk = nbytes;
+
+                    // Wait for any previous address sequence to end automatically.
+                    while self.i2c.cr2.read().start().bit_is_set() {};
+
+                    self.i2c.cr2.write(|w| {
+                        w
+                            // (Re)start transfer
+                            .start().set_bit()
+                            // Set number of bytes to transfer
+                            .nbytes().bits(nbytes)
+                            // Set address to transfer to/from
+                            .sadd().bits(address.sadd())
+                            // 7 or 10-bit addressing mode
+                            .add10().bit(address.is_ten_bit())
+                            // Set transfer direction
+                            .rd_wrn().bit(group.read)
+                            // Software end mode, STOP is sent after the last group
+                            .autoend().clear_bit()
+                            .reload().bit(reload)
+                    });
+                    started = true;
+
+                    for operation in operations[group.operations].iter_mut() {
+                        match operation {
+                            Operation::Read(buffer) => {
+                                for byte in buffer.iter_mut() {
+                                    reload_chunk!(self.i2c, in_chunk, remaining);
+                                    busy_wait!(self.i2c, rxne, bit_is_set);
+                                    *byte = self.i2c.rxdr.read().rxdata().bits();
+                                }
+                            }
+                            Operation::Write(bytes) => {
+                                for byte in bytes.iter() {
+                                    reload_chunk!(self.i2c, in_chunk, remaining);
+                                    busy_wait!(self.i2c, txis, bit_is_set);
+                                    self.i2c.txdr.write(|w| w.txdata().bits(*byte));
+                                }
+                            }
+                        }
+                    }
+
+                    // Wait until the transfer is complete before a repeated START or STOP
+                    busy_wait!(self.i2c, tc, bit_is_set);
+                }
+
+                if started {
+                    self.i2c.cr2.modify(|_, w| w.stop().set_bit());
+                    busy_wait!(self.i2c, stopf, bit_is_set);
+                }
+
+                Ok(())
+            }
+        }
+
+        impl<SDA, SCL> embedded_hal_one::i2c::I2c for I2c<$I2CX, SDA, SCL> {
+            fn transaction(
+                &mut self,
+                addr: u8,
+                operations: &mut [Operation<'_>],
+            ) -> Result<(), Self::Error> {
+                self.transaction_to(seven_bit(addr)?, operations)
+            }
+        }
+
+        impl<SDA, SCL> embedded_hal_one::i2c::I2c<embedded_hal_one::i2c::TenBitAddress> for I2c<$I2CX, SDA, SCL> {
+            fn transaction(
+                &mut self,
+                addr: u16,
+                operations: &mut [Operation<'_>],
+            ) -> Result<(), Self::Error> {
+                let address = Address10::new(addr).ok_or(Error::InvalidAddress)?;
+                self.transaction_to(address, operations)
+            }
+        }
+
         impl<SDA, SCL> Read for I2c<$I2CX, SDA, SCL> {
             type Error = Error;
 
+            /// Reads into an empty buffer do nothing, see `transaction_to`
             fn read(&mut self, addr: u8, bytes: &mut [u8]) -> Result<(), Self::Error> {
+                if bytes.is_empty() {
+                    return Ok(());
+                }
                 // TODO support transfers of more than 255 bytes
-                assert!(bytes.len() < 256 && bytes.len() > 0);
+                assert!(bytes.len() < 256);
+                let addr = seven_bit(addr)?;
+                self.clear_stop_flag();
 
                 // Wait for any previous address sequence to end automatically.
                 // This could be up to 50% of a bus cycle (ie. up to 0.5/freq)
@@ -386,7 +639,9 @@ macro_rules! i2c {
                         // Set number of bytes to transfer
                         .nbytes().bits(bytes.len() as u8)
                         // Set address to transfer to/from
-                        .sadd().bits((addr << 1) as u16)
+                        .sadd().bits(addr.sadd())
+                        // 7-bit addressing mode
+                        .add10().clear_bit()
                         // Set transfer direction to read
                         .rd_wrn().set_bit()
                         // automatic end mode
@@ -513,3 +768,105 @@ i2c!(
         PG3<AlternateOD<AF4>>,
     ],
 );
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn groups(operations: &[Operation<'_>]) -> ([Option<OperationGroup>; 4], usize) {
+        let mut groups = [None, None, None, None];
+        let mut next = 0;
+        let mut count = 0;
+        while let Some(group) = operation_group(operations, next) {
+            next = group.operations.end;
+            if !group.is_skipped() {
+                groups[count] = Some(group);
+                count += 1;
+            }
+        }
+        (groups, count)
+    }
+
+    #[test]
+    fn empty_read_separates_writes() {
+        let mut empty = [];
+        let operations = [
+            Operation::Write(&[1, 2]),
+            Operation::Read(&mut empty),
+            Operation::Write(&[3]),
+        ];
+        let (groups, count) = groups(&operations);
+        assert_eq!(count, 2);
+        assert_eq!(
+            groups[0],
+            Some(OperationGroup {
+                read: false,
+                operations: 0..1,
+                len: 2,
+            })
+        );
+        assert_eq!(
+            groups[1],
+            Some(OperationGroup {
+                read: false,
+                operations: 2..3,
+                len: 1,
+            })
+        );
+    }
+
+    #[test]
+    fn adjacent_operations_are_merged() {
+        let mut a = [0; 2];
+        let mut empty = [];
+        let mut b = [0; 3];
+        let operations = [
+            Operation::Write(&[]),
+            Operation::Write(&[1]),
+            Operation::Read(&mut a),
+            Operation::Read(&mut empty),
+            Operation::Read(&mut b),
+        ];
+        let (groups, count) = groups(&operations);
+        assert_eq!(count, 2);
+        assert_eq!(
+            groups[0],
+            Some(OperationGroup {
+                read: false,
+                operations: 0..2,
+                len: 1,
+            })
+        );
+        assert_eq!(
+            groups[1],
+            Some(OperationGroup {
+                read: true,
+                operations: 2..5,
+                len: 5,
+            })
+        );
+    }
+
+    #[test]
+    fn only_empty_reads() {
+        let mut empty = [];
+        let operations = [Operation::Read(&mut empty)];
+        assert_eq!(groups(&operations).1, 0);
+    }
+
+    #[test]
+    fn chunks() {
+        assert_eq!(chunk(0), (0, false));
+        assert_eq!(chunk(255), (255, false));
+        assert_eq!(chunk(256), (255, true));
+        assert_eq!(chunk(1000), (255, true));
+    }
+
+    #[test]
+    fn address_range() {
+        assert_eq!(seven_bit(0x7F).map(Address7::get).ok(), Some(0x7F));
+        assert!(matches!(seven_bit(0x80), Err(Error::InvalidAddress)));
+        assert_eq!(Address10::new(0x3FF).map(|a| a.sadd()), Some(0x3FF));
+        assert_eq!(Address10::new(0x400), None);
+    }
+}
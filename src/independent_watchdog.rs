@@ -6,24 +6,46 @@
 //!
 //! The Independent Watchdog peripheral triggers a system reset when its internal counter expires.
 //!
+//! Timeouts which are not known at compile time, like ones loaded from flash, should be applied
+//! with `try_start`/`try_start_windowed` which report invalid timeouts instead of panicking.
+//!
 //! # Examples
 //!
 //! - [IWDG Example](todo-insert-link-here)
 //!
 //! Originally from stm32h7-hal, adapted for stm32g4xx-hal
 use crate::{
+    dbgmcu::{DbgMcu, DebugStop},
+    rcc::{Rcc, LSI_FREQ},
     stm32::{iwdg::pr::PR_A, IWDG},
-    time::MicroSecond,
+    time::{MicroSecond, MilliSecond},
 };
 use fugit::ExtU32;
 
+/// Invalid watchdog timeout
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum WatchdogError {
+    /// The max time is longer than the ~32.7s the IWDG can count to
+    MaxTooLong,
+    /// The min time is greater than the max time
+    MinGreaterThanMax,
+}
+
 /// The implementation of the hardware IWDG
 pub struct IndependentWatchdog {
     iwdg: IWDG,
+
+    /// Prescaler divider, reload value and window size (in counter ticks) of the last
+    /// `start_windowed`, cached since PR, RLR and WINR are write protected once started
+    prescaler_divider: u32,
+    reload_value: u32,
+    window_value: u32,
 }
 
 impl IndependentWatchdog {
-    const CLOCK_SPEED: u32 = 32000;
+    /// The IWDG is always clocked by the LSI, it can not use the LSE
+    const CLOCK_SPEED: u32 = LSI_FREQ;
     const MAX_COUNTER_VALUE: u32 = 0x00000FFF;
     const MAX_MILLIS_FOR_PRESCALER: [(PR_A, u32); 8] = [
         (
@@ -62,7 +84,25 @@ impl IndependentWatchdog {
 
     /// Create a new instance
     pub fn new(iwdg: IWDG) -> Self {
-        Self { iwdg }
+        Self {
+            iwdg,
+            prescaler_divider: 0,
+            reload_value: 0,
+            window_value: 0,
+        }
+    }
+
+    /// Returns `true` if the independent watchdog caused a reset since the reset flags were
+    /// last cleared, see `Rcc::get_reset_reason` and `Rcc::clear_reset_reason`
+    pub fn caused_reset(rcc: &Rcc) -> bool {
+        rcc.get_reset_reason().independent_watchdog
+    }
+
+    /// Stop the watchdog while the core is halted by a debugger if `stop` is `true`
+    ///
+    /// Same as `dbgmcu.stop_on_debug(DebugStop::Iwdg, stop)`
+    pub fn stop_on_debug(&self, dbgmcu: &mut DbgMcu, stop: bool) {
+        dbgmcu.stop_on_debug(DebugStop::Iwdg, stop);
     }
 
     /// Feed the watchdog, resetting the timer to 0
@@ -72,20 +112,52 @@ impl IndependentWatchdog {
 
     /// Start the watchdog where it must be fed before the max time is over and
     /// not before the min time has passed
-    pub fn start_windowed<T: Into<MicroSecond>>(&mut self, min_window_time: T, max_window_time: T) {
+    ///
+    /// The times are rounded to what the prescaler and 12 bit counter can represent, the
+    /// effective `(min, max)` times are returned, see also `window` and `interval`.
+    ///
+    /// Panics if the times are invalid, use `try_start_windowed` for user supplied timeouts.
+    pub fn start_windowed<T: Into<MicroSecond>>(
+        &mut self,
+        min_window_time: T,
+        max_window_time: T,
+    ) -> (MilliSecond, MilliSecond) {
+        match self.try_start_windowed(min_window_time, max_window_time) {
+            Ok(times) => times,
+            Err(WatchdogError::MaxTooLong) => panic!("IWDG max time is greater than is possible"),
+            Err(WatchdogError::MinGreaterThanMax) => {
+                panic!("IWDG min time is greater than max time")
+            }
+        }
+    }
+
+    /// Same as `start_windowed` but returns an error instead of panicking on invalid times
+    ///
+    /// The watchdog is left untouched if an error is returned.
+    pub fn try_start_windowed<T: Into<MicroSecond>>(
+        &mut self,
+        min_window_time: T,
+        max_window_time: T,
+    ) -> Result<(MilliSecond, MilliSecond), WatchdogError> {
         let min_window_time: MicroSecond = min_window_time.into();
         let max_window_time: MicroSecond = max_window_time.into();
 
+        if min_window_time > max_window_time {
+            return Err(WatchdogError::MinGreaterThanMax);
+        }
+
+        // Find the prescaler before touching the watchdog, it can not be stopped once started
+        let (prescaler, _) = Self::MAX_MILLIS_FOR_PRESCALER
+            .iter()
+            .find(|(_, max_millis)| *max_millis >= max_window_time.to_millis())
+            .ok_or(WatchdogError::MaxTooLong)?;
+
         // Start the watchdog
         self.iwdg.kr.write(|w| w.key().start());
         // Enable register access
         self.iwdg.kr.write(|w| w.key().enable());
 
         // Set the prescaler
-        let (prescaler, _) = Self::MAX_MILLIS_FOR_PRESCALER
-            .iter()
-            .find(|(_, max_millis)| *max_millis >= max_window_time.to_millis())
-            .expect("IWDG max time is greater than is possible");
         while self.iwdg.sr.read().pvu().bit_is_set() {
             cortex_m::asm::nop();
         }
@@ -129,11 +201,45 @@ impl IndependentWatchdog {
         }
 
         self.feed();
+
+        self.prescaler_divider = Self::get_prescaler_divider(prescaler);
+        self.reload_value = reload_value;
+        self.window_value = window_value;
+
+        Ok((self.window(), self.interval()))
     }
 
     /// Start the watchdog with the given max time and no minimal time
-    pub fn start<T: Into<MicroSecond>>(&mut self, max_time: T) {
-        self.start_windowed(0_u32.millis(), max_time.into());
+    ///
+    /// Returns the effective max time, see `interval`
+    pub fn start<T: Into<MicroSecond>>(&mut self, max_time: T) -> MilliSecond {
+        self.start_windowed(0_u32.millis(), max_time.into()).1
+    }
+
+    /// Same as `start` but returns an error instead of panicking if `max_time` is too long
+    pub fn try_start<T: Into<MicroSecond>>(
+        &mut self,
+        max_time: T,
+    ) -> Result<MilliSecond, WatchdogError> {
+        self.try_start_windowed(0_u32.millis(), max_time.into())
+            .map(|(_, max)| max)
+    }
+
+    /// The effective max time between feeds, computed from the configured prescaler and reload value
+    ///
+    /// Returns 0 if the watchdog has not been started. Note that the IWDG counter itself can not
+    /// be read, so there is no way to tell how much of this time remains.
+    pub fn interval(&self) -> MilliSecond {
+        self.ticks_to_millis(self.reload_value)
+    }
+
+    /// The effective min time before the watchdog may be fed, 0 if there is no window
+    pub fn window(&self) -> MilliSecond {
+        self.ticks_to_millis(self.window_value)
+    }
+
+    fn ticks_to_millis(&self, ticks: u32) -> MilliSecond {
+        (ticks * self.prescaler_divider * 1000 / Self::CLOCK_SPEED).millis()
     }
 
     fn get_prescaler_divider(prescaler: &PR_A) -> u32 {
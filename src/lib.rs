@@ -75,6 +75,7 @@ pub mod can;
 pub mod comparator;
 // pub mod crc;
 pub mod dac;
+pub mod dbgmcu;
 pub mod delay;
 pub mod dma;
 pub mod exti;
@@ -56,6 +56,12 @@
 // TODO: The output can not be a Option<PIN> if we want to handle "route to pin vs adc"
 //       in a compile time way. See OPAINTOEN in OPAMPx_CSR
 
+/// Output state of an opamp whose output is only connected internally, to the ADC
+///
+/// Only an opamp in this state can be sampled by the ADC. Use `into_internal_output` on an
+/// opamp to get it into this state.
+pub struct InternalOutput;
+
 /// Pga mode internal
 ///
 /// This mode does not expose the inverting signal on any pin,
@@ -176,6 +182,8 @@ macro_rules! opamps {
                 pub mod $opamp {
                     use core::{borrow::Borrow, marker::PhantomData};
 
+                    use super::InternalOutput;
+
                     #[allow(unused_imports)]
                     use crate::gpio::gpioa::*;
 
@@ -186,16 +194,20 @@ macro_rules! opamps {
                     pub struct Disabled;
 
                     /// State type for opamp running in voltage follower mode.
-                    pub struct Follower<Input> {
+                    ///
+                    /// `Output` is either the optional output pin or [`InternalOutput`].
+                    pub struct Follower<Input, Output = Option<$output>> {
                         input: Input,
-                        output: Option<$output>,
+                        output: Output,
                     }
 
                     /// State type for opamp running in open-loop mode.
-                    pub struct OpenLoop<NonInverting, Inverting> {
+                    ///
+                    /// `Output` is either the optional output pin or [`InternalOutput`].
+                    pub struct OpenLoop<NonInverting, Inverting, Output = Option<$output>> {
                         non_inverting: NonInverting,
                         inverting: Inverting,
-                        output: Option<$output>,
+                        output: Output,
                     }
 
                     // TODO: Inverting gain
@@ -220,10 +232,12 @@ macro_rules! opamps {
                     }
 
                     /// State type for opamp running in programmable-gain mode.
-                    pub struct Pga<NonInverting, MODE> {
+                    ///
+                    /// `Output` is either the optional output pin or [`InternalOutput`].
+                    pub struct Pga<NonInverting, MODE, Output = Option<$output>> {
                         non_inverting: PhantomData<NonInverting>,
                         config: MODE,
-                        output: Option<$output>,
+                        output: Output,
                     }
 
                     /// Trait for opamps that can be run in programmable gain mode.
@@ -248,7 +262,7 @@ macro_rules! opamps {
                         pub fn enable_output(&mut self, output:$output) {
                             self.output = Some(output);
                             unsafe {
-                                (*crate::stm32::OPAMP::ptr()).[<$opamp _csr>].write(|w|
+                                (*crate::stm32::OPAMP::ptr()).[<$opamp _csr>].modify(|_, w|
                                     w.opaintoen().output_pin());
                             }
                         }
@@ -258,11 +272,27 @@ macro_rules! opamps {
                         /// If the output was enabled, the output pin is returned.
                         pub fn disable_output(&mut self) -> Option<$output> {
                             unsafe {
-                                (*crate::stm32::OPAMP::ptr()).[<$opamp _csr>].write(|w|
+                                (*crate::stm32::OPAMP::ptr()).[<$opamp _csr>].modify(|_, w|
                                     w.opaintoen().adcchannel());
                             }
                             self.output.take()
                         }
+
+                        /// Connects the output to the ADC only and returns the output pin, if the
+                        /// output was routed to it
+                        pub fn into_internal_output(mut self) -> (Follower<Input, InternalOutput>, Option<$output>) {
+                            let output = self.disable_output();
+                            (Follower { input: self.input, output: InternalOutput }, output)
+                        }
+                    }
+
+                    impl<Input> Follower<Input, InternalOutput> {
+
+                        /// Disables the opamp and returns the resources it held.
+                        pub fn disable(self) -> (Disabled, Input) {
+                            unsafe { (*crate::stm32::OPAMP::ptr()).[<$opamp _csr>].reset() }
+                            (Disabled, self.input)
+                        }
                     }
 
                     impl<NonInverting, Inverting> OpenLoop<NonInverting, Inverting> {
@@ -277,7 +307,7 @@ macro_rules! opamps {
                         pub fn enable_output(&mut self, output:$output) {
                             self.output = Some(output);
                             unsafe {
-                                (*crate::stm32::OPAMP::ptr()).[<$opamp _csr>].write(|w|
+                                (*crate::stm32::OPAMP::ptr()).[<$opamp _csr>].modify(|_, w|
                                     w.opaintoen().output_pin());
                             }
                         }
@@ -287,11 +317,32 @@ macro_rules! opamps {
                         /// If the output was enabled, the output pin is returned.
                         pub fn disable_output(&mut self) -> Option<$output> {
                             unsafe {
-                                (*crate::stm32::OPAMP::ptr()).[<$opamp _csr>].write(|w|
+                                (*crate::stm32::OPAMP::ptr()).[<$opamp _csr>].modify(|_, w|
                                     w.opaintoen().adcchannel());
                             }
                             self.output.take()
                         }
+
+                        /// Connects the output to the ADC only and returns the output pin, if the
+                        /// output was routed to it
+                        pub fn into_internal_output(mut self) -> (OpenLoop<NonInverting, Inverting, InternalOutput>, Option<$output>) {
+                            let output = self.disable_output();
+                            let opamp = OpenLoop {
+                                non_inverting: self.non_inverting,
+                                inverting: self.inverting,
+                                output: InternalOutput,
+                            };
+                            (opamp, output)
+                        }
+                    }
+
+                    impl<NonInverting, Inverting> OpenLoop<NonInverting, Inverting, InternalOutput> {
+
+                        /// Disables the opamp and returns the resources it held.
+                        pub fn disable(self) -> (Disabled, NonInverting, Inverting) {
+                            unsafe { (*crate::stm32::OPAMP::ptr()).[<$opamp _csr>].reset() }
+                            (Disabled, self.non_inverting, self.inverting)
+                        }
                     }
 
                     impl<NonInverting, MODE> Pga<NonInverting, MODE> {
@@ -306,7 +357,7 @@ macro_rules! opamps {
                         pub fn enable_output(&mut self, output: $output) {
                             self.output = Some(output);
                             unsafe {
-                                (*crate::stm32::OPAMP::ptr()).[<$opamp _csr>].write(|w|
+                                (*crate::stm32::OPAMP::ptr()).[<$opamp _csr>].modify(|_, w|
                                     w.opaintoen().output_pin());
                             }
                         }
@@ -316,11 +367,32 @@ macro_rules! opamps {
                         /// If the output was enabled, the output pin is returned.
                         pub fn disable_output(&mut self) -> Option<$output> {
                             unsafe {
-                                (*crate::stm32::OPAMP::ptr()).[<$opamp _csr>].write(|w|
+                                (*crate::stm32::OPAMP::ptr()).[<$opamp _csr>].modify(|_, w|
                                     w.opaintoen().adcchannel());
                             }
                             self.output.take()
                         }
+
+                        /// Connects the output to the ADC only and returns the output pin, if the
+                        /// output was routed to it
+                        pub fn into_internal_output(mut self) -> (Pga<NonInverting, MODE, InternalOutput>, Option<$output>) {
+                            let output = self.disable_output();
+                            let opamp = Pga {
+                                non_inverting: PhantomData,
+                                config: self.config,
+                                output: InternalOutput,
+                            };
+                            (opamp, output)
+                        }
+                    }
+
+                    impl<NonInverting, MODE> Pga<NonInverting, MODE, InternalOutput> {
+
+                        /// Disables the opamp and returns the resources it held.
+                        pub fn disable(self) -> (Disabled, MODE) {
+                            unsafe { (*crate::stm32::OPAMP::ptr()).[<$opamp _csr>].reset() }
+                            (Disabled, self.config)
+                        }
                     }
 
                     opamps!{ @follower $opamp, $output, $($non_inverting_mask, $non_inverting),* }
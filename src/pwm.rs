@@ -176,7 +176,6 @@ use fugit::HertzU64;
 
 use crate::hal;
 use crate::stm32::LPTIMER1;
-use crate::stm32::RCC;
 #[cfg(any(
     feature = "stm32g473",
     feature = "stm32g474",
@@ -196,7 +195,7 @@ use crate::stm32::TIM20;
 use crate::stm32::TIM5;
 use crate::stm32::{TIM1, TIM15, TIM16, TIM17, TIM2, TIM3, TIM4, TIM8};
 
-use crate::rcc::{Enable, GetBusFreq, Rcc, Reset};
+use crate::rcc::{GetBusFreq, Rcc};
 use crate::time::{ExtU32, Hertz, NanoSecond, RateExtU32};
 
 #[cfg(any(
@@ -1182,11 +1181,7 @@ macro_rules! simple_tim_hal {
         where
             PINS: Pins<$TIMX, T, U>,
         {
-            unsafe {
-                let rcc_ptr = &(*RCC::ptr());
-                $TIMX::enable(rcc_ptr);
-                $TIMX::reset(rcc_ptr);
-            }
+            rcc.enable_and_reset::<$TIMX>();
 
             let clk = $TIMX::get_timer_frequency(&rcc.clocks);
 
@@ -1237,11 +1232,7 @@ macro_rules! tim_hal {
                 where
                     PINS: Pins<Self, CHANNEL, COMP>
                 {
-                    unsafe {
-                        let rcc_ptr = &(*RCC::ptr());
-                        $TIMX::enable(rcc_ptr);
-                        $TIMX::reset(rcc_ptr);
-                    }
+                    rcc.enable_and_reset::<$TIMX>();
 
                     let clk = $TIMX::get_timer_frequency(&rcc.clocks).raw();
 
@@ -1810,11 +1801,7 @@ macro_rules! lptim_hal {
             where
                 PINS: Pins<$TIMX, T, U>,
             {
-                unsafe {
-                    let rcc_ptr = &(*RCC::ptr());
-                    $TIMX::enable(rcc_ptr);
-                    $TIMX::reset(rcc_ptr);
-                }
+                rcc.enable_and_reset::<$TIMX>();
 
                 let clk = $TIMX::get_timer_frequency(&rcc.clocks);
                 let reload = clk / freq;
@@ -10,6 +10,9 @@
 //!
 //! There is a Boost Mode that allows higher clock speeds.
 //!
+//! The low-power Stop modes, see [`StopMode`], are entered using `Rcc::enter_stop` which also
+//! restores the clocks on wakeup.
+//!
 //! Adapted from stm32h7xx-hal
 
 use crate::stm32::PWR;
@@ -45,6 +48,14 @@ impl Pwr {
 
         self
     }
+
+    /// Select voltage range 1 with boost mode, required for system clocks above 150MHz
+    ///
+    /// `Rcc::freeze` sets `PWR_CR5.R1MODE` and the extra flash wait states when switching clocks.
+    pub fn vos_boost(self) -> Self {
+        self.vos(VoltageScale::Range1 { enable_boost: true })
+    }
+
     pub fn freeze(self) -> PowerConfiguration {
         let Self { vos } = self;
         PowerConfiguration { vos }
@@ -82,6 +93,19 @@ pub enum VoltageScale {
     Range2,
 }
 
+/// Low-power Stop mode, entered using `Rcc::enter_stop`
+///
+/// All clocks in the core domain are stopped and SRAM and register contents are retained.
+/// Note that the STM32G4 has no Stop 2 mode, Stop 1 is the lowest power Stop mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum StopMode {
+    /// Main regulator kept on, fastest wakeup
+    Stop0 = 0b000,
+    /// Main regulator off, low-power regulator on
+    Stop1 = 0b001,
+}
+
 /// Power Configuration
 ///
 /// Generated when the PWR peripheral is frozen. The existence of this
@@ -71,9 +71,27 @@ impl<PIN> Mco<PIN> {
 }
 
 pub trait MCOExt<PIN> {
+    /// Configure the MCO (MCOSEL and MCOPRE), call `Mco::enable` to start outputting the clock
+    ///
+    /// Panics if `psc` is greater than `Prescaler::Div16`
     fn mco(self, src: MCOSrc, psc: Prescaler, rcc: &mut Rcc) -> Mco<PIN>;
 }
 
+impl Rcc {
+    /// Output `src` divided by `psc` on the MCO `pin` (PA8 or PG10)
+    ///
+    /// Same as `pin.mco(src, psc, rcc)` followed by `Mco::enable`. Useful to verify the clock
+    /// configuration with a scope.
+    pub fn configure_mco<P, PIN>(&mut self, src: MCOSrc, psc: Prescaler, pin: P) -> Mco<PIN>
+    where
+        P: MCOExt<PIN>,
+    {
+        let mco = pin.mco(src, psc, self);
+        mco.enable();
+        mco
+    }
+}
+
 macro_rules! mco {
     ($($PIN:ident),+) => {
         $(
@@ -85,9 +103,7 @@ macro_rules! mco {
                         Prescaler::Div4 => 0b010,
                         Prescaler::Div8 => 0b011,
                         Prescaler::Div16 => 0b100,
-                        Prescaler::Div32 => 0b101,
-                        Prescaler::Div64 => 0b110,
-                        _ => 0b111,
+                        _ => panic!("MCO prescaler can be at most Div16"),
                     };
                     rcc.rb.cfgr.modify(|r, w| unsafe {
                         w.bits((r.bits() & !(0b111 << 28)) | (psc_bits << 28))
@@ -38,6 +38,19 @@ pub enum LSCOSrc {
     LSE,
 }
 
+/// RTC clock source
+#[derive(Clone, Copy)]
+pub enum RtcClockSource {
+    /// LSE crystal, see `LSE_FREQ`
+    LSE,
+    /// External clock on OSC32_IN, see `LSE_FREQ`
+    LSE_BYPASS,
+    /// LSI, see `LSI_FREQ`
+    LSI,
+    /// HSE divided by 32, the HSE has to be running already, e.g. as PLL source
+    HSE_DIV32(Hertz),
+}
+
 /// PLL clock input source
 #[derive(Clone, Copy)]
 pub enum PLLSrc {
@@ -322,6 +335,44 @@ pub struct PllConfig {
     pub p: Option<PllPDiv>,
 }
 
+impl PllConfig {
+    /// Frequency at the PLL input, after the M divider
+    pub const fn input_frequency(&self) -> Hertz {
+        Hertz::from_raw(self.mux.frequency().raw() / self.m.divisor())
+    }
+
+    /// Frequency of the PLL VCO
+    pub const fn vco_frequency(&self) -> Hertz {
+        Hertz::from_raw(self.input_frequency().raw() * self.n.multiplier())
+    }
+
+    /// Check the PLL frequencies against the limits in the datasheet
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let input = self.input_frequency();
+        if !(2_660_000..=16_000_000).contains(&input.raw()) {
+            return Err(ConfigError::PllInput(input));
+        }
+
+        let vco = self.vco_frequency();
+        if !(96_000_000..=344_000_000).contains(&vco.raw()) {
+            return Err(ConfigError::PllVco(vco));
+        }
+
+        let outputs = [
+            self.p.map(|p| (vco / p.divisor(), 2_064_500)),
+            self.q.map(|q| (vco / q.divisor(), 8_000_000)),
+            self.r.map(|r| (vco / r.divisor(), 8_000_000)),
+        ];
+        for &(freq, min) in outputs.iter().flatten() {
+            if !(min..=MAX_SYS_FREQ).contains(&freq.raw()) {
+                return Err(ConfigError::PllOutput(freq));
+            }
+        }
+
+        Ok(())
+    }
+}
+
 impl Default for PllConfig {
     fn default() -> PllConfig {
         PllConfig {
@@ -335,6 +386,28 @@ impl Default for PllConfig {
     }
 }
 
+/// Max system clock frequency
+const MAX_SYS_FREQ: u32 = 170_000_000;
+
+/// Max system clock frequency without boost mode
+const MAX_SYS_FREQ_NO_BOOST: u32 = 150_000_000;
+
+/// Clock configuration outside of the datasheet limits, see `Config::validate`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ConfigError {
+    /// PLL input frequency after the M divider is not within 2.66 - 16MHz
+    PllInput(Hertz),
+    /// PLL VCO frequency is not within 96 - 344MHz
+    PllVco(Hertz),
+    /// A PLL P, Q or R output is outside of its allowed range
+    PllOutput(Hertz),
+    /// PLL selected as system clock without the R output enabled
+    PllRDisabled,
+    /// System clock above 170MHz, or above 150MHz without boost mode
+    SysClk(Hertz),
+}
+
 /// Clocks configutation
 pub struct Config {
     pub(crate) sys_mux: SysClockSrc,
@@ -385,10 +458,46 @@ impl Config {
         self
     }
 
+    /// NOTE: `Rcc::freeze` takes the boost mode from the `PowerConfiguration`, use
+    /// `Pwr::vos_boost` to run at more than 150MHz
     pub fn boost(mut self, enable_boost: bool) -> Self {
         self.enable_boost = enable_boost;
         self
     }
+
+    /// Check the configuration against the limits in the datasheet
+    ///
+    /// `Rcc::freeze` does not check this, an invalid configuration might still run but at the
+    /// wrong frequency. A system clock above 150MHz requires boost mode, see `Config::boost`. The PLL is only checked if it is used, that is if it is selected as
+    /// system clock or its P or Q outputs are enabled. The resulting frequencies are available
+    /// in `rcc.clocks` after `freeze`, e.g. `rcc.clocks.pll_clk.r`.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let pll_used = matches!(self.sys_mux, SysClockSrc::PLL)
+            || self.pll_cfg.p.is_some()
+            || self.pll_cfg.q.is_some();
+        if pll_used {
+            self.pll_cfg.validate()?;
+        }
+
+        let sys_clk = match self.sys_mux {
+            SysClockSrc::PLL => match self.pll_cfg.r {
+                Some(r) => self.pll_cfg.vco_frequency() / r.divisor(),
+                None => return Err(ConfigError::PllRDisabled),
+            },
+            SysClockSrc::HSI => Hertz::MHz(16),
+            SysClockSrc::HSE(freq) => freq,
+        };
+        let max = if self.enable_boost {
+            MAX_SYS_FREQ
+        } else {
+            MAX_SYS_FREQ_NO_BOOST
+        };
+        if sys_clk.raw() > max {
+            return Err(ConfigError::SysClk(sys_clk));
+        }
+
+        Ok(())
+    }
 }
 
 impl Default for Config {
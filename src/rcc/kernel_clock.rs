@@ -0,0 +1,278 @@
+use crate::rcc::*;
+use crate::stm32::{ADC1, ADC2, FDCAN1, I2C1, I2C2, I2C3, LPUART1, UART4, USART1, USART2, USART3};
+
+#[cfg(any(
+    feature = "stm32g471",
+    feature = "stm32g473",
+    feature = "stm32g474",
+    feature = "stm32g483",
+    feature = "stm32g484"
+))]
+use crate::stm32::{ADC3, I2C4, UART5};
+
+#[cfg(any(
+    feature = "stm32g473",
+    feature = "stm32g474",
+    feature = "stm32g483",
+    feature = "stm32g484"
+))]
+use crate::stm32::{ADC4, ADC5, FDCAN3};
+
+#[cfg(any(
+    feature = "stm32g471",
+    feature = "stm32g473",
+    feature = "stm32g474",
+    feature = "stm32g483",
+    feature = "stm32g484"
+))]
+use crate::stm32::FDCAN2;
+
+/// I2C kernel clock source (CCIPR/CCIPR2 I2CxSEL)
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum I2cClockSource {
+    /// APB clock, the default
+    Pclk = 0b00,
+    /// System clock
+    SysClk = 0b01,
+    /// HSI16, keeps running in Stop mode which allows the I2C to wake up the MCU
+    Hsi16 = 0b10,
+}
+
+/// U(S)ART and LPUART kernel clock source (CCIPR UARTxSEL)
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum UsartClockSource {
+    /// APB clock, the default
+    Pclk = 0b00,
+    /// System clock
+    SysClk = 0b01,
+    /// HSI16
+    Hsi16 = 0b10,
+    /// LSE, which has to be enabled separately
+    Lse = 0b11,
+}
+
+/// ADC kernel clock source (CCIPR ADC12SEL/ADC345SEL)
+///
+/// ADC1 and ADC2 share one selection, as do ADC3, ADC4 and ADC5. This is the asynchronous
+/// clock of the ADC, it is not used in the synchronous clock modes.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum AdcClockSource {
+    /// No clock, the default
+    NoClock = 0b00,
+    /// PLL "P" output, which has to be enabled in the PLL configuration
+    PllP = 0b01,
+    /// System clock
+    SysClk = 0b10,
+}
+
+/// FDCAN kernel clock source (CCIPR FDCANSEL)
+///
+/// The selection is shared by all FDCAN instances.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum FdcanClockSource {
+    /// HSE, the default, which has to be enabled separately
+    Hse = 0b00,
+    /// PLL "Q" output, which has to be enabled in the PLL configuration
+    PllQ = 0b01,
+    /// APB clock
+    Pclk = 0b10,
+}
+
+/// Peripheral with a selectable kernel clock
+pub trait KernelClockSelect: RccBus {
+    type Source: Copy;
+
+    /// Select the kernel clock source, HSI16 is enabled if selected
+    fn set_kernel_clock(rcc: &mut Rcc, source: Self::Source);
+
+    /// Currently selected kernel clock source
+    fn kernel_clock(rcc: &Rcc) -> Self::Source;
+}
+
+/// Peripheral with a selectable kernel clock of which the frequency is known
+///
+/// Not implemented for the ADC and FDCAN, which can be clocked from the HSE or a PLL output
+/// of which the frequency is not tracked by `Rcc`.
+pub trait KernelClock: KernelClockSelect {
+    /// Frequency of the currently selected kernel clock source
+    fn kernel_frequency(rcc: &Rcc) -> Hertz;
+}
+
+impl Rcc {
+    /// Select the kernel clock source of peripheral `P`
+    pub fn set_kernel_clock<P: KernelClockSelect>(&mut self, source: P::Source) {
+        P::set_kernel_clock(self, source);
+    }
+}
+
+macro_rules! kernel_clock {
+    ($Source:ident: $($PER:ident: $ccipr:ident, $sel:ident;)+) => {$(
+        kernel_clock!(@select $Source: $PER: $ccipr, $sel;);
+
+        impl KernelClock for $PER {
+            fn kernel_frequency(rcc: &Rcc) -> Hertz {
+                kernel_clock!(@freq $Source, Self::kernel_clock(rcc), rcc)
+            }
+        }
+    )+};
+
+    (@select $Source:ident: $($PER:ident: $ccipr:ident, $sel:ident;)+) => {$(
+        impl KernelClockSelect for $PER {
+            type Source = $Source;
+
+            // `bits` is only unsafe for fields with reserved values
+            #[allow(unused_unsafe)]
+            fn set_kernel_clock(rcc: &mut Rcc, source: $Source) {
+                kernel_clock!(@enable $Source, source, rcc);
+                // SAFETY: source only holds valid selections for this field
+                rcc.rb.$ccipr.modify(|_, w| unsafe { w.$sel().bits(source as u8) });
+            }
+
+            fn kernel_clock(rcc: &Rcc) -> $Source {
+                kernel_clock!(@from_bits $Source, rcc.rb.$ccipr.read().$sel().bits())
+            }
+        }
+    )+};
+
+    (@enable AdcClockSource, $source:expr, $rcc:expr) => {};
+    (@enable FdcanClockSource, $source:expr, $rcc:expr) => {};
+    (@enable $Source:ident, $source:expr, $rcc:expr) => {
+        if $source == $Source::Hsi16 {
+            $rcc.enable_hsi();
+        }
+    };
+
+    (@from_bits I2cClockSource, $bits:expr) => {
+        match $bits {
+            0b01 => I2cClockSource::SysClk,
+            0b10 => I2cClockSource::Hsi16,
+            _ => I2cClockSource::Pclk,
+        }
+    };
+    (@from_bits UsartClockSource, $bits:expr) => {
+        match $bits {
+            0b01 => UsartClockSource::SysClk,
+            0b10 => UsartClockSource::Hsi16,
+            0b11 => UsartClockSource::Lse,
+            _ => UsartClockSource::Pclk,
+        }
+    };
+    (@from_bits AdcClockSource, $bits:expr) => {
+        match $bits {
+            0b01 => AdcClockSource::PllP,
+            0b10 => AdcClockSource::SysClk,
+            _ => AdcClockSource::NoClock,
+        }
+    };
+    (@from_bits FdcanClockSource, $bits:expr) => {
+        match $bits {
+            0b01 => FdcanClockSource::PllQ,
+            0b10 => FdcanClockSource::Pclk,
+            _ => FdcanClockSource::Hse,
+        }
+    };
+
+    (@freq I2cClockSource, $source:expr, $rcc:expr) => {
+        match $source {
+            I2cClockSource::Pclk => <Self as RccBus>::Bus::get_frequency(&$rcc.clocks),
+            I2cClockSource::SysClk => $rcc.clocks.sys_clk,
+            I2cClockSource::Hsi16 => HSI_FREQ.Hz(),
+        }
+    };
+    (@freq UsartClockSource, $source:expr, $rcc:expr) => {
+        match $source {
+            UsartClockSource::Pclk => <Self as RccBus>::Bus::get_frequency(&$rcc.clocks),
+            UsartClockSource::SysClk => $rcc.clocks.sys_clk,
+            UsartClockSource::Hsi16 => HSI_FREQ.Hz(),
+            UsartClockSource::Lse => LSE_FREQ.Hz(),
+        }
+    };
+}
+
+kernel_clock!(I2cClockSource:
+    I2C1: ccipr, i2c1sel;
+    I2C2: ccipr, i2c2sel;
+    I2C3: ccipr, i2c3sel;
+);
+
+#[cfg(any(
+    feature = "stm32g471",
+    feature = "stm32g473",
+    feature = "stm32g474",
+    feature = "stm32g483",
+    feature = "stm32g484"
+))]
+kernel_clock!(I2cClockSource:
+    I2C4: ccipr2, i2c4sel;
+);
+
+kernel_clock!(UsartClockSource:
+    USART1: ccipr, usart1sel;
+    USART2: ccipr, usart2sel;
+    USART3: ccipr, usart3sel;
+    UART4: ccipr, uart4sel;
+    LPUART1: ccipr, lpuart1sel;
+);
+
+#[cfg(any(
+    feature = "stm32g471",
+    feature = "stm32g473",
+    feature = "stm32g474",
+    feature = "stm32g483",
+    feature = "stm32g484"
+))]
+kernel_clock!(UsartClockSource:
+    UART5: ccipr, uart5sel;
+);
+
+kernel_clock!(@select AdcClockSource:
+    ADC1: ccipr, adc12sel;
+    ADC2: ccipr, adc12sel;
+);
+
+#[cfg(any(
+    feature = "stm32g471",
+    feature = "stm32g473",
+    feature = "stm32g474",
+    feature = "stm32g483",
+    feature = "stm32g484"
+))]
+kernel_clock!(@select AdcClockSource:
+    ADC3: ccipr, adc345sel;
+);
+
+#[cfg(any(
+    feature = "stm32g473",
+    feature = "stm32g474",
+    feature = "stm32g483",
+    feature = "stm32g484"
+))]
+kernel_clock!(@select AdcClockSource:
+    ADC4: ccipr, adc345sel;
+    ADC5: ccipr, adc345sel;
+);
+
+kernel_clock!(@select FdcanClockSource:
+    FDCAN1: ccipr, fdcansel;
+);
+
+#[cfg(any(
+    feature = "stm32g471",
+    feature = "stm32g473",
+    feature = "stm32g474",
+    feature = "stm32g483",
+    feature = "stm32g484"
+))]
+kernel_clock!(@select FdcanClockSource:
+    FDCAN2: ccipr, fdcansel;
+);
+
+#[cfg(any(
+    feature = "stm32g473",
+    feature = "stm32g474",
+    feature = "stm32g483",
+    feature = "stm32g484"
+))]
+kernel_clock!(@select FdcanClockSource:
+    FDCAN3: ccipr, fdcansel;
+);
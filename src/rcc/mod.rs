@@ -1,19 +1,29 @@
-use crate::pwr::{self, PowerConfiguration};
+use crate::pwr::{self, PowerConfiguration, StopMode};
 use crate::stm32::{rcc, FLASH, PWR, RCC};
 use crate::time::{Hertz, RateExtU32};
+use cortex_m::peripheral::SCB;
+use fugit::HertzU64;
 
 mod clockout;
 mod config;
 mod enable;
+mod kernel_clock;
 
 pub use clockout::*;
 pub use config::*;
+pub use kernel_clock::*;
 
 pub trait Instance: crate::Sealed + Enable + Reset + GetBusFreq {}
 
 /// HSI speed
 pub const HSI_FREQ: u32 = 16_000_000;
 
+/// LSI speed (typical, the LSI is not trimmed)
+pub const LSI_FREQ: u32 = 32_000;
+
+/// LSE speed, assumes a 32.768kHz crystal
+pub const LSE_FREQ: u32 = 32_768;
+
 /// Clock frequencies
 #[derive(Clone, Copy, Debug)]
 pub struct Clocks {
@@ -31,6 +41,12 @@ pub struct Clocks {
     pub apb2_clk: Hertz,
     /// APB 2 timers frequency (Timers 1, 8, 20, 15, 16, 17 and HRTIM1)
     pub apb2_tim_clk: Hertz,
+    /// HRTIM high resolution frequency
+    ///
+    /// The HRTIM has no clock source selection, it is always fed by the APB 2 timers clock
+    /// which its DLL multiplies by 32. This is the tick rate of an HRTIM timer with prescaler
+    /// `Pscl1`, e.g. 150MHz * 32 = 4.8GHz. Too high for a `Hertz`, hence the `HertzU64`.
+    pub hrtim_clk: HertzU64,
     /// PLL frequency
     pub pll_clk: PLLClocks,
 }
@@ -57,6 +73,7 @@ impl Default for Clocks {
             apb1_tim_clk: freq,
             apb2_clk: freq,
             apb2_tim_clk: freq,
+            hrtim_clk: HertzU64::from(freq) * 32,
             pll_clk: PLLClocks {
                 r: None,
                 q: None,
@@ -227,10 +244,72 @@ impl Rcc {
                 apb1_tim_clk: apb1_tim_clk.Hz(),
                 apb2_clk: apb2_freq.Hz(),
                 apb2_tim_clk: apb2_tim_clk.Hz(),
+                hrtim_clk: HertzU64::Hz(apb2_tim_clk as u64) * 32,
             },
         }
     }
 
+    /// Enter `mode` and sleep until woken up by an EXTI event or interrupt
+    ///
+    /// The HSE and PLL are stopped in Stop mode and the MCU wakes up running from HSI16. Before
+    /// returning, the clocks are restored to what they were before entering Stop mode so
+    /// `self.clocks` stays valid. Note that interrupt handlers that run as part of the wakeup
+    /// execute before the clocks are restored.
+    pub fn enter_stop(&mut self, scb: &mut SCB, mode: StopMode) {
+        let cr = self.rb.cr.read();
+        let hse_bypass = cr.hsebyp().bit_is_set();
+        let hse_on = cr.hseon().bit_is_set();
+        let pll_on = cr.pllon().bit_is_set();
+        let sw_bits = self.rb.cfgr.read().sws().bits();
+
+        self.rb.apb1enr1.modify(|_, w| w.pwren().set_bit());
+        let pwr = unsafe { &(*PWR::ptr()) };
+        pwr.cr1.modify(|_, w| unsafe { w.lpms().bits(mode as u8) });
+
+        scb.set_sleepdeep();
+        cortex_m::asm::dsb();
+        cortex_m::asm::wfi();
+        scb.clear_sleepdeep();
+
+        if hse_on {
+            self.enable_hse(hse_bypass);
+        }
+        if pll_on {
+            // The PLL configuration is retained in Stop mode
+            self.rb.cr.modify(|_, w| w.pllon().set_bit());
+            while self.rb.cr.read().pllrdy().bit_is_clear() {}
+        }
+        self.restore_sysclk(sw_bits);
+    }
+
+    /// Switch back to the system clock source `sw_bits` after waking up from Stop mode
+    // `bits` is only unsafe for fields with reserved values
+    #[allow(unused_unsafe)]
+    fn restore_sysclk(&mut self, sw_bits: u8) {
+        let ahb_psc_bits = self.rb.cfgr.read().hpre().bits();
+
+        // (From RM0440 chapter "Power control (PWR)")
+        // When switching to a system clock above 80MHz in Range1 boost mode, the AHB clock has to
+        // be divided by 2 during the switch and for at least 1us after it
+        let sys_freq = self.clocks.sys_clk.raw();
+        let boost = sys_freq > 80_000_000 && ahb_psc_bits < 0b1000;
+        if boost {
+            self.rb.cfgr.modify(|_, w| unsafe { w.hpre().bits(0b1000) });
+        }
+
+        // SAFETY: sw_bits was read from SWS before entering Stop mode
+        self.rb.cfgr.modify(|_, w| unsafe { w.sw().bits(sw_bits) });
+        while self.rb.cfgr.read().sws().bits() != sw_bits {}
+
+        if boost {
+            let us_per_s = 1_000_000;
+            cortex_m::asm::delay((sys_freq + us_per_s - 1) / us_per_s);
+            self.rb
+                .cfgr
+                .modify(|_, w| unsafe { w.hpre().bits(ahb_psc_bits) });
+        }
+    }
+
     pub fn unlock_rtc(&mut self) {
         self.rb.apb1enr1.modify(|_, w| w.pwren().set_bit());
         let pwr = unsafe { &(*PWR::ptr()) };
@@ -344,7 +423,7 @@ impl Rcc {
                 90_000_001..=120_000_000 => 0b0011,
                 120_000_001..=150_000_000 => 0b0100,
                 150_000_001.. => panic!(
-                    "Too high f_sys: {}, max with voltage scale in 'range1 normal mode' is: 150MHz",
+                    "Too high f_sys: {}, max with voltage scale in 'range1 normal mode' is: 150MHz, see `Pwr::vos_boost`",
                     sys_freq
                 ),
             },
@@ -428,18 +507,69 @@ impl Rcc {
         while self.rb.cr.read().hserdy().bit_is_clear() {}
     }
 
-    pub(crate) fn enable_lse(&self, bypass: bool) {
+    /// Enable the LSE and wait for it to be ready, `bypass` for an external clock instead of
+    /// a crystal
+    ///
+    /// NOTE: The LSE is in the backup domain, so `unlock_rtc` has to be called first unless
+    /// the backup domain has already been unlocked
+    pub fn enable_lse(&self, bypass: bool) {
         self.rb
             .bdcr
             .modify(|_, w| w.lseon().set_bit().lsebyp().bit(bypass));
         while self.rb.bdcr.read().lserdy().bit_is_clear() {}
     }
 
+    /// Returns `true` if the LSE is enabled and stable
+    pub fn lse_ready(&self) -> bool {
+        self.rb.bdcr.read().lserdy().bit_is_set()
+    }
+
+    /// Select and enable the RTC clock, returns its frequency
+    ///
+    /// Changing the RTC clock once selected requires a backup domain reset, which is done here
+    /// if needed. This also resets the RTC and the LSE configuration.
+    // `bits` is only unsafe for fields with reserved values
+    #[allow(unused_unsafe)]
+    pub fn set_rtc_clock(&mut self, src: RtcClockSource) -> Hertz {
+        self.unlock_rtc();
+
+        let (bits, freq) = match src {
+            RtcClockSource::LSE => (0b01, LSE_FREQ.Hz()),
+            RtcClockSource::LSE_BYPASS => (0b01, LSE_FREQ.Hz()),
+            RtcClockSource::LSI => (0b10, LSI_FREQ.Hz()),
+            RtcClockSource::HSE_DIV32(hse) => (0b11, hse / 32),
+        };
+
+        let current = self.rb.bdcr.read().rtcsel().bits();
+        if current != 0 && current != bits {
+            self.rb.bdcr.modify(|_, w| w.bdrst().set_bit());
+            self.rb.bdcr.modify(|_, w| w.bdrst().clear_bit());
+        }
+
+        match src {
+            RtcClockSource::LSE => self.enable_lse(false),
+            RtcClockSource::LSE_BYPASS => self.enable_lse(true),
+            RtcClockSource::LSI => self.enable_lsi(),
+            RtcClockSource::HSE_DIV32(_) => {}
+        }
+
+        // SAFETY: bits is one of the clock sources above
+        self.rb
+            .bdcr
+            .modify(|_, w| unsafe { w.rtcsel().bits(bits) }.rtcen().set_bit());
+
+        freq
+    }
+
     pub(crate) fn enable_lsi(&self) {
         self.rb.csr.modify(|_, w| w.lsion().set_bit());
         while self.rb.csr.read().lsirdy().bit_is_clear() {}
     }
 
+    /// Read the reset flags in RCC_CSR
+    ///
+    /// The flags are sticky, several may be set if `clear_reset_reason` has not been called
+    /// since an earlier reset.
     pub fn get_reset_reason(&self) -> ResetReason {
         let csr = self.rb.csr.read();
 
@@ -454,11 +584,15 @@ impl Rcc {
         }
     }
 
+    /// Clear all reset flags (RMVF)
     pub fn clear_reset_reason(&mut self) {
         self.rb.csr.modify(|_, w| w.rmvf().set_bit());
     }
 }
 
+/// Reset flags, see `Rcc::get_reset_reason`
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct ResetReason {
     /// Low-power reset flag
     ///
@@ -496,6 +630,13 @@ pub struct ResetReason {
     pub option_byte: bool,
 }
 
+impl ResetReason {
+    /// Returns `true` if the independent or the window watchdog caused a reset
+    pub fn watchdog(&self) -> bool {
+        self.independent_watchdog || self.window_watchdog
+    }
+}
+
 /// Extension trait that constrains the `RCC` peripheral
 pub trait RccExt {
     /// Constrains the `RCC` peripheral so it plays nicely with the other abstractions
@@ -654,6 +795,14 @@ pub trait Reset: RccBus {
     fn reset(rcc: &RccRB);
 }
 
+impl Rcc {
+    /// Enable the bus clock of peripheral `P` and then reset it
+    pub fn enable_and_reset<P: Enable + Reset>(&mut self) {
+        P::enable(&self.rb);
+        P::reset(&self.rb);
+    }
+}
+
 pub trait GetBusFreq {
     fn get_frequency(clocks: &Clocks) -> Hertz;
     fn get_timer_frequency(clocks: &Clocks) -> Hertz {
@@ -7,7 +7,7 @@ use crate::dma::{
 use crate::gpio::{gpioa::*, gpiob::*, gpioc::*, gpiod::*, gpioe::*, gpiog::*};
 use crate::gpio::{Alternate, AlternateOD, AF12, AF5, AF7, AF8};
 use crate::prelude::*;
-use crate::rcc::{Enable, GetBusFreq, Rcc, RccBus, Reset};
+use crate::rcc::{Enable, KernelClock, Rcc};
 use crate::stm32::*;
 
 use cortex_m::interrupt;
@@ -447,17 +447,12 @@ macro_rules! uart_lp {
                 rcc: &mut Rcc,
             ) -> Result<Self, InvalidConfig> {
                 // Enable clock for USART
-                unsafe {
-                    let rcc_ptr = &(*RCC::ptr());
-                    $USARTX::enable(rcc_ptr);
-                    $USARTX::reset(rcc_ptr);
-                }
+                rcc.enable_and_reset::<$USARTX>();
 
-                // TODO: By default, all UARTs are clocked from PCLK. We could modify RCC_CCIPR to
-                // try SYSCLK if PCLK is not high enough. We could also select 8x oversampling
-                // instead of 16x.
+                // TODO: We could also select 8x oversampling instead of 16x.
 
-                let clk = <$USARTX as RccBus>::Bus::get_frequency(&rcc.clocks).raw() as u64;
+                // The kernel clock defaults to PCLK, see `Rcc::set_kernel_clock`
+                let clk = <$USARTX as KernelClock>::kernel_frequency(rcc).raw() as u64;
                 let bdr = config.baudrate.0 as u64;
                 let div = ($clk_mul * clk) / bdr;
                 if div < 16 {
@@ -594,17 +589,12 @@ macro_rules! uart_full {
                 rcc: &mut Rcc,
             ) -> Result<Self, InvalidConfig> {
                 // Enable clock for USART
-                unsafe {
-                    let rcc_ptr = &(*RCC::ptr());
-                    $USARTX::enable(rcc_ptr);
-                    $USARTX::reset(rcc_ptr);
-                }
+                rcc.enable_and_reset::<$USARTX>();
 
-                // TODO: By default, all UARTs are clocked from PCLK. We could modify RCC_CCIPR to
-                // try SYSCLK if PCLK is not high enough. We could also select 8x oversampling
-                // instead of 16x.
+                // TODO: We could also select 8x oversampling instead of 16x.
 
-                let clk = <$USARTX as RccBus>::Bus::get_frequency(&rcc.clocks).raw() as u64;
+                // The kernel clock defaults to PCLK, see `Rcc::set_kernel_clock`
+                let clk = <$USARTX as KernelClock>::kernel_frequency(rcc).raw() as u64;
                 let bdr = config.baudrate.0 as u64;
                 let clk_mul = 1;
                 let div = (clk_mul * clk) / bdr;
@@ -1,6 +1,6 @@
 use crate::dma::mux::DmaMuxResources;
 use crate::dma::traits::TargetAddress;
-use crate::dma::MemoryToPeripheral;
+use crate::dma::{MemoryToPeripheral, PeripheralToMemory};
 use crate::gpio::{gpioa::*, gpiob::*, gpioc::*, gpiof::*, Alternate, AF5, AF6};
 #[cfg(any(
     feature = "stm32g471",
@@ -10,7 +10,7 @@ use crate::gpio::{gpioa::*, gpiob::*, gpioc::*, gpiof::*, Alternate, AF5, AF6};
     feature = "stm32g484"
 ))]
 use crate::gpio::{gpioe::*, gpiog::*};
-use crate::rcc::{Enable, GetBusFreq, Rcc, RccBus, Reset};
+use crate::rcc::{GetBusFreq, Rcc, RccBus};
 #[cfg(any(
     feature = "stm32g471",
     feature = "stm32g473",
@@ -19,7 +19,7 @@ use crate::rcc::{Enable, GetBusFreq, Rcc, RccBus, Reset};
     feature = "stm32g484"
 ))]
 use crate::stm32::SPI4;
-use crate::stm32::{RCC, SPI1, SPI2, SPI3};
+use crate::stm32::{SPI1, SPI2, SPI3};
 use crate::time::Hertz;
 use core::cell::UnsafeCell;
 use core::ptr;
@@ -79,6 +79,7 @@ macro_rules! spi {
         miso: [ $($( #[ $pmetamiso:meta ] )* $MISO:ty,)+ ],
         mosi: [ $($( #[ $pmetamosi:meta ] )* $MOSI:ty,)+ ],
         $mux:expr,
+        $mux_rx:expr,
     ) => {
         impl PinSck<$SPIX> for NoSck {}
 
@@ -111,11 +112,7 @@ macro_rules! spi {
             T: Into<Hertz>
             {
                  // Enable and reset SPI
-                unsafe {
-                    let rcc_ptr = &(*RCC::ptr());
-                    $SPIX::enable(rcc_ptr);
-                    $SPIX::reset(rcc_ptr);
-                }
+                rcc.enable_and_reset::<$SPIX>();
 
                 // disable SS output
                 spi.cr2.write(|w| w.ssoe().clear_bit());
@@ -179,6 +176,17 @@ macro_rules! spi {
                     pins: self.pins,
                 }
             }
+
+            /// Request a DMA transfer for every received byte
+            ///
+            /// Note that in master mode the bus is only clocked while data is being sent
+            pub fn enable_rx_dma(self) -> Spi<$SPIX, PINS> {
+                self.spi.cr2.modify(|_, w| w.rxdmaen().set_bit());
+                Spi {
+                    spi: self.spi,
+                    pins: self.pins,
+                }
+            }
         }
 
         impl SpiExt<$SPIX> for $SPIX {
@@ -245,6 +253,17 @@ macro_rules! spi {
             const REQUEST_LINE: Option<u8> = Some($mux as u8);
         }
 
+        unsafe impl<Pin> TargetAddress<PeripheralToMemory> for Spi<$SPIX, Pin> {
+            #[inline(always)]
+            fn address(&self) -> u32 {
+                &unsafe { &*<$SPIX>::ptr() }.dr as *const _ as u32
+            }
+
+            type MemSize = u8;
+
+            const REQUEST_LINE: Option<u8> = Some($mux_rx as u8);
+        }
+
 
         impl<PINS> ::hal::blocking::spi::transfer::Default<u8> for Spi<$SPIX, PINS> {}
 
@@ -292,6 +311,7 @@ spi!(
         PG4<Alternate<AF5>>,
     ],
     DmaMuxResources::SPI1_TX,
+    DmaMuxResources::SPI1_RX,
 );
 
 spi!(
@@ -312,6 +332,7 @@ spi!(
         PB15<Alternate<AF5>>,
     ],
     DmaMuxResources::SPI2_TX,
+    DmaMuxResources::SPI2_RX,
 );
 
 spi!(
@@ -338,6 +359,7 @@ spi!(
         PC12<Alternate<AF6>>,
     ],
     DmaMuxResources::SPI3_TX,
+    DmaMuxResources::SPI3_RX,
 );
 
 #[cfg(any(
@@ -363,4 +385,5 @@ spi!(
         PE14<Alternate<AF5>>,
     ],
     DmaMuxResources::SPI4_TX,
+    DmaMuxResources::SPI4_RX,
 );
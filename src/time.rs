@@ -2,8 +2,9 @@
 /// STM32G4xx MCUs.
 pub use fugit::{
     Duration, ExtU32, HertzU32 as Hertz, HoursDurationU32 as Hour,
-    MicrosDurationU32 as MicroSecond, MinutesDurationU32 as Minute, NanosDurationU32 as NanoSecond,
-    RateExtU32, SecsDurationU32 as Second,
+    MicrosDurationU32 as MicroSecond, MillisDurationU32 as MilliSecond,
+    MinutesDurationU32 as Minute, NanosDurationU32 as NanoSecond, RateExtU32,
+    SecsDurationU32 as Second,
 };
 
 /// Baudrate
@@ -13,6 +14,8 @@ pub struct Bps(pub u32);
 /// A measurement of a monotonically nondecreasing clock
 pub type Instant = fugit::TimerInstantU32<1_000_000>;
 
+pub use crate::timer::Monotonic;
+
 /// WeekDay (1-7)
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct WeekDay(pub u32);
@@ -204,6 +204,16 @@ impl Instant {
     }
 }
 
+/// A free running microsecond counter backed by a 32 bit general purpose timer (TIM2 or TIM5)
+///
+/// Unlike [`MonoTimer`] this keeps counting while the core is halted, unless the timer is
+/// stopped using [`DbgMcu::stop_on_debug`](crate::dbgmcu::DbgMcu::stop_on_debug). The counter
+/// wraps after 2^32 µs (~71.6 minutes), the same as [`time::Instant`](crate::time::Instant), so
+/// durations between two instants less than that apart are correct across the overflow.
+pub struct Monotonic<TIM> {
+    tim: TIM,
+}
+
 pub trait Instance: crate::Sealed + rcc::Enable + rcc::Reset + rcc::GetBusFreq {}
 
 impl<TIM> Timer<TIM>
@@ -227,6 +237,49 @@ where
     }
 }
 
+macro_rules! monotonic {
+    ($($TIM:ty,)+) => {
+        $(
+            impl Monotonic<$TIM> {
+                /// Starts the timer as a free running microsecond counter
+                ///
+                /// The timer clock has to be a whole number of MHz.
+                pub fn new(tim: $TIM, clocks: &Clocks) -> Self {
+                    let Timer { tim, clk } = Timer::new(tim, clocks);
+
+                    assert!(clk.raw() % 1_000_000 == 0);
+                    let psc = u16(clk.raw() / 1_000_000 - 1).unwrap();
+
+                    tim.cr1.modify(|_, w| w.cen().clear_bit());
+                    tim.psc.write(|w| unsafe { w.psc().bits(psc) });
+                    tim.arr.write(|w| unsafe { w.bits(u32::MAX) });
+
+                    // Trigger update event to load the prescaler, without raising the update flag
+                    tim.cr1.modify(|_, w| w.urs().set_bit());
+                    tim.egr.write(|w| w.ug().set_bit());
+                    tim.cr1.modify(|_, w| w.urs().clear_bit());
+
+                    tim.cnt.reset();
+                    tim.cr1.modify(|_, w| w.cen().set_bit());
+
+                    Monotonic { tim }
+                }
+
+                /// Returns an `Instant` corresponding to "now"
+                pub fn now(&self) -> crate::time::Instant {
+                    crate::time::Instant::from_ticks(self.tim.cnt.read().bits())
+                }
+
+                /// Releases the TIM peripheral
+                pub fn release(self) -> $TIM {
+                    self.tim.cr1.modify(|_, w| w.cen().clear_bit());
+                    self.tim
+                }
+            }
+        )+
+    }
+}
+
 macro_rules! hal_ext_trgo {
     ($($TIM:ty: ($tim:ident, $mms:ident),)+) => {
         $(
@@ -370,6 +423,10 @@ hal! {
     crate::stm32::TIM17: (tim17),
 }
 
+monotonic! {
+    crate::stm32::TIM2,
+}
+
 hal_ext_trgo! {
     crate::stm32::TIM1: (tim1, mms2),
     crate::stm32::TIM2: (tim2, mms2),
@@ -393,6 +450,17 @@ hal! {
     crate::stm32::TIM5: (tim5),
 }
 
+#[cfg(any(
+    feature = "stm32g471",
+    feature = "stm32g473",
+    feature = "stm32g474",
+    feature = "stm32g483",
+    feature = "stm32g484"
+))]
+monotonic! {
+    crate::stm32::TIM5,
+}
+
 #[cfg(any(
     feature = "stm32g473",
     feature = "stm32g474",